@@ -1,8 +1,11 @@
 use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::config::Config;
 use crate::model::Repo;
 
 /// Cache entry with timestamp
@@ -12,10 +15,31 @@ struct CacheEntry {
     repos: Vec<Repo>,
 }
 
+/// Timestamped non-repo value stored under `meta/` (e.g. the instance list)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MetaEntry<T> {
+    timestamp: u64,
+    value: T,
+}
+
+/// Summary of a single provider's cache file
+#[derive(Debug, Clone)]
+pub struct CacheInfo {
+    pub provider: String,
+    pub path: PathBuf,
+    pub timestamp: u64,
+    pub age_secs: u64,
+    pub valid: bool,
+    pub repo_count: usize,
+}
+
 /// Filesystem-based cache with TTL support
+#[allow(clippy::struct_field_names)]
 pub struct Cache {
     cache_dir: PathBuf,
     ttl_secs: u64,
+    /// TTL overrides by provider kind (`github`, not `github-weekly`)
+    provider_ttl_secs: BTreeMap<String, u64>,
 }
 
 impl Cache {
@@ -24,20 +48,76 @@ impl Cache {
         let cache_dir = dirs::cache_dir()
             .context("Failed to determine cache directory")?
             .join("trotd");
+        Self::check_dir(&cache_dir)?;
 
         Ok(Self {
             cache_dir,
             ttl_secs: ttl_mins * 60,
+            provider_ttl_secs: BTreeMap::new(),
         })
     }
 
+    /// Create a cache using the config's default and per-provider TTLs
+    pub fn for_config(config: &Config) -> Result<Self> {
+        let mut cache = Self::new(config.general.cache_ttl_mins)?;
+        for provider in [
+            "github",
+            "gitlab",
+            "gitea",
+            "codeberg",
+            "bitbucket",
+            "crates",
+        ] {
+            cache = cache.with_provider_ttl(provider, config.get_cache_ttl_mins(provider));
+        }
+        Ok(cache)
+    }
+
+    /// Override the TTL for one provider kind, including its instances and windows
+    pub fn with_provider_ttl(mut self, provider: &str, ttl_mins: u64) -> Self {
+        self.provider_ttl_secs
+            .insert(provider.to_string(), ttl_mins * 60);
+        self
+    }
+
     /// Create a cache instance with a custom directory (for testing)
     #[cfg(test)]
-    fn with_dir(cache_dir: PathBuf, ttl_mins: u64) -> Self {
+    pub(crate) fn with_dir(cache_dir: PathBuf, ttl_mins: u64) -> Self {
         Self {
             cache_dir,
             ttl_secs: ttl_mins * 60,
+            provider_ttl_secs: BTreeMap::new(),
+        }
+    }
+
+    /// Fail with an actionable error when the cache path exists but isn't a directory
+    fn check_dir(cache_dir: &Path) -> Result<()> {
+        if cache_dir.exists() && !cache_dir.is_dir() {
+            anyhow::bail!(
+                "cache path exists but is not a directory: {} (remove or rename it)",
+                cache_dir.display()
+            );
         }
+        Ok(())
+    }
+
+    /// Directory holding the cache files
+    pub fn dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Time-to-live for provider entries, in minutes
+    pub fn ttl_mins(&self) -> u64 {
+        self.ttl_secs / 60
+    }
+
+    /// TTL in seconds for a cache key such as `github`, `github-weekly`, or `gitea@host`
+    fn ttl_secs_for(&self, provider: &str) -> u64 {
+        let kind = provider.split(['@', '-']).next().unwrap_or(provider);
+        self.provider_ttl_secs
+            .get(kind)
+            .copied()
+            .unwrap_or(self.ttl_secs)
     }
 
     /// Get cache file path for a provider
@@ -66,16 +146,42 @@ impl Cache {
 
         // Check if cache is still valid
         let age = Self::now().saturating_sub(entry.timestamp);
-        if age > self.ttl_secs {
+        if age > self.ttl_secs_for(provider) {
             return None;
         }
 
         Some(entry.repos)
     }
 
+    /// Load cached repositories regardless of TTL, with their age in seconds
+    ///
+    /// Used as a fallback when the live fetch fails, and in offline mode.
+    pub async fn get_stale(&self, provider: &str) -> Option<(Vec<Repo>, u64)> {
+        let content = tokio::fs::read_to_string(self.cache_file(provider))
+            .await
+            .ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        let age = Self::now().saturating_sub(entry.timestamp);
+        Some((entry.repos, age))
+    }
+
+    /// Total stars per repo name from the last saved entry, whatever its age
+    ///
+    /// Read before overwriting the entry with fresh data to compute star deltas.
+    pub async fn get_previous(&self, provider: &str) -> Option<HashMap<String, u64>> {
+        let (repos, _) = self.get_stale(provider).await?;
+        Some(
+            repos
+                .into_iter()
+                .filter_map(|repo| repo.stars_total.map(|stars| (repo.name, stars)))
+                .collect(),
+        )
+    }
+
     /// Save repositories to cache for a provider
     pub async fn set(&self, provider: &str, repos: Vec<Repo>) -> Result<()> {
         // Ensure cache directory exists
+        Self::check_dir(&self.cache_dir)?;
         tokio::fs::create_dir_all(&self.cache_dir)
             .await
             .with_context(|| {
@@ -101,20 +207,111 @@ impl Cache {
         Ok(())
     }
 
-    /// Clear cache for a specific provider
-    #[allow(dead_code)]
-    pub async fn clear(&self, provider: &str) -> Result<()> {
-        let cache_file = self.cache_file(provider);
-        if cache_file.exists() {
-            tokio::fs::remove_file(&cache_file).await.with_context(|| {
-                format!("Failed to remove cache file: {}", cache_file.display())
-            })?;
+    /// Get cache file path for a non-repo value (kept out of `list_entries`)
+    fn meta_file(&self, name: &str) -> PathBuf {
+        self.cache_dir.join("meta").join(format!("{name}.json"))
+    }
+
+    /// Load a cached non-repo value if it's younger than `ttl_mins`
+    pub async fn get_meta<T: DeserializeOwned>(&self, name: &str, ttl_mins: u64) -> Option<T> {
+        let content = tokio::fs::read_to_string(self.meta_file(name)).await.ok()?;
+        let entry: MetaEntry<T> = serde_json::from_str(&content).ok()?;
+
+        let age = Self::now().saturating_sub(entry.timestamp);
+        if age > ttl_mins.saturating_mul(60) {
+            return None;
         }
+
+        Some(entry.value)
+    }
+
+    /// Save a non-repo value with the current timestamp
+    pub async fn set_meta<T: Serialize>(&self, name: &str, value: &T) -> Result<()> {
+        Self::check_dir(&self.cache_dir)?;
+        let meta_file = self.meta_file(name);
+        if let Some(dir) = meta_file.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        }
+
+        let entry = MetaEntry {
+            timestamp: Self::now(),
+            value,
+        };
+        let content =
+            serde_json::to_string_pretty(&entry).context("Failed to serialize cache entry")?;
+
+        tokio::fs::write(&meta_file, content)
+            .await
+            .with_context(|| format!("Failed to write cache file: {}", meta_file.display()))?;
+
         Ok(())
     }
 
+    /// List all cache entries with their age and TTL status, sorted by provider
+    pub async fn list_entries(&self) -> Vec<CacheInfo> {
+        let mut entries = Vec::new();
+
+        let Ok(mut dir) = tokio::fs::read_dir(&self.cache_dir).await else {
+            return entries;
+        };
+
+        while let Ok(Some(file)) = dir.next_entry().await {
+            let path = file.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(provider) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            // Skip files that aren't valid cache entries
+            let Ok(content) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+            let Ok(entry) = serde_json::from_str::<CacheEntry>(&content) else {
+                continue;
+            };
+
+            let age_secs = Self::now().saturating_sub(entry.timestamp);
+            entries.push(CacheInfo {
+                provider: provider.to_string(),
+                path: path.clone(),
+                timestamp: entry.timestamp,
+                age_secs,
+                valid: age_secs <= self.ttl_secs_for(provider),
+                repo_count: entry.repos.len(),
+            });
+        }
+
+        entries.sort_by(|a, b| a.provider.cmp(&b.provider));
+        entries
+    }
+
+    /// Clear cache for a specific provider, returning how many entries existed
+    ///
+    /// Also removes the provider's variants: other trending windows, spoken
+    /// languages and filters (`github-weekly`) and instances (`gitea@host`).
+    pub async fn clear(&self, provider: &str) -> Result<usize> {
+        let mut removed = 0;
+        for entry in self.list_entries().await {
+            let matches = entry
+                .provider
+                .strip_prefix(provider)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '@']));
+            if !matches {
+                continue;
+            }
+            tokio::fs::remove_file(&entry.path).await.with_context(|| {
+                format!("Failed to remove cache file: {}", entry.path.display())
+            })?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
     /// Clear all cached data
-    #[allow(dead_code)]
     pub async fn clear_all(&self) -> Result<()> {
         if self.cache_dir.exists() {
             tokio::fs::remove_dir_all(&self.cache_dir)
@@ -151,6 +348,7 @@ mod tests {
             stars_total: Some(100),
             last_activity: Some(chrono::Utc::now()),
             topics: vec!["rust".to_string(), "cli".to_string()],
+            ..Default::default()
         }];
 
         // Clear any existing cache
@@ -170,8 +368,104 @@ mod tests {
         assert_eq!(cached.len(), 1);
         assert_eq!(cached[0].name, "test/repo");
 
+        // Cleanup reports whether there was anything to remove
+        assert_eq!(cache.clear("test-provider").await.unwrap(), 1);
+        assert_eq!(cache.clear("test-provider").await.unwrap(), 0);
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_cache_path_is_file() {
+        let path = std::env::temp_dir().join(format!("trotd-test-file-{}", Cache::now()));
+        std::fs::write(&path, "not a directory").unwrap();
+
+        let err = Cache::check_dir(&path).unwrap_err();
+        assert!(err.to_string().contains("not a directory"));
+
+        let cache = Cache::with_dir(path.clone(), 60);
+        let err = cache.set("github", vec![]).await.unwrap_err();
+        assert!(err.to_string().contains("not a directory"));
+        assert!(cache.get("github").await.is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_meta_roundtrip() {
+        let temp_dir = std::env::temp_dir().join(format!("trotd-test-meta-{}", Cache::now()));
+        let cache = Cache::with_dir(temp_dir.clone(), 60);
+
+        assert!(cache
+            .get_meta::<Vec<String>>("instances", 60)
+            .await
+            .is_none());
+
+        let value = vec!["https://codeberg.org".to_string()];
+        cache.set_meta("instances", &value).await.unwrap();
+        assert_eq!(
+            cache.get_meta::<Vec<String>>("instances", 60).await,
+            Some(value)
+        );
+
+        // Meta values don't show up as provider cache entries
+        assert!(cache.list_entries().await.is_empty());
+
+        let _ = tokio::fs::remove_dir_all(&temp_dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_list_entries() {
+        let temp_dir = std::env::temp_dir().join(format!("trotd-test-list-{}", Cache::now()));
+        let cache = Cache::with_dir(temp_dir.clone(), 60);
+
+        let repo = Repo {
+            provider: "gitlab".to_string(),
+            icon: "[GL]".to_string(),
+            name: "test/repo".to_string(),
+            url: "https://gitlab.com/test/repo".to_string(),
+            stars_total: Some(10),
+            ..Default::default()
+        };
+
+        // Missing directory yields no entries
+        assert!(cache.list_entries().await.is_empty());
+
+        cache
+            .set("gitlab", vec![repo.clone(), repo.clone()])
+            .await
+            .unwrap();
+        cache.set("github", vec![repo]).await.unwrap();
+        // Unrelated and malformed files are ignored
+        std::fs::write(temp_dir.join("notes.txt"), "hello").unwrap();
+        std::fs::write(temp_dir.join("broken.json"), "{").unwrap();
+
+        let entries = cache.list_entries().await;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].provider, "github");
+        assert_eq!(entries[0].repo_count, 1);
+        assert_eq!(entries[1].provider, "gitlab");
+        assert_eq!(entries[1].repo_count, 2);
+        assert!(entries.iter().all(|e| e.valid));
+
+        // Clearing a provider takes its windows and instances along
+        for key in [
+            "github-weekly",
+            "github@ghe.example.com",
+            "gitlab-fdeadbeef",
+            "githubby",
+        ] {
+            cache.set(key, vec![]).await.unwrap();
+        }
+        assert_eq!(cache.clear("github").await.unwrap(), 3);
+        let left: Vec<String> = cache
+            .list_entries()
+            .await
+            .into_iter()
+            .map(|e| e.provider)
+            .collect();
+        assert_eq!(left, vec!["githubby", "gitlab", "gitlab-fdeadbeef"]);
+
         // Cleanup
-        cache.clear("test-provider").await.unwrap();
         let _ = std::fs::remove_dir_all(&temp_dir);
     }
 
@@ -187,12 +481,10 @@ mod tests {
             icon: "[GH]".to_string(),
             name: "test/repo".to_string(),
             language: Some("Rust".to_string()),
-            description: None,
             url: "https://github.com/test/repo".to_string(),
-            stars_today: None,
             stars_total: Some(50),
             last_activity: Some(chrono::Utc::now()),
-            topics: vec![],
+            ..Default::default()
         }];
 
         // Clear any existing cache
@@ -206,8 +498,48 @@ mod tests {
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
         assert!(cache.get("test-expiry").await.is_none());
 
+        // Expired data is still available through the stale path
+        let (stale, age) = cache.get_stale("test-expiry").await.unwrap();
+        assert_eq!(stale.len(), 1);
+        assert!(age >= 1);
+
         // Cleanup
         let _ = cache.clear("test-expiry").await;
         let _ = std::fs::remove_dir_all(&temp_dir);
     }
+
+    #[tokio::test]
+    async fn test_cache_expiry_per_provider() {
+        let temp_dir = std::env::temp_dir().join(format!("trotd-test-ttl-{}", Cache::now()));
+        // GitHub expires immediately, everything else keeps the 60-minute default
+        let cache = Cache::with_dir(temp_dir.clone(), 60).with_provider_ttl("github", 0);
+
+        for key in ["github", "github-weekly", "gitlab", "gitlab@example.com"] {
+            cache.set(key, vec![]).await.unwrap();
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        assert!(cache.get("github").await.is_none());
+        assert!(cache.get("github-weekly").await.is_none());
+        assert!(cache.get("gitlab").await.is_some());
+        assert!(cache.get("gitlab@example.com").await.is_some());
+
+        let valid: Vec<(String, bool)> = cache
+            .list_entries()
+            .await
+            .into_iter()
+            .map(|e| (e.provider, e.valid))
+            .collect();
+        assert_eq!(
+            valid,
+            vec![
+                ("github".to_string(), false),
+                ("github-weekly".to_string(), false),
+                ("gitlab".to_string(), true),
+                ("gitlab@example.com".to_string(), true),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
 }
@@ -1,6 +1,11 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::model::{IconSet, Since, SortKey};
+use crate::script::Script;
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -15,9 +20,41 @@ pub struct Config {
     pub gitea: GiteaConfig,
     #[serde(default)]
     pub github: GitHubConfig,
+    #[serde(default)]
+    pub gitlab: GitLabConfig,
+    #[serde(default)]
+    pub codeberg: CodebergConfig,
+    #[serde(default)]
+    pub bitbucket: BitbucketConfig,
+    #[serde(default)]
+    pub crates: CratesConfig,
+    /// Self-hosted Gitea/GitLab instances, one `[[custom_provider]]` table each
+    #[serde(
+        default,
+        rename = "custom_provider",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub custom_providers: Vec<CustomProviderConfig>,
+    /// Per-provider icon and color overrides, keyed by provider id (`github`, `gitlab@company`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub icons: BTreeMap<String, IconOverride>,
+    /// Where each non-default value came from, keyed by dotted path (e.g. `general.sort`)
+    #[serde(skip)]
+    pub provenance: BTreeMap<String, Source>,
+}
+
+/// Source of an effective configuration value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Default,
+    File,
+    Env,
+    Cli,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct GeneralConfig {
     #[serde(default = "default_max_per_provider")]
     pub max_per_provider: usize,
@@ -27,25 +64,174 @@ pub struct GeneralConfig {
     pub gitlab_max_entries: Option<usize>,
     #[serde(default)]
     pub gitea_max_entries: Option<usize>,
+    /// Overall cap across providers, applied after filtering and sorting
+    #[serde(default)]
+    pub total_limit: Option<usize>,
     #[serde(default = "default_timeout_secs")]
     pub timeout_secs: u64,
+    /// Maximum providers and instances fetching at the same time
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
     #[serde(default = "default_cache_ttl_mins")]
     pub cache_ttl_mins: u64,
     #[serde(default)]
+    pub github_cache_ttl_mins: Option<u64>,
+    #[serde(default)]
+    pub gitlab_cache_ttl_mins: Option<u64>,
+    #[serde(default)]
+    pub gitea_cache_ttl_mins: Option<u64>,
+    #[serde(default)]
     pub language_filter: Vec<String>,
+    /// Never show these languages (case-insensitive), whatever `language_filter` says
+    #[serde(default)]
+    pub exclude_languages: Vec<String>,
     #[serde(default = "default_github_timeout_secs")]
     pub github_timeout_secs: u64,
     #[serde(default = "default_gitlab_timeout_secs")]
     pub gitlab_timeout_secs: u64,
     #[serde(default = "default_gitea_timeout_secs")]
     pub gitea_timeout_secs: u64,
+    /// Cap on establishing a connection, separate from the overall request timeout
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u64>,
+    /// Stop waiting after this many seconds of the run and show what arrived
+    #[serde(default)]
+    pub deadline_secs: Option<u64>,
+    /// Headers added to every request (e.g. for corporate proxies); `Authorization` is rejected
+    #[serde(default)]
+    pub extra_headers: BTreeMap<String, String>,
+    /// Shortcut for the script whitelist: only Latin and Common (plus `allowed_scripts`)
     #[serde(default)]
     pub ascii_only: bool,
+    /// Scripts allowed besides Latin and Common (e.g. `Han`); non-empty enables the filter
+    #[serde(default)]
+    pub allowed_scripts: Vec<String>,
+    /// Collapse the same repository listed by several providers
+    #[serde(default)]
+    pub dedupe: bool,
+    #[serde(default)]
+    pub min_stars: Option<u64>,
+    /// Per-provider star floors (fall back to `min_stars`)
+    #[serde(default)]
+    pub github_min_stars: Option<u64>,
+    #[serde(default)]
+    pub gitlab_min_stars: Option<u64>,
+    #[serde(default)]
+    pub gitea_min_stars: Option<u64>,
+    /// Regexes matched against `owner/name`; matching repos are dropped
+    #[serde(default)]
+    pub exclude_name_patterns: Vec<String>,
+    /// Drop repositories last active longer ago than this, e.g. `24h`, `7d`, `2w`
+    #[serde(default)]
+    pub min_activity: Option<String>,
+    /// Drop repositories with no known last activity
+    #[serde(default)]
+    pub require_activity: bool,
+    #[serde(default)]
+    pub strip_emoji: bool,
+    #[serde(default)]
+    pub star_separator: StarSeparator,
+    /// Abbreviate MOTD star counts (`90.0k`, `1.5M`); off, or a `star_separator`, prints exact counts
+    #[serde(default = "default_true")]
+    pub humanize_stars: bool,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: usize,
+    /// Base delay of the exponential retry backoff, in milliseconds
+    #[serde(default = "default_retry_base_ms")]
+    pub retry_base_ms: u64,
+    /// Global kill switch: `false` disables retries for every provider
+    #[serde(default = "default_true")]
+    pub retries_enabled: bool,
+    /// Allow per-repo enrichment requests (umbrella for enrichment options)
+    #[serde(default)]
+    pub enrich: bool,
+    /// Enrichment: keep only repos with a README (GitHub/Gitea)
     #[serde(default)]
-    pub min_stars: Option<u32>,
+    pub require_readme: bool,
+    #[serde(default)]
+    pub sort: Option<SortKey>,
+    #[serde(default)]
+    pub icons: IconSet,
+    /// Truncate MOTD descriptions to this many columns (default 45, 0 hides them)
+    #[serde(default)]
+    pub description_width: Option<usize>,
+    /// Dim whole rows as repos age
+    #[serde(default)]
+    pub dim_old: bool,
+    /// Make repo names clickable links (OSC 8) in terminals
+    #[serde(default)]
+    pub hyperlinks: bool,
+    /// Print a MOTD footer counting repos per provider and summing total stars
+    #[serde(default)]
+    pub summary: bool,
+    /// Day boundaries for fresh / recent / aging / old
+    #[serde(default = "default_dim_old_days")]
+    pub dim_old_days: [u32; 3],
+    /// Repos always shown above trending ones (`owner/name` for GitHub, or full URLs)
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    /// URL of a JSON list of extra GitLab/Gitea instances to query
+    #[serde(default)]
+    pub instances_url: Option<String>,
+    /// How long the downloaded instance list is cached
+    #[serde(default = "default_instances_ttl_mins")]
+    pub instances_ttl_mins: u64,
+    /// Show expired cache data when a provider's live fetch fails
+    #[serde(default = "default_true")]
+    pub stale_fallback: bool,
+    /// Only warn about stale data at least this old (0 = always warn)
+    #[serde(default)]
+    pub stale_warn_mins: u64,
+    /// Never touch the network: show cached data of any age, skip uncached providers
+    #[serde(default)]
+    pub offline: bool,
+    /// Show expired cache entries right away and refetch them in the background
+    #[serde(default)]
+    pub stale_while_revalidate: bool,
+}
+
+/// Thousands separator used for star counts in MOTD output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StarSeparator {
+    #[default]
+    None,
+    Comma,
+    Space,
+    /// Derived from `LC_ALL` / `LC_NUMERIC` / `LANG`
+    Locale,
+}
+
+impl StarSeparator {
+    /// Resolve to the separator character, if any
+    pub fn resolve(self) -> Option<char> {
+        match self {
+            Self::None => None,
+            Self::Comma => Some(','),
+            Self::Space => Some(' '),
+            Self::Locale => {
+                let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+                    .iter()
+                    .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+                    .unwrap_or_default();
+                Some(locale_separator(&locale))
+            }
+        }
+    }
+}
+
+/// Thousands separator conventionally used by a POSIX locale name (e.g. `de_DE.UTF-8`)
+fn locale_separator(locale: &str) -> char {
+    let lang = locale.split(['_', '.', '@']).next().unwrap_or("");
+    match lang {
+        "de" | "es" | "it" | "nl" | "pt" | "da" | "tr" | "id" | "el" => '.',
+        "fr" | "ru" | "pl" | "cs" | "sv" | "fi" | "nb" | "no" | "uk" | "sk" | "hu" => ' ',
+        _ => ',',
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ProvidersConfig {
     #[serde(default = "default_true")]
     pub github: bool,
@@ -53,26 +239,223 @@ pub struct ProvidersConfig {
     pub gitlab: bool,
     #[serde(default = "default_true")]
     pub gitea: bool,
+    #[serde(default = "default_true")]
+    pub codeberg: bool,
+    /// Opt-in: Bitbucket has no stars, so its repositories are ranked by recent activity
+    #[serde(default)]
+    pub bitbucket: bool,
+    /// Opt-in: crates.io's most downloaded crates, showing recent downloads
+    #[serde(default)]
+    pub crates: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[allow(clippy::struct_field_names)]
 pub struct AuthConfig {
     pub github_token: Option<String>,
+    /// Extra GitHub tokens, rotated to when one hits its rate limit
+    #[serde(default)]
+    pub github_tokens: Vec<String>,
     pub gitlab_token: Option<String>,
     pub gitea_token: Option<String>,
+    /// Tokens for further Gitea/Forgejo instances, keyed by host (e.g. `codeberg.org`)
+    #[serde(default)]
+    pub gitea_tokens: BTreeMap<String, String>,
+    pub bitbucket_token: Option<String>,
+    /// Fail unless every enabled provider that takes a token gets it from the environment
+    #[serde(default)]
+    pub require_env_tokens: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GiteaConfig {
-    #[serde(default = "default_gitea_url")]
-    pub base_url: String,
+    /// Gitea/Forgejo instances to query; the first is the `gitea` provider and
+    /// the rest are listed as `gitea@host` (a single `base_url` string also works)
+    #[serde(
+        default = "default_gitea_urls",
+        alias = "base_url",
+        deserialize_with = "one_or_many"
+    )]
+    pub base_urls: Vec<String>,
+    /// Retries for Gitea requests (falls back to `general.max_retries`)
+    #[serde(default)]
+    pub max_retries: Option<usize>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubConfig {
     #[serde(default)]
     pub exclude_topics: Vec<String>,
+    /// Keep only repositories carrying every one of these topics
+    #[serde(default)]
+    pub topics_all: Vec<String>,
+    /// Keep only repositories carrying at least one of these topics
+    /// (`include_topics` is accepted as an alias)
+    #[serde(default, alias = "include_topics")]
+    pub topics_any: Vec<String>,
+    /// Retry once when the trending page comes back with zero repositories
+    #[serde(default)]
+    pub retry_on_empty: bool,
+    /// Query the GraphQL API instead of scraping when a token is set
+    #[serde(default)]
+    pub use_graphql: bool,
+    /// Merge daily and weekly trending pages (doubles scraping requests)
+    #[serde(default)]
+    pub merge_windows: bool,
+    /// Trending window: daily, weekly, or monthly (ignored with `merge_windows`)
+    #[serde(default)]
+    pub since: Since,
+    /// Only repos whose README is in this human language, e.g. `en` (trending page only)
+    #[serde(default)]
+    pub spoken_language: Option<String>,
+    /// Retries for GitHub requests (falls back to `general.max_retries`)
+    #[serde(default)]
+    pub max_retries: Option<usize>,
+    /// Maximum concurrent trending-page (HTML) requests
+    #[serde(default = "default_html_concurrency")]
+    pub html_concurrency: usize,
+    /// Maximum concurrent REST API (JSON) requests
+    #[serde(default = "default_api_concurrency")]
+    pub api_concurrency: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GitLabConfig {
+    /// Drop projects carrying any of these topics (case-insensitive)
+    #[serde(default)]
+    pub exclude_topics: Vec<String>,
+    /// Retries for GitLab requests (falls back to `general.max_retries`)
+    #[serde(default)]
+    pub max_retries: Option<usize>,
+    /// Enrichment: when topics name no language, look up the project's primary
+    /// language via the languages API (also accepted as `detect_language`)
+    #[serde(default, alias = "detect_language")]
+    pub use_languages_api: bool,
+    /// Enrichment: drop projects with fewer commits than this in the last 7 days
+    #[serde(default)]
+    pub min_recent_commits: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CodebergConfig {
+    /// Retries for Codeberg requests (falls back to `general.max_retries`)
+    #[serde(default)]
+    pub max_retries: Option<usize>,
+    /// Cache TTL for Codeberg (falls back to `general.cache_ttl_mins`)
+    #[serde(default)]
+    pub cache_ttl_mins: Option<u64>,
+    /// Star floor for Codeberg (falls back to `general.min_stars`)
+    #[serde(default)]
+    pub min_stars: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BitbucketConfig {
+    /// Retries for Bitbucket requests (falls back to `general.max_retries`)
+    #[serde(default)]
+    pub max_retries: Option<usize>,
+    /// Cache TTL for Bitbucket (falls back to `general.cache_ttl_mins`)
+    #[serde(default)]
+    pub cache_ttl_mins: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CratesConfig {
+    /// Retries for crates.io requests (falls back to `general.max_retries`)
+    #[serde(default)]
+    pub max_retries: Option<usize>,
+    /// Cache TTL for crates.io (falls back to `general.cache_ttl_mins`)
+    #[serde(default)]
+    pub cache_ttl_mins: Option<u64>,
+}
+
+/// A Gitea/Forgejo or GitLab instance added from config (`[[custom_provider]]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomProviderConfig {
+    /// Short name; the provider is shown and cached as `kind@id`
+    pub id: String,
+    /// Icon shown for its repositories, e.g. `[CO]`
+    pub icon: String,
+    /// Instance URL, e.g. `https://git.company.internal`
+    pub base_url: String,
+    pub kind: CustomProviderKind,
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Contents of a TOML `--providers-file`
+#[derive(Debug, Deserialize)]
+struct ProvidersFile {
+    #[serde(default)]
+    custom_provider: Vec<CustomProviderConfig>,
+}
+
+/// Icon override from the `[icons]` table
+///
+/// Either a bare glyph (`github = "\uf09b"`) or a table with an optional
+/// `icon` and `color` (`gitlab = { icon = "GL", color = "bright red" }`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "IconOverrideRepr")]
+pub struct IconOverride {
+    pub icon: Option<String>,
+    /// Color name as understood by `colored`, e.g. `cyan` or `bright blue`
+    pub color: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IconOverrideRepr {
+    Glyph(String),
+    Styled(IconStyle),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IconStyle {
+    #[serde(default)]
+    icon: Option<String>,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+impl From<IconOverrideRepr> for IconOverride {
+    fn from(repr: IconOverrideRepr) -> Self {
+        match repr {
+            IconOverrideRepr::Glyph(icon) => Self {
+                icon: Some(icon),
+                color: None,
+            },
+            IconOverrideRepr::Styled(style) => Self {
+                icon: style.icon,
+                color: style.color,
+            },
+        }
+    }
+}
+
+/// API spoken by a custom provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomProviderKind {
+    Gitea,
+    Gitlab,
+}
+
+impl CustomProviderKind {
+    /// Built-in provider implementing this API
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Gitea => "gitea",
+            Self::Gitlab => "gitlab",
+        }
+    }
+}
+
+impl CustomProviderConfig {
+    /// Provider id used for caching and messages, e.g. `gitlab@company`
+    pub fn provider_id(&self) -> String {
+        format!("{}@{}", self.kind.as_str(), self.id)
+    }
 }
 
 impl Default for GeneralConfig {
@@ -82,14 +465,53 @@ impl Default for GeneralConfig {
             github_max_entries: None,
             gitlab_max_entries: None,
             gitea_max_entries: None,
+            total_limit: None,
             timeout_secs: default_timeout_secs(),
+            max_concurrency: default_max_concurrency(),
             cache_ttl_mins: default_cache_ttl_mins(),
+            github_cache_ttl_mins: None,
+            gitlab_cache_ttl_mins: None,
+            gitea_cache_ttl_mins: None,
             language_filter: vec![],
+            exclude_languages: vec![],
             github_timeout_secs: default_github_timeout_secs(),
             gitlab_timeout_secs: default_gitlab_timeout_secs(),
             gitea_timeout_secs: default_gitea_timeout_secs(),
+            connect_timeout_secs: None,
+            deadline_secs: None,
+            extra_headers: BTreeMap::new(),
             ascii_only: false,
+            allowed_scripts: vec![],
+            dedupe: false,
             min_stars: None,
+            github_min_stars: None,
+            gitlab_min_stars: None,
+            gitea_min_stars: None,
+            exclude_name_patterns: vec![],
+            min_activity: None,
+            require_activity: false,
+            strip_emoji: false,
+            star_separator: StarSeparator::None,
+            humanize_stars: true,
+            max_retries: default_max_retries(),
+            retry_base_ms: default_retry_base_ms(),
+            retries_enabled: true,
+            enrich: false,
+            require_readme: false,
+            sort: None,
+            icons: IconSet::Ascii,
+            description_width: None,
+            dim_old: false,
+            hyperlinks: false,
+            summary: false,
+            dim_old_days: default_dim_old_days(),
+            pinned: vec![],
+            instances_url: None,
+            instances_ttl_mins: default_instances_ttl_mins(),
+            stale_fallback: true,
+            stale_warn_mins: 0,
+            offline: false,
+            stale_while_revalidate: false,
         }
     }
 }
@@ -100,6 +522,27 @@ impl Default for ProvidersConfig {
             github: true,
             gitlab: true,
             gitea: true,
+            codeberg: true,
+            bitbucket: false,
+            crates: false,
+        }
+    }
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            exclude_topics: vec![],
+            topics_all: vec![],
+            topics_any: vec![],
+            retry_on_empty: false,
+            use_graphql: false,
+            merge_windows: false,
+            since: Since::Daily,
+            spoken_language: None,
+            max_retries: None,
+            html_concurrency: default_html_concurrency(),
+            api_concurrency: default_api_concurrency(),
         }
     }
 }
@@ -107,7 +550,8 @@ impl Default for ProvidersConfig {
 impl Default for GiteaConfig {
     fn default() -> Self {
         Self {
-            base_url: default_gitea_url(),
+            base_urls: default_gitea_urls(),
+            max_retries: None,
         }
     }
 }
@@ -132,53 +576,578 @@ fn default_gitea_timeout_secs() -> u64 {
     10
 }
 
+fn default_max_concurrency() -> usize {
+    4
+}
+
+fn default_html_concurrency() -> usize {
+    2
+}
+
+fn default_api_concurrency() -> usize {
+    4
+}
+
+fn default_instances_ttl_mins() -> u64 {
+    24 * 60
+}
+
+fn default_dim_old_days() -> [u32; 3] {
+    [1, 2, 7]
+}
+
+fn default_max_retries() -> usize {
+    3
+}
+
+fn default_retry_base_ms() -> u64 {
+    1000
+}
+
 fn default_cache_ttl_mins() -> u64 {
     60
 }
 
-fn default_gitea_url() -> String {
-    "https://gitea.com".to_string()
+/// Environment variables that can supply each token-using provider's token
+const TOKEN_ENV_VARS: [(&str, &[&str]); 4] = [
+    ("github", &["TROTD_GITHUB_TOKEN", "TROTD_GITHUB_TOKENS"]),
+    ("gitlab", &["TROTD_GITLAB_TOKEN"]),
+    ("gitea", &["TROTD_GITEA_TOKEN"]),
+    ("bitbucket", &["TROTD_BITBUCKET_TOKEN"]),
+];
+
+/// Largest per-provider entry count `validate` accepts
+const MAX_ENTRIES: usize = 100;
+
+/// Longest cache TTL `validate` accepts: one week
+const MAX_CACHE_TTL_MINS: u64 = 7 * 24 * 60;
+
+/// Parse an activity window such as `24h`, `7d`, or `2w`
+pub fn parse_duration(s: &str) -> Result<chrono::Duration> {
+    let s = s.trim();
+    let (count, unit) = s.split_at(s.len() - s.chars().last().map_or(0, char::len_utf8));
+    let count: u32 = count
+        .parse()
+        .with_context(|| format!("Invalid duration '{s}': expected e.g. 24h, 7d, or 2w"))?;
+    let count = i64::from(count);
+    match unit {
+        "h" => Ok(chrono::Duration::hours(count)),
+        "d" => Ok(chrono::Duration::days(count)),
+        "w" => Ok(chrono::Duration::weeks(count)),
+        _ => anyhow::bail!("Invalid duration '{s}': use an h, d, or w suffix"),
+    }
+}
+
+/// Gitea instance used when `gitea.base_urls` is empty
+const DEFAULT_GITEA_URL: &str = "https://gitea.com";
+
+fn default_gitea_urls() -> Vec<String> {
+    vec![DEFAULT_GITEA_URL.to_string()]
+}
+
+/// Accept either a single string or a list of strings
+fn one_or_many<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(url) => vec![url],
+        OneOrMany::Many(urls) => urls,
+    })
 }
 
 fn default_true() -> bool {
     true
 }
 
+/// Check a boolean environment variable (unset, empty, `0`, and `false` are off)
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| !v.is_empty() && v != "0" && !v.eq_ignore_ascii_case("false"))
+}
+
+fn flatten_into(
+    value: &serde_json::Value,
+    prefix: &str,
+    out: &mut BTreeMap<String, serde_json::Value>,
+) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_into(child, &path, out);
+            }
+        }
+        leaf => {
+            out.insert(prefix.to_string(), leaf.clone());
+        }
+    }
+}
+
+/// System-wide config files from `XDG_CONFIG_DIRS`, lowest precedence first
+///
+/// Unset or empty means `/etc/xdg`. The spec lists the most important
+/// directory first, so the order is reversed for merging.
+fn system_config_paths(config_dirs: Option<&str>) -> Vec<PathBuf> {
+    let config_dirs = config_dirs
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or("/etc/xdg");
+    config_dirs
+        .split(':')
+        .filter(|dir| !dir.is_empty())
+        .rev()
+        .map(|dir| PathBuf::from(dir).join("trotd").join("trotd.toml"))
+        .collect()
+}
+
+/// Whether to write a default user config, given the files `load` would merge
+///
+/// Only when there are none at all: a system-wide file means an admin manages
+/// the defaults, and a full user file would shadow every key in it.
+/// `no_write` is the `TROTD_NO_CONFIG_WRITE` flag.
+fn should_write_default(files: &[PathBuf], write_default: bool, no_write: bool) -> bool {
+    write_default && files.is_empty() && !no_write
+}
+
+/// Parse TOML, collecting the dotted paths of keys the target type doesn't know
+fn parse_toml<T: serde::de::DeserializeOwned>(content: &str) -> Result<(T, Vec<String>)> {
+    let mut unknown = Vec::new();
+    let value = serde_ignored::deserialize(toml::Deserializer::new(content), |key| {
+        unknown.push(key.to_string());
+    })?;
+    Ok((value, unknown))
+}
+
+/// Warn about unknown keys in a config file, or fail naming them when `strict`
+///
+/// Warning keeps an old file (e.g. the auto-written default after a setting
+/// is renamed) from breaking a login MOTD.
+fn report_unknown_keys(path: &Path, unknown: &[String], strict: bool) -> Result<()> {
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    if strict {
+        anyhow::bail!(
+            "Unknown key(s) in {}: {}",
+            path.display(),
+            unknown.join(", ")
+        );
+    }
+    for key in unknown {
+        eprintln!("⚠ {}: unknown key {key} ignored", path.display());
+    }
+    Ok(())
+}
+
+/// Merge `overlay` into `base`: tables merge key by key, anything else is replaced
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 impl Config {
     /// Load configuration from file, with XDG config directory support
+    ///
+    /// System-wide files in `XDG_CONFIG_DIRS` are merged under the user file,
+    /// so any key the user sets wins.
     pub fn load() -> Result<Self> {
-        // Try XDG config directory first, then current directory
-        let config_paths = [
+        Self::load_with(true)
+    }
+
+    /// Config files that `load` merges, lowest precedence first (empty: defaults only)
+    pub fn files() -> Vec<PathBuf> {
+        // System-wide files first, so the user file merged on top wins
+        let mut files: Vec<PathBuf> =
+            system_config_paths(std::env::var("XDG_CONFIG_DIRS").ok().as_deref())
+                .into_iter()
+                .filter(|path| path.exists())
+                .collect();
+
+        // Then the XDG config directory, falling back to the current directory
+        let user_paths = [
             dirs::config_dir().map(|p| p.join("trotd").join("trotd.toml")),
             Some(PathBuf::from("trotd.toml")),
         ];
+        files.extend(user_paths.into_iter().flatten().find(|path| path.exists()));
+        files
+    }
+
+    /// Load configuration, optionally creating a default config file when none exists
+    ///
+    /// The file is never written when `TROTD_NO_CONFIG_WRITE` is set (to anything
+    /// other than an empty string, `0`, or `false`).
+    pub fn load_with(write_default: bool) -> Result<Self> {
+        Self::load_files(write_default, false)
+    }
 
-        for path in config_paths.iter().flatten() {
-            if path.exists() {
-                let content = std::fs::read_to_string(path)
-                    .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    /// Load the config files like `load`, but fail on any unknown key
+    /// (`trotd config check`). Never writes a default file.
+    pub fn check() -> Result<Self> {
+        Self::load_files(false, true)
+    }
 
-                let mut config: Config = toml::from_str(&content)
-                    .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+    /// Merge the config files, warning about unknown keys (or failing when `strict`)
+    fn load_files(write_default: bool, strict: bool) -> Result<Self> {
+        let files = Self::files();
 
-                // Convert empty token strings to None
-                config.normalize_tokens();
+        // No config file found anywhere: create the default and tell the user
+        if should_write_default(&files, write_default, env_flag("TROTD_NO_CONFIG_WRITE")) {
+            Self::create_default_config_if_missing()?;
+        }
 
-                // Apply environment variable overrides
-                config.apply_env_overrides();
+        if files.is_empty() {
+            let mut config = Config::default();
+            config.apply_env_overrides();
+            config.mark_changed_since(&Config::default(), Source::Env);
+            config
+                .validate()
+                .context("Invalid configuration from environment variables")?;
+            return Ok(config);
+        }
 
-                return Ok(config);
-            }
+        let mut merged = toml::Value::Table(toml::Table::new());
+        for path in &files {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+            // Parse and check each file on its own so errors point at the offending
+            // one; unknown keys (typos, stale or renamed settings) are reported
+            let (file_config, unknown) = parse_toml::<Config>(&content)
+                .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+            report_unknown_keys(path, &unknown, strict)?;
+            file_config
+                .validate()
+                .with_context(|| format!("Invalid config file: {}", path.display()))?;
+            let file: toml::Value = toml::from_str(&content)
+                .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+            merge_toml(&mut merged, file);
         }
 
-        // No config file found, create default and warn user
-        Self::create_default_config_if_missing()?;
+        let mut config: Config = merged
+            .clone()
+            .try_into()
+            .context("Failed to parse merged config files")?;
 
-        let mut config = Config::default();
+        // Convert empty token strings to None
+        config.normalize_tokens();
+
+        // Every key spelled out in a file is attributed to it
+        let mut keys = BTreeMap::new();
+        flatten_into(
+            &serde_json::to_value(merged).unwrap_or_default(),
+            "",
+            &mut keys,
+        );
+        config
+            .provenance
+            .extend(keys.into_keys().map(|k| (k, Source::File)));
+
+        // Apply environment variable overrides
+        let before = config.clone();
         config.apply_env_overrides();
+        config.mark_changed_since(&before, Source::Env);
+        config
+            .validate()
+            .context("Invalid configuration from environment variables")?;
+
         Ok(config)
     }
 
+    /// Add the custom providers listed in a `--providers-file`
+    ///
+    /// A `.json` file holds an array of provider objects; anything else is TOML
+    /// with `[[custom_provider]]` tables, as in the main config. An `id` that is
+    /// already defined is an error.
+    pub fn add_providers_file(&mut self, path: &Path) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read providers file: {}", path.display()))?;
+        let (providers, unknown) = if path.extension().is_some_and(|ext| ext == "json") {
+            let mut unknown = Vec::new();
+            let mut json = serde_json::Deserializer::from_str(&content);
+            serde_ignored::deserialize(&mut json, |key| unknown.push(key.to_string()))
+                .map(|providers| (providers, unknown))
+                .map_err(anyhow::Error::from)
+        } else {
+            parse_toml::<ProvidersFile>(&content)
+                .map(|(file, unknown)| (file.custom_provider, unknown))
+        }
+        .with_context(|| format!("Failed to parse providers file: {}", path.display()))?;
+        report_unknown_keys(path, &unknown, false)?;
+
+        for custom in providers {
+            if self.custom_providers.iter().any(|c| c.id == custom.id) {
+                anyhow::bail!(
+                    "custom_provider id {:?} in {} is already defined",
+                    custom.id,
+                    path.display()
+                );
+            }
+            self.custom_providers.push(custom);
+        }
+        self.normalize_tokens();
+        self.validate()
+            .with_context(|| format!("Invalid providers file: {}", path.display()))
+    }
+
+    /// With `auth.require_env_tokens`, fail if a provider about to be queried has no
+    /// token set in the environment, so CI never falls back to unauthenticated requests
+    ///
+    /// `providers` is the set actually fetched, after `--provider`.
+    pub fn check_env_tokens(
+        &self,
+        providers: &[&str],
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<()> {
+        if !self.auth.require_env_tokens {
+            return Ok(());
+        }
+
+        let missing: Vec<&str> = TOKEN_ENV_VARS
+            .iter()
+            .filter(|(provider, _)| providers.contains(provider))
+            .filter(|(_, names)| {
+                !names
+                    .iter()
+                    .any(|name| var(name).is_some_and(|v| !v.trim().is_empty()))
+            })
+            .map(|(_, names)| names[0])
+            .collect();
+
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "auth.require_env_tokens is set but {} is unset; export it or disable the provider",
+                missing.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Check numeric settings against their allowed ranges, naming the offending key
+    pub fn validate(&self) -> Result<()> {
+        let general = &self.general;
+
+        let timeouts = [
+            ("general.timeout_secs", general.timeout_secs),
+            ("general.github_timeout_secs", general.github_timeout_secs),
+            ("general.gitlab_timeout_secs", general.gitlab_timeout_secs),
+            ("general.gitea_timeout_secs", general.gitea_timeout_secs),
+        ];
+        for (key, secs) in timeouts {
+            if secs == 0 {
+                anyhow::bail!("{key} must be greater than 0");
+            }
+        }
+
+        if general.max_concurrency == 0 {
+            anyhow::bail!("general.max_concurrency must be greater than 0");
+        }
+        if general.deadline_secs == Some(0) {
+            anyhow::bail!("general.deadline_secs must be greater than 0");
+        }
+
+        let ttls = [
+            ("general.cache_ttl_mins", Some(general.cache_ttl_mins)),
+            (
+                "general.github_cache_ttl_mins",
+                general.github_cache_ttl_mins,
+            ),
+            (
+                "general.gitlab_cache_ttl_mins",
+                general.gitlab_cache_ttl_mins,
+            ),
+            ("general.gitea_cache_ttl_mins", general.gitea_cache_ttl_mins),
+            ("codeberg.cache_ttl_mins", self.codeberg.cache_ttl_mins),
+            ("bitbucket.cache_ttl_mins", self.bitbucket.cache_ttl_mins),
+            ("crates.cache_ttl_mins", self.crates.cache_ttl_mins),
+        ];
+        for (key, mins) in ttls {
+            if let Some(mins) = mins.filter(|&m| m > MAX_CACHE_TTL_MINS) {
+                anyhow::bail!("{key} = {mins} is longer than a week ({MAX_CACHE_TTL_MINS})");
+            }
+        }
+
+        let entries = [
+            ("general.max_per_provider", Some(general.max_per_provider)),
+            ("general.github_max_entries", general.github_max_entries),
+            ("general.gitlab_max_entries", general.gitlab_max_entries),
+            ("general.gitea_max_entries", general.gitea_max_entries),
+        ];
+        for (key, n) in entries {
+            if let Some(n) = n.filter(|n| !(1..=MAX_ENTRIES).contains(n)) {
+                anyhow::bail!("{key} = {n} must be between 1 and {MAX_ENTRIES}");
+            }
+        }
+
+        if let Some(ref window) = general.min_activity {
+            parse_duration(window).context("general.min_activity")?;
+        }
+
+        if let Some(name) = general
+            .allowed_scripts
+            .iter()
+            .find(|n| Script::from_name(n).is_none())
+        {
+            anyhow::bail!(
+                "general.allowed_scripts: unknown script {name:?} (expected one of: {})",
+                Script::names().collect::<Vec<_>>().join(", ")
+            );
+        }
+
+        for (i, custom) in self.custom_providers.iter().enumerate() {
+            if custom.id.trim().is_empty() || custom.id.contains(['@', '/']) {
+                anyhow::bail!(
+                    "custom_provider id {:?} must be non-empty without '@' or '/'",
+                    custom.id
+                );
+            }
+            if self.custom_providers[..i].iter().any(|c| c.id == custom.id) {
+                anyhow::bail!("custom_provider id {:?} is defined twice", custom.id);
+            }
+            if !custom.base_url.starts_with("http://") && !custom.base_url.starts_with("https://") {
+                anyhow::bail!(
+                    "custom_provider {}: base_url {:?} must start with http:// or https://",
+                    custom.id,
+                    custom.base_url
+                );
+            }
+        }
+
+        for (provider, style) in &self.icons {
+            if style
+                .icon
+                .as_deref()
+                .is_some_and(|icon| icon.trim().is_empty())
+            {
+                anyhow::bail!("icons.{provider}: icon must not be empty");
+            }
+            if let Some(ref color) = style.color {
+                if colored::Color::from_str(color).is_err() {
+                    anyhow::bail!(
+                        "icons.{provider}: unknown color {color:?} (e.g. red, cyan, bright blue)"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Icon to show for a provider's repositories, if `[icons]` overrides it
+    ///
+    /// An exact provider id wins. Instances without their own icon (shown
+    /// as `{icon}@{host}`) keep the host and take their kind's glyph.
+    pub fn icon_override(&self, provider: &str, icon: &str) -> Option<String> {
+        if let Some(glyph) = self.icons.get(provider).and_then(|o| o.icon.as_ref()) {
+            return Some(glyph.clone());
+        }
+        let (kind, host) = provider.split_once('@')?;
+        let suffix = format!("@{host}");
+        if !icon.ends_with(&suffix) {
+            return None;
+        }
+        let glyph = self.icons.get(kind)?.icon.as_ref()?;
+        Some(format!("{glyph}{suffix}"))
+    }
+
+    /// Icon colors from `[icons]`, keyed by provider id or kind
+    pub fn icon_colors(&self) -> BTreeMap<String, colored::Color> {
+        self.icons
+            .iter()
+            .filter_map(|(provider, style)| {
+                let color = colored::Color::from_str(style.color.as_deref()?).ok()?;
+                Some((provider.clone(), color))
+            })
+            .collect()
+    }
+
+    /// Copy of the config with auth tokens masked, safe to print
+    pub fn redacted(&self) -> Config {
+        let mut config = self.clone();
+        for token in [
+            &mut config.auth.github_token,
+            &mut config.auth.gitlab_token,
+            &mut config.auth.gitea_token,
+            &mut config.auth.bitbucket_token,
+        ] {
+            if token.is_some() {
+                *token = Some("***".to_string());
+            }
+        }
+        for token in &mut config.auth.github_tokens {
+            *token = "***".to_string();
+        }
+        for token in config.auth.gitea_tokens.values_mut() {
+            *token = "***".to_string();
+        }
+        for custom in &mut config.custom_providers {
+            if custom.token.is_some() {
+                custom.token = Some("***".to_string());
+            }
+        }
+        // Proxy headers often carry credentials too
+        for value in config.general.extra_headers.values_mut() {
+            *value = "***".to_string();
+        }
+        config
+    }
+
+    /// Attribute every value that differs from `before` to `source`
+    pub fn mark_changed_since(&mut self, before: &Config, source: Source) {
+        let old = before.flatten();
+        for (key, value) in self.flatten() {
+            if old.get(&key) != Some(&value) {
+                self.provenance.insert(key, source);
+            }
+        }
+    }
+
+    /// Source of every effective value, keyed by dotted path
+    pub fn provenance_map(&self) -> BTreeMap<String, Source> {
+        self.flatten()
+            .into_keys()
+            .map(|key| {
+                let source = self
+                    .provenance
+                    .get(&key)
+                    .copied()
+                    .unwrap_or(Source::Default);
+                (key, source)
+            })
+            .collect()
+    }
+
+    /// Effective config as dotted-path leaves (arrays are kept whole)
+    fn flatten(&self) -> BTreeMap<String, serde_json::Value> {
+        let mut out = BTreeMap::new();
+        flatten_into(
+            &serde_json::to_value(self).unwrap_or_default(),
+            "",
+            &mut out,
+        );
+        out
+    }
+
     /// Create a default config file in the XDG config directory if it doesn't exist
     fn create_default_config_if_missing() -> Result<()> {
         if let Some(config_dir) = dirs::config_dir() {
@@ -215,6 +1184,7 @@ impl Config {
                 self.auth.github_token = None;
             }
         }
+        self.auth.github_tokens.retain(|t| !t.trim().is_empty());
         if let Some(ref token) = self.auth.gitlab_token {
             if token.trim().is_empty() {
                 self.auth.gitlab_token = None;
@@ -225,6 +1195,15 @@ impl Config {
                 self.auth.gitea_token = None;
             }
         }
+        self.auth.gitea_tokens.retain(|_, t| !t.trim().is_empty());
+        for custom in &mut self.custom_providers {
+            custom.token = custom.token.take().filter(|t| !t.trim().is_empty());
+        }
+        if let Some(ref token) = self.auth.bitbucket_token {
+            if token.trim().is_empty() {
+                self.auth.bitbucket_token = None;
+            }
+        }
     }
 
     /// Apply environment variable overrides
@@ -239,6 +1218,10 @@ impl Config {
             self.general.language_filter = val.split(',').map(|s| s.trim().to_string()).collect();
         }
 
+        if let Ok(val) = std::env::var("TROTD_EXCLUDE_LANGUAGES") {
+            self.general.exclude_languages = val.split(',').map(|s| s.trim().to_string()).collect();
+        }
+
         if let Ok(val) = std::env::var("TROTD_GITHUB_TIMEOUT_SECS") {
             if let Ok(timeout) = val.parse() {
                 self.general.github_timeout_secs = timeout;
@@ -257,14 +1240,28 @@ impl Config {
             }
         }
 
+        if let Ok(val) = std::env::var("TROTD_CONNECT_TIMEOUT_SECS") {
+            if let Ok(timeout) = val.parse() {
+                self.general.connect_timeout_secs = Some(timeout);
+            }
+        }
+
         if let Ok(val) = std::env::var("TROTD_GITEA_BASE_URL") {
-            self.gitea.base_url = val;
+            self.gitea.base_urls = vec![val];
         }
 
         if let Ok(val) = std::env::var("TROTD_GITHUB_TOKEN") {
             self.auth.github_token = Some(val);
         }
 
+        if let Ok(val) = std::env::var("TROTD_GITHUB_TOKENS") {
+            self.auth.github_tokens = val
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+
         if let Ok(val) = std::env::var("TROTD_GITLAB_TOKEN") {
             self.auth.gitlab_token = Some(val);
         }
@@ -273,6 +1270,10 @@ impl Config {
             self.auth.gitea_token = Some(val);
         }
 
+        if let Ok(val) = std::env::var("TROTD_BITBUCKET_TOKEN") {
+            self.auth.bitbucket_token = Some(val);
+        }
+
         if let Ok(val) = std::env::var("TROTD_MIN_STARS") {
             if let Ok(min) = val.parse() {
                 self.general.min_stars = Some(min);
@@ -296,9 +1297,121 @@ impl Config {
         if self.providers.gitea {
             providers.push("gitea");
         }
+        if self.providers.codeberg {
+            providers.push("codeberg");
+        }
+        if self.providers.bitbucket {
+            providers.push("bitbucket");
+        }
+        if self.providers.crates {
+            providers.push("crates");
+        }
         providers
     }
 
+    /// All configured GitHub tokens: `github_token` first, then `github_tokens`, deduplicated
+    pub fn github_token_pool(&self) -> Vec<String> {
+        let mut pool: Vec<String> = Vec::new();
+        for token in self
+            .auth
+            .github_token
+            .iter()
+            .chain(&self.auth.github_tokens)
+        {
+            if !pool.contains(token) {
+                pool.push(token.clone());
+            }
+        }
+        pool
+    }
+
+    /// Get the number of HTTP retries for a specific provider
+    pub fn get_max_retries(&self, provider: &str) -> usize {
+        if !self.general.retries_enabled {
+            return 0;
+        }
+
+        match provider {
+            "github" => self.github.max_retries,
+            "gitlab" => self.gitlab.max_retries,
+            "gitea" => self.gitea.max_retries,
+            "codeberg" => self.codeberg.max_retries,
+            "bitbucket" => self.bitbucket.max_retries,
+            "crates" => self.crates.max_retries,
+            _ => None,
+        }
+        .unwrap_or(self.general.max_retries)
+    }
+
+    /// Get the cache TTL in minutes for a specific provider
+    pub fn get_cache_ttl_mins(&self, provider: &str) -> u64 {
+        match provider {
+            "github" => self.general.github_cache_ttl_mins,
+            "gitlab" => self.general.gitlab_cache_ttl_mins,
+            "gitea" => self.general.gitea_cache_ttl_mins,
+            "codeberg" => self.codeberg.cache_ttl_mins,
+            "bitbucket" => self.bitbucket.cache_ttl_mins,
+            "crates" => self.crates.cache_ttl_mins,
+            _ => None,
+        }
+        .unwrap_or(self.general.cache_ttl_mins)
+    }
+
+    /// The primary Gitea instance: the first of `gitea.base_urls`
+    pub fn gitea_base_url(&self) -> &str {
+        self.gitea
+            .base_urls
+            .first()
+            .map_or(DEFAULT_GITEA_URL, String::as_str)
+    }
+
+    /// Token for a Gitea instance: its `auth.gitea_tokens` entry by host, or
+    /// `auth.gitea_token` for the primary instance only
+    ///
+    /// Host tokens are only sent over https, and the primary token only to
+    /// the primary URL's exact scheme and host.
+    pub fn gitea_token_for(&self, base_url: &str) -> Option<String> {
+        let origin = |url: &str| {
+            let (scheme, rest) = url.split_once("://").unwrap_or(("", url));
+            let host = rest.split('/').next().unwrap_or(rest);
+            (scheme.to_ascii_lowercase(), host.to_ascii_lowercase())
+        };
+        let (scheme, host) = origin(base_url);
+        let by_host = self
+            .auth
+            .gitea_tokens
+            .iter()
+            .find(|(h, _)| scheme == "https" && h.to_ascii_lowercase() == host)
+            .map(|(_, token)| token.clone());
+        by_host.or_else(|| {
+            let primary = origin(self.gitea_base_url()) == (scheme, host);
+            primary.then(|| self.auth.gitea_token.clone()).flatten()
+        })
+    }
+
+    /// Get the minimum star count for a specific provider, if any
+    ///
+    /// Bitbucket and crates.io report no stars, so no floor applies to them.
+    pub fn get_min_stars(&self, provider: &str) -> Option<u64> {
+        match provider {
+            "github" => self.general.github_min_stars,
+            "gitlab" => self.general.gitlab_min_stars,
+            "gitea" => self.general.gitea_min_stars,
+            "codeberg" => self.codeberg.min_stars,
+            "bitbucket" | "crates" => return None,
+            _ => None,
+        }
+        .or(self.general.min_stars)
+    }
+
+    /// `general.min_activity` as a duration (checked by `validate`)
+    pub fn min_activity(&self) -> Option<chrono::Duration> {
+        self.general
+            .min_activity
+            .as_deref()
+            .and_then(|s| parse_duration(s).ok())
+    }
+
     /// Get the maximum number of entries for a specific provider
     pub fn get_max_entries(&self, provider: &str) -> usize {
         match provider {
@@ -319,6 +1432,241 @@ impl Config {
     }
 }
 
+/// Annotated example config printed by `trotd sample-config`
+///
+/// Uncommented values are the defaults; keep in sync with the structs above.
+pub const SAMPLE_CONFIG: &str = r#"# trotd configuration
+#
+# Save as ~/.config/trotd/trotd.toml (or ./trotd.toml). Every key is optional;
+# the values below are the defaults. Commented-out keys default to unset.
+
+[general]
+# Repositories shown per provider
+max_per_provider = 2
+# Per-provider overrides for max_per_provider
+# github_max_entries = 5
+# gitlab_max_entries = 5
+# gitea_max_entries = 5
+# Overall cap across all providers, applied after filtering and sorting
+# total_limit = 5
+
+# Request timeout in seconds (extra GitLab/Gitea instances use this)
+timeout_secs = 6
+# Providers and instances fetched at the same time, README checks included
+# (cache hits without them don't count)
+max_concurrency = 4
+# Per-provider request timeouts in seconds
+github_timeout_secs = 15
+gitlab_timeout_secs = 10
+gitea_timeout_secs = 10
+# Cap on establishing a connection; fails fast on dead hosts while the
+# request timeouts above still bound slow responses
+# connect_timeout_secs = 2
+# Show whatever arrived after this many seconds; unfinished providers count as
+# failed (keeps MOTD logins fast when one provider hangs). Covers the instance
+# list, pinned repos and background cache refreshes too
+# deadline_secs = 5
+
+# How long fetched results are cached, in minutes
+cache_ttl_mins = 60
+# Per-provider cache lifetimes (fall back to cache_ttl_mins)
+# github_cache_ttl_mins = 180
+# gitlab_cache_ttl_mins = 30
+# gitea_cache_ttl_mins = 60
+# Serve expired cache entries when a provider fails (marked as stale)
+stale_fallback = true
+# Only warn about stale data at least this many minutes old (0 = always)
+stale_warn_mins = 0
+# Serve only from the cache, whatever its age; providers without one are skipped
+offline = false
+# Show expired cache entries immediately and refresh them in the background,
+# ready for the next run. The output is printed first; the process then waits
+# for the refresh, at most until deadline_secs
+stale_while_revalidate = false
+
+# Only show these languages (case-insensitive, empty = all)
+language_filter = []
+# Never show these languages (case-insensitive), e.g. ["JavaScript", "PHP"]
+exclude_languages = []
+# Drop repositories with fewer stars
+# min_stars = 50
+# Per-provider star floors (fall back to min_stars)
+# github_min_stars = 500
+# gitlab_min_stars = 50
+# gitea_min_stars = 5
+# Hide repositories whose owner/name matches any of these regexes
+# exclude_name_patterns = ["^microsoft/", "-awesome$"]
+# Hide repositories last active longer ago than this (h, d, or w suffix)
+# min_activity = "7d"
+# Hide repositories with no known last activity (an API row whose timestamp is
+# missing or unparsable; scraped GitHub rows count as active now)
+require_activity = false
+# Hide repositories whose name or description uses a script other than Latin
+# (accents included) or Common (digits, punctuation, emoji). A few stray Greek
+# letters in mostly Latin text (λ-calculus) are tolerated
+ascii_only = false
+# Scripts allowed on top of Latin and Common; listing any enables the filter.
+# Greek, Cyrillic, Armenian, Hebrew, Arabic, Devanagari, Thai, Hangul,
+# Hiragana, Katakana, Han, Other; unknown names are a config error
+allowed_scripts = []
+# Collapse the same repository listed by several providers (keeps the most starred)
+dedupe = false
+# Remove emoji from names and descriptions
+strip_emoji = false
+# Thousands separator for star counts: "none", "comma", "space", or "locale"
+star_separator = "none"
+# Abbreviate MOTD star counts (90.0k, 1.5M); false, or any star_separator other
+# than "none", prints exact counts grouped by that separator
+humanize_stars = true
+# Provider icons: "ascii" ([GH]), "emoji", or "nerdfont"
+icons = "ascii"
+# Sort order across providers: "relevance" (stars today, then total stars, then
+# recent activity), "velocity" (stars per day), "stars", "stars-today",
+# "recency", "name", or "provider" (no sorting)
+# sort = "relevance"
+# Truncate descriptions to this many columns (0 hides the column)
+# description_width = 45
+# Dim rows as they age, using dim_old_days as the day boundaries between
+# fresh, recent, aging, and old
+dim_old = false
+dim_old_days = [1, 2, 7]
+# Make repo names clickable (OSC 8 links; only when coloring a terminal)
+hyperlinks = false
+# Footer line with repos per provider and total stars
+summary = false
+
+# HTTP retries per request (per-provider overrides below)
+max_retries = 3
+# Base delay before the first retry in milliseconds (grows exponentially)
+retry_base_ms = 1000
+# false disables retries everywhere
+retries_enabled = true
+
+# Enrichment: extra per-repo requests for richer data (slower)
+enrich = false
+# Enrichment: keep only repositories that have a README (GitHub/Gitea)
+require_readme = false
+
+# Repositories always shown above trending ones (owner/name for GitHub, or full URLs)
+pinned = []
+
+# JSON list of extra GitLab/Gitea instances to query
+# instances_url = "https://example.com/trotd-instances.json"
+# How long the downloaded instance list is cached, in minutes
+instances_ttl_mins = 1440
+
+# Headers added to every request, e.g. for corporate proxies or WAFs
+# (Authorization can't be set here; use [auth])
+# [general.extra_headers]
+# X-Corp-Auth = "..."
+
+[providers]
+# Enable or disable each provider
+github = true
+gitlab = true
+gitea = true
+codeberg = true
+# Opt-in: Bitbucket has no stars, so it lists recently updated repositories
+bitbucket = false
+# Opt-in: crates.io's most downloaded crates (recent downloads shown)
+crates = false
+
+[auth]
+# API tokens (raise rate limits; environment variables are preferred)
+# github_token = "ghp_..."
+# Extra GitHub tokens, rotated to when one is rate-limited (403/429)
+github_tokens = []
+# gitlab_token = "glpat-..."
+# gitea_token = "..."
+# bitbucket_token = "..."
+# Fail unless each enabled provider's token comes from its TROTD_*_TOKEN
+# environment variable (for CI)
+require_env_tokens = false
+# Tokens for further Gitea/Forgejo instances, by host
+# [auth.gitea_tokens]
+# "codeberg.org" = "..."
+
+[github]
+# Drop repositories with any of these topics (uses the Search API)
+exclude_topics = []
+# Keep only repositories with every one of these topics (AND)
+topics_all = []
+# Keep only repositories with at least one of these topics (OR)
+topics_any = []
+# Retry once when the trending page comes back empty
+retry_on_empty = false
+# With a token, search the GraphQL API (recently created repos with 100+ stars)
+# instead of scraping the trending page
+use_graphql = false
+# Merge daily and weekly trending pages (doubles scraping requests)
+merge_windows = false
+# Trending window: "daily", "weekly", or "monthly" (ignored with merge_windows)
+since = "daily"
+# Only repos whose README is in this spoken language, e.g. "en" (trending page only)
+# spoken_language = "en"
+# Maximum concurrent trending-page scrapes
+html_concurrency = 2
+# Maximum concurrent REST API calls
+api_concurrency = 4
+# Retries for GitHub requests (falls back to general.max_retries)
+# max_retries = 3
+
+[gitlab]
+# Drop projects with any of these topics, e.g. ["mirror", "archived"]
+exclude_topics = []
+# Enrichment: look up the primary language of projects whose topics don't name
+# one (needs enrich; also spelled detect_language)
+use_languages_api = false
+# Enrichment: minimum commits in the last 7 days (needs enrich, max 100)
+# min_recent_commits = 5
+# Retries for GitLab requests (falls back to general.max_retries)
+# max_retries = 3
+
+[gitea]
+# Gitea/Forgejo instances to query (a single base_url = "..." also works);
+# all but the first are shown as gitea@host
+base_urls = ["https://gitea.com"]
+# Retries for Gitea requests (falls back to general.max_retries)
+# max_retries = 0
+
+[codeberg]
+# Retries for Codeberg requests (falls back to general.max_retries)
+# max_retries = 3
+# Cache TTL in minutes (falls back to general.cache_ttl_mins)
+# cache_ttl_mins = 60
+# Star floor (falls back to general.min_stars)
+# min_stars = 5
+
+[bitbucket]
+# Retries for Bitbucket requests (falls back to general.max_retries)
+# max_retries = 3
+# Cache TTL in minutes (falls back to general.cache_ttl_mins); Bitbucket has
+# no stars, so min_stars never applies to it
+# cache_ttl_mins = 60
+
+[crates]
+# Retries for crates.io requests (falls back to general.max_retries)
+# max_retries = 3
+# Cache TTL in minutes (falls back to general.cache_ttl_mins); crates have no
+# stars, so min_stars never applies to them
+# cache_ttl_mins = 60
+
+# Self-hosted Gitea/Forgejo or GitLab instances, shown as kind@id
+# [[custom_provider]]
+# id = "company"
+# icon = "[CO]"
+# base_url = "https://git.company.internal"
+# kind = "gitlab"
+# token = "glpat-..."
+
+# Override provider icons and their colors, keyed by provider id
+# (instances such as gitea@codeberg.org also match their kind)
+# [icons]
+# github = "\uf09b"
+# gitlab = { icon = "\uf296", color = "bright red" }
+# "gitlab@company" = { color = "cyan" }
+"#;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,7 +1680,9 @@ mod tests {
         assert!(config.providers.github);
         assert!(config.providers.gitlab);
         assert!(config.providers.gitea);
-        assert_eq!(config.gitea.base_url, "https://gitea.com");
+        assert_eq!(config.gitea.base_urls, vec!["https://gitea.com"]);
+        assert_eq!(config.github.html_concurrency, 2);
+        assert_eq!(config.github.api_concurrency, 4);
     }
 
     #[test]
@@ -340,7 +1690,7 @@ mod tests {
         let mut config = Config::default();
         config.providers.gitlab = false;
         let enabled = config.enabled_providers();
-        assert_eq!(enabled, vec!["github", "gitea"]);
+        assert_eq!(enabled, vec!["github", "gitea", "codeberg"]);
     }
 
     #[test]
@@ -366,7 +1716,33 @@ mod tests {
         assert_eq!(config.general.timeout_secs, 10);
         assert_eq!(config.general.language_filter, vec!["rust", "go"]);
         assert!(!config.providers.gitlab);
-        assert_eq!(config.gitea.base_url, "https://codeberg.org");
+        assert_eq!(config.gitea.base_urls, vec!["https://codeberg.org"]);
+    }
+
+    #[test]
+    fn test_star_separator_parsing() {
+        let config: Config = toml::from_str("[general]\nstar_separator = \"comma\"").unwrap();
+        assert_eq!(config.general.star_separator, StarSeparator::Comma);
+        assert_eq!(
+            Config::default().general.star_separator,
+            StarSeparator::None
+        );
+        assert_eq!(StarSeparator::Space.resolve(), Some(' '));
+    }
+
+    #[test]
+    fn test_min_stars_u64() {
+        // Same type as `Repo::stars_total`, so values past u32::MAX parse
+        let config: Config = toml::from_str("[general]\nmin_stars = 5000000000").unwrap();
+        assert_eq!(config.general.min_stars, Some(5_000_000_000));
+    }
+
+    #[test]
+    fn test_locale_separator() {
+        assert_eq!(locale_separator("en_US.UTF-8"), ',');
+        assert_eq!(locale_separator("de_DE.UTF-8"), '.');
+        assert_eq!(locale_separator("fr_FR"), ' ');
+        assert_eq!(locale_separator("C"), ',');
     }
 
     #[test]
@@ -393,20 +1769,447 @@ mod tests {
         assert_eq!(config.get_max_entries("unknown"), 2); // Falls back to default
     }
 
+    #[test]
+    fn test_get_max_retries() {
+        let toml_str = r"
+            [general]
+            max_retries = 2
+
+            [github]
+            max_retries = 5
+
+            [gitea]
+            max_retries = 0
+        ";
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.get_max_retries("github"), 5);
+        assert_eq!(config.get_max_retries("gitlab"), 2); // Falls back to global
+        assert_eq!(config.get_max_retries("gitea"), 0);
+        assert_eq!(Config::default().get_max_retries("github"), 3);
+
+        let mut config = config;
+        config.general.retries_enabled = false;
+        assert_eq!(config.get_max_retries("github"), 0);
+        assert_eq!(config.get_max_retries("gitlab"), 0);
+    }
+
     #[test]
     fn test_config_parsing_with_per_provider_limits() {
-        let toml_str = r#"
+        let toml_str = r"
             [general]
             max_per_provider = 2
             github_max_entries = 3
             gitlab_max_entries = 1
             gitea_max_entries = 1
-        "#;
+        ";
 
         let config: Config = toml::from_str(toml_str).unwrap();
         assert_eq!(config.get_max_entries("github"), 3);
         assert_eq!(config.get_max_entries("gitlab"), 1);
         assert_eq!(config.get_max_entries("gitea"), 1);
     }
+
+    #[test]
+    fn test_provenance_tracks_changes() {
+        let mut config = Config::default();
+        config
+            .provenance
+            .insert("general.sort".to_string(), Source::File);
+        let before = config.clone();
+        config.general.min_stars = Some(10);
+        config.mark_changed_since(&before, Source::Cli);
+
+        let map = config.provenance_map();
+        assert_eq!(map.get("general.min_stars"), Some(&Source::Cli));
+        assert_eq!(map.get("general.sort"), Some(&Source::File));
+        assert_eq!(map.get("general.max_per_provider"), Some(&Source::Default));
+        assert_eq!(map.get("general.language_filter"), Some(&Source::Default));
+        assert!(!map.contains_key("general"));
+    }
+
+    #[test]
+    fn test_redacted_masks_tokens() {
+        let mut config = Config::default();
+        config.auth.github_token = Some("secret".to_string());
+        let redacted = config.redacted();
+        assert_eq!(redacted.auth.github_token.as_deref(), Some("***"));
+        assert_eq!(redacted.auth.gitlab_token, None);
+    }
+
+    #[test]
+    fn test_sample_config_matches_defaults() {
+        let sample: Config = toml::from_str(SAMPLE_CONFIG).unwrap();
+        assert_eq!(sample.flatten(), Config::default().flatten());
+    }
+
+    #[test]
+    fn test_sample_config_documents_every_key() {
+        for key in Config::default().flatten().keys() {
+            let field = key.rsplit('.').next().unwrap();
+            let documented = SAMPLE_CONFIG.contains(&format!("\n{field} ="))
+                || SAMPLE_CONFIG.contains(&format!("\n# {field} ="));
+            assert!(documented, "{key} missing from SAMPLE_CONFIG");
+        }
+    }
+
+    #[test]
+    fn test_include_topics_alias() {
+        let config: Config = toml::from_str("[github]\ninclude_topics = [\"cli\"]").unwrap();
+        assert_eq!(config.github.topics_any, vec!["cli"]);
+    }
+
+    #[test]
+    fn test_unknown_keys_reported() {
+        let (config, unknown) =
+            parse_toml::<Config>("[general]\nmax_per_provder = 3\n[gitub]\nsince = \"weekly\"")
+                .unwrap();
+        assert_eq!(unknown, ["general.max_per_provder", "gitub"]);
+        assert_eq!(config.general.max_per_provider, default_max_per_provider());
+
+        // Warn by default, fail naming the file and key in strict mode
+        let path = Path::new("/etc/xdg/trotd/trotd.toml");
+        assert!(report_unknown_keys(path, &unknown, false).is_ok());
+        let err = report_unknown_keys(path, &unknown, true)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("/etc/xdg/trotd/trotd.toml"), "{err}");
+        assert!(err.contains("general.max_per_provder"), "{err}");
+
+        // Aliases and free-form tables are not unknown
+        let (config, unknown) = parse_toml::<Config>(
+            "[gitea]\nbase_url = \"https://codeberg.org\"\n[general.extra_headers]\nX-Team = \"a\"",
+        )
+        .unwrap();
+        assert!(unknown.is_empty(), "{unknown:?}");
+        assert_eq!(config.gitea.base_urls, vec!["https://codeberg.org"]);
+    }
+
+    #[test]
+    fn test_check_env_tokens() {
+        let mut config = Config::default();
+        let env = |name: &str| (name == "TROTD_GITHUB_TOKENS").then(|| "ghp_a".to_string());
+        let no_env = |_: &str| None;
+        let both = ["github", "gitlab"];
+
+        // Off by default
+        assert!(config.check_env_tokens(&both, no_env).is_ok());
+
+        config.auth.require_env_tokens = true;
+        config.auth.gitlab_token = Some("from-file".to_string());
+        let err = config.check_env_tokens(&both, env).unwrap_err().to_string();
+        assert!(err.contains("TROTD_GITLAB_TOKEN"));
+        assert!(!err.contains("TROTD_GITHUB_TOKEN"));
+        assert!(!err.contains("TROTD_GITEA_TOKEN"));
+
+        // e.g. `--provider gh`: providers enabled by default but not queried don't count
+        assert!(config.check_env_tokens(&["github"], env).is_ok());
+        assert!(config.check_env_tokens(&["github"], no_env).is_err());
+    }
+
+    #[test]
+    fn test_validate_ranges() {
+        assert!(Config::default().validate().is_ok());
+
+        let invalid = |edit: fn(&mut Config), key: &str| {
+            let mut config = Config::default();
+            edit(&mut config);
+            let err = config.validate().unwrap_err().to_string();
+            assert!(err.contains(key), "{err}");
+        };
+        invalid(|c| c.general.timeout_secs = 0, "general.timeout_secs");
+        invalid(
+            |c| c.general.github_timeout_secs = 0,
+            "general.github_timeout_secs",
+        );
+        invalid(|c| c.general.max_concurrency = 0, "general.max_concurrency");
+        invalid(
+            |c| c.general.deadline_secs = Some(0),
+            "general.deadline_secs",
+        );
+        invalid(
+            |c| c.general.cache_ttl_mins = 60 * 24 * 30,
+            "general.cache_ttl_mins",
+        );
+        invalid(
+            |c| c.general.max_per_provider = 101,
+            "general.max_per_provider",
+        );
+        invalid(
+            |c| c.general.max_per_provider = 0,
+            "general.max_per_provider",
+        );
+        invalid(
+            |c| c.general.gitea_max_entries = Some(500),
+            "general.gitea_max_entries",
+        );
+        invalid(
+            |c| c.general.allowed_scripts = vec!["Klingon".to_string()],
+            "han, other",
+        );
+
+        let mut config = Config::default();
+        config.general.allowed_scripts = vec!["Han".to_string(), " cyrillic".to_string()];
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_icon_overrides() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [icons]
+            github = "GH!"
+            gitea = { icon = "tea", color = "bright green" }
+            "gitlab@company" = { color = "cyan" }
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(
+            config.icon_override("github", "[GH]").as_deref(),
+            Some("GH!")
+        );
+        assert_eq!(
+            config
+                .icon_override("gitea@codeberg.org", "[GE]@codeberg.org")
+                .as_deref(),
+            Some("tea@codeberg.org")
+        );
+        // Custom providers keep their configured icon unless overridden by id
+        assert_eq!(config.icon_override("gitea@ops", "[OP]"), None);
+        assert_eq!(config.icon_override("gitlab@company", "[CO]"), None);
+        assert_eq!(config.icon_override("bitbucket", "[BB]"), None);
+
+        let colors = config.icon_colors();
+        assert_eq!(colors.get("gitea"), Some(&colored::Color::BrightGreen));
+        assert_eq!(colors.get("gitlab@company"), Some(&colored::Color::Cyan));
+        assert!(!colors.contains_key("github"));
+
+        config.icons.get_mut("github").unwrap().color = Some("mauve".to_string());
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("icons.github"), "{err}");
+
+        assert!(toml::from_str::<Config>("[icons]\ngithub = { glyph = \"x\" }").is_err());
+    }
+
+    #[test]
+    fn test_custom_providers() {
+        let toml_str = r#"
+            [[custom_provider]]
+            id = "company"
+            icon = "[CO]"
+            base_url = "https://git.company.internal"
+            kind = "gitlab"
+            token = "secret"
+
+            [[custom_provider]]
+            id = "lab"
+            icon = "[LB]"
+            base_url = "http://10.0.0.5:3000"
+            kind = "gitea"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.custom_providers.len(), 2);
+        assert_eq!(config.custom_providers[0].provider_id(), "gitlab@company");
+        assert_eq!(config.custom_providers[1].kind, CustomProviderKind::Gitea);
+        assert_eq!(config.custom_providers[1].token, None);
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.redacted().custom_providers[0].token.as_deref(),
+            Some("***")
+        );
+
+        let unknown_kind = toml_str.replace("\"gitea\"", "\"bitbucket\"");
+        assert!(toml::from_str::<Config>(&unknown_kind).is_err());
+
+        let mut config = config;
+        config.custom_providers[1].id = "a@b".to_string();
+        assert!(config.validate().is_err());
+        config.custom_providers[1].id = "company".to_string();
+        config.custom_providers[1].kind = CustomProviderKind::Gitlab;
+        assert!(config.validate().unwrap_err().to_string().contains("twice"));
+    }
+
+    #[test]
+    fn test_add_providers_file() {
+        let dir = std::env::temp_dir().join(format!("trotd-test-providers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let json = dir.join("forges.json");
+        std::fs::write(
+            &json,
+            r#"[{"id": "ops", "icon": "[OP]", "base_url": "https://git.ops.internal", "kind": "gitea"}]"#,
+        )
+        .unwrap();
+        let toml_path = dir.join("forges.toml");
+        std::fs::write(
+            &toml_path,
+            "[[custom_provider]]\nid = \"ops\"\nicon = \"[O2]\"\nbase_url = \"https://gl.ops\"\nkind = \"gitlab\"\n",
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        config.add_providers_file(&json).unwrap();
+        assert_eq!(config.custom_providers[0].provider_id(), "gitea@ops");
+
+        let err = config
+            .add_providers_file(&toml_path)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("\"ops\"") && err.contains("already defined"),
+            "{err}"
+        );
+
+        let mut config = Config::default();
+        config.add_providers_file(&toml_path).unwrap();
+        assert_eq!(config.custom_providers[0].kind, CustomProviderKind::Gitlab);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("24h").unwrap(), chrono::Duration::hours(24));
+        assert_eq!(parse_duration(" 7d ").unwrap(), chrono::Duration::days(7));
+        assert_eq!(parse_duration("2w").unwrap(), chrono::Duration::weeks(2));
+        for bad in ["", "d", "7", "7m", "-1d", "1.5d"] {
+            assert!(parse_duration(bad).is_err(), "{bad}");
+        }
+
+        let mut config = Config::default();
+        config.general.min_activity = Some("3x".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_detect_language_alias() {
+        let config: Config = toml::from_str("[gitlab]\ndetect_language = true").unwrap();
+        assert!(config.gitlab.use_languages_api);
+    }
+
+    #[test]
+    fn test_gitea_base_urls() {
+        let toml_str = r#"
+            [gitea]
+            base_urls = ["https://codeberg.org", "https://git.example.com/"]
+
+            [auth]
+            gitea_token = "main"
+
+            [auth.gitea_tokens]
+            "git.example.com" = "own"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.gitea_base_url(), "https://codeberg.org");
+        assert_eq!(
+            config.gitea_token_for("https://codeberg.org").as_deref(),
+            Some("main")
+        );
+        assert_eq!(
+            config.gitea_token_for("https://git.example.com").as_deref(),
+            Some("own")
+        );
+        assert_eq!(config.gitea_token_for("https://gitea.com"), None);
+        // Never over plain http, nor to a host that only resembles a configured one
+        assert_eq!(config.gitea_token_for("http://git.example.com"), None);
+        assert_eq!(config.gitea_token_for("http://codeberg.org"), None);
+        assert_eq!(
+            config.gitea_token_for("https://git.example.com.evil.net"),
+            None
+        );
+
+        let config: Config = toml::from_str("[gitea]\nbase_urls = []").unwrap();
+        assert_eq!(config.gitea_base_url(), "https://gitea.com");
+    }
+
+    #[test]
+    fn test_get_min_stars() {
+        let mut config = Config::default();
+        assert_eq!(config.get_min_stars("github"), None);
+        config.general.min_stars = Some(50);
+        config.general.gitea_min_stars = Some(5);
+        assert_eq!(config.get_min_stars("gitea"), Some(5));
+        assert_eq!(config.get_min_stars("github"), Some(50));
+        assert_eq!(config.get_min_stars("codeberg"), Some(50));
+        config.codeberg.min_stars = Some(10);
+        assert_eq!(config.get_min_stars("codeberg"), Some(10));
+        // No stars to compare against
+        assert_eq!(config.get_min_stars("bitbucket"), None);
+        assert_eq!(config.get_min_stars("crates"), None);
+    }
+
+    #[test]
+    fn test_get_cache_ttl_mins() {
+        let mut config = Config::default();
+        config.general.cache_ttl_mins = 45;
+        config.general.github_cache_ttl_mins = Some(180);
+        assert_eq!(config.get_cache_ttl_mins("github"), 180);
+        assert_eq!(config.get_cache_ttl_mins("gitlab"), 45);
+        assert_eq!(config.get_cache_ttl_mins("codeberg"), 45);
+        config.crates.cache_ttl_mins = Some(720);
+        assert_eq!(config.get_cache_ttl_mins("crates"), 720);
+    }
+
+    #[test]
+    fn test_github_token_pool() {
+        let mut config = Config::default();
+        assert!(config.github_token_pool().is_empty());
+
+        config.auth.github_token = Some("a".to_string());
+        config.auth.github_tokens = vec!["b".to_string(), "a".to_string(), "c".to_string()];
+        assert_eq!(config.github_token_pool(), vec!["a", "b", "c"]);
+
+        config.auth.github_token = None;
+        assert_eq!(config.github_token_pool(), vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_should_write_default() {
+        let system = PathBuf::from("/etc/xdg/trotd/trotd.toml");
+        assert!(should_write_default(&[], true, false));
+        // A system-wide file alone is enough to skip the default user file
+        assert!(!should_write_default(&[system], true, false));
+        assert!(!should_write_default(&[], false, false));
+        assert!(!should_write_default(&[], true, true));
+    }
+
+    #[test]
+    fn test_system_config_paths() {
+        let path = |dir: &str| PathBuf::from(dir).join("trotd").join("trotd.toml");
+        assert_eq!(system_config_paths(None), vec![path("/etc/xdg")]);
+        assert_eq!(system_config_paths(Some("")), vec![path("/etc/xdg")]);
+        // Most important directory last, so it is merged on top
+        assert_eq!(
+            system_config_paths(Some("/opt/xdg::/etc/xdg")),
+            vec![path("/etc/xdg"), path("/opt/xdg")]
+        );
+    }
+
+    #[test]
+    fn test_merge_toml_user_wins() {
+        let system = r#"
+[general]
+max_per_provider = 5
+language_filter = ["go"]
+
+[providers]
+gitea = false
+"#;
+        let user = r#"
+[general]
+max_per_provider = 9
+language_filter = ["rust"]
+"#;
+        let mut merged: toml::Value = toml::from_str(system).unwrap();
+        let user: toml::Value = toml::from_str(user).unwrap();
+        merge_toml(&mut merged, user);
+
+        let config: Config = merged.try_into().unwrap();
+        assert_eq!(config.general.max_per_provider, 9);
+        assert_eq!(config.general.language_filter, vec!["rust"]);
+        // System keys the user didn't set survive
+        assert!(!config.providers.gitea);
+    }
 }
 
@@ -0,0 +1,1737 @@
+use anyhow::{Context, Result};
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::http::HttpClient;
+use crate::model::{LanguageFilter, Provider, ProviderCfg, Repo, Since};
+use crate::providers::{Bitbucket, Codeberg, CratesIo, GitHub, GitLab, Gitea};
+use crate::script::ScriptFilter;
+
+/// Per-run options that don't belong in `Config`
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// Provider ids to query (`None` uses `Config::enabled_providers`)
+    pub providers: Option<Vec<String>>,
+    /// Read from and write to the filesystem cache
+    pub use_cache: bool,
+    /// Skip cache reads but still write fresh results (ignored without `use_cache`)
+    pub refresh: bool,
+    /// Log progress to stderr
+    pub verbose: bool,
+    /// Extra GitLab/Gitea instances to query (see `load_instances`)
+    pub instances: Vec<Instance>,
+    /// Cutoff for the whole command (see `deadline_after`); `None` starts
+    /// `general.deadline_secs` when the fetch begins
+    pub deadline: Option<tokio::time::Instant>,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            providers: None,
+            use_cache: true,
+            refresh: false,
+            verbose: false,
+            instances: vec![],
+            deadline: None,
+        }
+    }
+}
+
+/// An extra GitLab or Gitea instance discovered from `general.instances_url`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Instance {
+    /// Provider kind: `gitlab` or `gitea`
+    pub kind: String,
+    /// Base URL, e.g. `https://codeberg.org`
+    pub url: String,
+}
+
+impl Instance {
+    /// Provider id used for caching and messages, e.g. `gitea@codeberg.org`
+    pub fn id(&self) -> String {
+        let host = self.url.split("://").nth(1).unwrap_or(&self.url);
+        format!(
+            "{}@{}",
+            self.kind,
+            host.trim_end_matches('/').replace('/', "_")
+        )
+    }
+}
+
+/// Entry in a published instance list: a bare URL (Gitea) or `{kind, url}`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum InstanceSpec {
+    Url(String),
+    Full { kind: String, url: String },
+}
+
+impl From<InstanceSpec> for Instance {
+    fn from(spec: InstanceSpec) -> Self {
+        match spec {
+            InstanceSpec::Url(url) => Self {
+                kind: "gitea".to_string(),
+                url,
+            },
+            InstanceSpec::Full { kind, url } => Self {
+                kind: kind.to_lowercase(),
+                url,
+            },
+        }
+    }
+}
+
+/// Where a provider other than a built-in default is queried, and how it is shown
+#[derive(Debug, Clone)]
+struct Target {
+    url: String,
+    token: Option<String>,
+    /// Icon from `[[custom_provider]]`; otherwise the kind's icon plus `@host`
+    icon: Option<String>,
+}
+
+/// Result of a fetch run: filtered repositories plus per-provider failures
+#[derive(Debug, Default)]
+pub struct FetchOutcome {
+    pub repos: Vec<Repo>,
+    pub errors: Vec<anyhow::Error>,
+    /// Providers that succeeded but returned no repositories
+    pub empty_providers: Vec<String>,
+    /// Providers whose fetch failed and were served from expired cache data
+    pub stale: Vec<StaleData>,
+    /// Number of repositories fetched before filtering
+    fetched: usize,
+    /// Background refetches of expired cache entries (`general.stale_while_revalidate`)
+    pub revalidations: Vec<tokio::task::JoinHandle<()>>,
+}
+
+/// Expired cache data shown because the live fetch failed
+#[derive(Debug)]
+pub struct StaleData {
+    pub provider: String,
+    /// Age of the cached data in seconds
+    pub age_secs: u64,
+    /// Why the live fetch failed
+    pub error: anyhow::Error,
+}
+
+/// One provider's id, repos, and stale-fallback details
+type ProviderResult = (String, Vec<Repo>, Option<StaleData>);
+
+impl FetchOutcome {
+    /// True when nothing was fetched and at least one provider failed
+    pub fn all_failed(&self) -> bool {
+        self.fetched == 0 && !self.errors.is_empty()
+    }
+
+    /// Record one provider's result, returning its repos if it had any
+    ///
+    /// `Ok(None)` is a provider skipped in offline mode for lack of cached data.
+    fn record(
+        &mut self,
+        result: Result<Option<ProviderResult>>,
+        verbose: bool,
+    ) -> Option<Vec<Repo>> {
+        match result {
+            Ok(None) => None,
+            Ok(Some((provider_id, repos, stale))) => {
+                if verbose {
+                    eprintln!("  📦 {}: {} repos", provider_id, repos.len());
+                }
+                if let Some(stale) = stale {
+                    self.stale.push(stale);
+                }
+                if repos.is_empty() {
+                    self.empty_providers.push(provider_id);
+                    None
+                } else {
+                    Some(repos)
+                }
+            }
+            Err(e) => {
+                if verbose {
+                    eprintln!("  ✗ Provider error: {e}");
+                }
+                self.errors.push(e);
+                None
+            }
+        }
+    }
+
+    /// Count providers cut off by `general.deadline_secs` as failed
+    fn record_unfinished(
+        &mut self,
+        pending: Vec<Option<String>>,
+        deadline_secs: Option<u64>,
+        verbose: bool,
+    ) {
+        let unfinished: Vec<String> = pending.into_iter().flatten().collect();
+        if unfinished.is_empty() {
+            return;
+        }
+        let secs = deadline_secs.unwrap_or_default();
+        if verbose {
+            eprintln!(
+                "  ⏱ Deadline of {secs}s reached; unfinished: {}",
+                unfinished.join(", ")
+            );
+        }
+        for provider_id in unfinished {
+            self.errors.push(anyhow::anyhow!(
+                "{provider_id}: no response within the {secs}s deadline"
+            ));
+        }
+    }
+}
+
+/// Fetch trending repositories from all enabled providers
+///
+/// This is the simple entry point for embedding trotd: it uses the cache,
+/// queries every provider enabled in `config`, applies the configured
+/// filters, and fails only if every provider failed.
+pub async fn fetch_trending(config: &Config) -> Result<Vec<Repo>> {
+    let outcome = fetch(config, &FetchOptions::default()).await?;
+
+    if outcome.all_failed() {
+        let reasons: Vec<String> = outcome.errors.iter().map(ToString::to_string).collect();
+        anyhow::bail!("All providers failed: {}", reasons.join("; "));
+    }
+
+    Ok(outcome.repos)
+}
+
+/// Fetch repositories with explicit run options, keeping partial failures
+pub async fn fetch(config: &Config, options: &FetchOptions) -> Result<FetchOutcome> {
+    fetch_with(config, options, None).await
+}
+
+/// Fetch repositories, handing each provider's results to `sink` as soon as
+/// that provider completes
+///
+/// Repos arrive in completion order and are filtered but not sorted, since
+/// sorting needs the full set. The returned outcome carries errors, stale
+/// and empty providers, but no repos.
+pub async fn fetch_streaming(
+    config: &Config,
+    options: &FetchOptions,
+    sink: &mut (dyn FnMut(Vec<Repo>) + Send),
+) -> Result<FetchOutcome> {
+    fetch_with(config, options, Some(sink)).await
+}
+
+async fn fetch_with(
+    config: &Config,
+    options: &FetchOptions,
+    sink: Option<&mut (dyn FnMut(Vec<Repo>) + Send)>,
+) -> Result<FetchOutcome> {
+    fetch_from(config, options, open_cache(config, options), sink).await
+}
+
+/// The cache for this run, or `None` when disabled or unusable
+fn open_cache(config: &Config, options: &FetchOptions) -> Option<Cache> {
+    let verbose = options.verbose;
+    if options.use_cache {
+        match Cache::for_config(config) {
+            Ok(c) => {
+                if verbose {
+                    eprintln!(
+                        "💾 Cache initialized (TTL: {} mins)",
+                        config.general.cache_ttl_mins
+                    );
+                }
+                Some(c)
+            }
+            Err(e) => {
+                // Unusable cache: warn once and run uncached rather than fail every write
+                eprintln!("⚠ {e}; caching disabled for this run");
+                None
+            }
+        }
+    } else {
+        if verbose {
+            eprintln!("🚫 Cache disabled");
+        }
+        None
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+async fn fetch_from(
+    config: &Config,
+    options: &FetchOptions,
+    cache: Option<Cache>,
+    sink: Option<&mut (dyn FnMut(Vec<Repo>) + Send)>,
+) -> Result<FetchOutcome> {
+    let verbose = options.verbose;
+
+    // Determine enabled providers
+    let enabled_providers = match options.providers {
+        Some(ref providers) => providers.iter().map(String::as_str).collect(),
+        None => config.enabled_providers(),
+    };
+
+    if verbose {
+        eprintln!("🔌 Enabled providers: {enabled_providers:?}");
+    }
+
+    let mut provider_instances: Vec<(String, Box<dyn Provider>, Option<Target>)> =
+        build_providers(config, &enabled_providers, verbose)
+            .into_iter()
+            .map(|(id, provider)| (id, provider, None))
+            .collect();
+
+    // Further configured Gitea instances, then discovered ones, limited to
+    // the selected provider kinds. Only configured ones get a token: the
+    // instance list is untrusted.
+    let configured = config.gitea.base_urls.iter().skip(1).map(|url| {
+        let instance = Instance {
+            kind: "gitea".to_string(),
+            url: url.trim_end_matches('/').to_string(),
+        };
+        (instance, true)
+    });
+    let discovered = options
+        .instances
+        .iter()
+        .map(|instance| (instance.clone(), false));
+    for (instance, trusted) in configured.chain(discovered) {
+        if !enabled_providers.contains(&instance.kind.as_str())
+            || provider_instances
+                .iter()
+                .any(|(id, _, _)| *id == instance.id())
+        {
+            continue;
+        }
+        if let Some(provider) = build_provider(config, &instance.kind, verbose) {
+            // Only send a token configured for this very host
+            let token = if trusted {
+                config.gitea_token_for(&instance.url)
+            } else {
+                None
+            };
+            let target = Target {
+                url: instance.url.clone(),
+                token,
+                icon: None,
+            };
+            provider_instances.push((instance.id(), provider, Some(target)));
+        }
+    }
+
+    // `[[custom_provider]]` entries are always queried, unless `--provider`
+    // selects other kinds
+    for custom in &config.custom_providers {
+        let kind = custom.kind.as_str();
+        if options
+            .providers
+            .as_ref()
+            .is_some_and(|p| !p.iter().any(|p| p == kind))
+        {
+            continue;
+        }
+        if let Some(provider) = build_provider(config, kind, verbose) {
+            let target = Target {
+                url: custom.base_url.trim_end_matches('/').to_string(),
+                token: custom.token.clone(),
+                icon: Some(custom.icon.clone()),
+            };
+            provider_instances.push((custom.provider_id(), provider, Some(target)));
+        }
+    }
+
+    if provider_instances.is_empty() {
+        anyhow::bail!("No providers enabled or available");
+    }
+
+    // Create language filter
+    let lang_filter = LanguageFilter::new(config.general.language_filter.clone())
+        .excluding(config.general.exclude_languages.clone());
+
+    if verbose {
+        if config.general.language_filter.is_empty() {
+            eprintln!("🌐 Language filter: all languages");
+        } else {
+            eprintln!("🌐 Language filter: {:?}", config.general.language_filter);
+        }
+        if !config.general.exclude_languages.is_empty() {
+            eprintln!(
+                "🚫 Excluded languages: {:?}",
+                config.general.exclude_languages
+            );
+        }
+        eprintln!("🚀 Fetching repositories...");
+    }
+
+    // Providers still running when the deadline passes, by index
+    let mut pending: Vec<Option<String>> = provider_instances
+        .iter()
+        .map(|(id, _, _)| Some(id.clone()))
+        .collect();
+
+    // Fetch repositories in parallel
+    let cache_arc = Arc::new(cache);
+    let revalidations = Arc::new(Mutex::new(Vec::new()));
+    // Cap in-flight fetches so many instances don't trip rate limits at once
+    let permits = Arc::new(Semaphore::new(config.general.max_concurrency.max(1)));
+    let mut futures = FuturesUnordered::new();
+
+    for (index, (provider_id, provider, target)) in provider_instances.into_iter().enumerate() {
+        let provider: Arc<dyn Provider> = Arc::from(provider);
+        let cache_ref = Arc::clone(&cache_arc);
+        let revalidations_ref = Arc::clone(&revalidations);
+        let permits_ref = Arc::clone(&permits);
+        let lang_filter_clone = lang_filter.clone();
+        let config_clone = config.clone();
+        let verbose_clone = verbose;
+        let refresh = options.refresh;
+
+        let future = async move {
+            let kind = provider.id();
+            let mut provider_cfg = provider_cfg(&config_clone, kind, verbose_clone);
+            if let Some(ref target) = target {
+                provider_cfg.token.clone_from(&target.token);
+                provider_cfg.tokens.clear();
+                provider_cfg.base_url = Some(target.url.clone());
+            }
+
+            let cache_key = cache_key(&provider_id, &provider_cfg);
+
+            // Icons are a display choice, so re-resolve them for cached entries
+            let icon = provider_cfg.icons.resolve(kind, provider.icon());
+            // Tell instances of one kind apart, e.g. `[GE]@codeberg.org`
+            let instance_icon = target.map(|target| {
+                target.icon.unwrap_or_else(|| {
+                    let host = provider_id.split_once('@').map_or("", |(_, host)| host);
+                    format!("{icon}@{host}")
+                })
+            });
+
+            // Offline: any cached data will do, and uncached providers are skipped
+            if config_clone.general.offline {
+                let cached = match *cache_ref {
+                    Some(ref cache) => cache.get_stale(&cache_key).await,
+                    None => None,
+                };
+                let Some((cached_repos, age_secs)) = cached else {
+                    if verbose_clone {
+                        eprintln!("  📴 {provider_id}: nothing cached, skipped");
+                    }
+                    return Ok(None);
+                };
+                if verbose_clone {
+                    eprintln!("  💾 {provider_id} (offline, {age_secs}s old)");
+                }
+                let mut repos = mark_cached(cached_repos, icon);
+                if let Some(ref instance_icon) = instance_icon {
+                    label_instance(&mut repos, &provider_id, instance_icon);
+                }
+                return Ok(Some((provider_id, repos, None)));
+            }
+
+            // Try cache first, unless refreshing
+            let mut cached = match *cache_ref {
+                Some(ref cache) if !refresh => cache.get(&cache_key).await,
+                _ => None,
+            };
+
+            // Expired: show the old entry now and refetch in the background
+            if cached.is_none() && !refresh && config_clone.general.stale_while_revalidate {
+                if let Some(ref cache) = *cache_ref {
+                    if let Some((stale_repos, age_secs)) = cache.get_stale(&cache_key).await {
+                        if verbose_clone {
+                            eprintln!(
+                                "  🔄 {provider_id} (expired, {age_secs}s old): revalidating"
+                            );
+                        }
+                        let task = tokio::spawn(revalidate(
+                            Arc::clone(&provider),
+                            provider_cfg.clone(),
+                            config_clone.get_max_entries(kind),
+                            lang_filter_clone.clone(),
+                            Arc::clone(&cache_ref),
+                            cache_key.clone(),
+                            Arc::clone(&permits_ref),
+                        ));
+                        lock(&revalidations_ref).push(task);
+                        cached = Some(stale_repos);
+                    }
+                }
+            }
+
+            let mut stale = None;
+            // Held across the network calls, README checks included
+            let mut permit = None;
+
+            // README answers are only reused alongside the list they were made for
+            let list_cached = cached.is_some();
+            let mut repos = if let Some(cached_repos) = cached {
+                if verbose_clone {
+                    eprintln!("  💾 {provider_id} (cached)");
+                }
+                mark_cached(cached_repos, icon)
+            } else {
+                // Fetch from provider, holding a permit for the network calls
+                permit = Some(permits_ref.acquire().await?);
+                if verbose_clone {
+                    eprintln!(
+                        "  🎫 {provider_id}: permit acquired ({} free)",
+                        permits_ref.available_permits()
+                    );
+                }
+                let fetched = provider
+                    .top_today(
+                        &provider_cfg,
+                        config_clone.get_max_entries(kind),
+                        &lang_filter_clone,
+                    )
+                    .await;
+
+                match fetched {
+                    Ok(mut repos) => {
+                        // Compare against the previous entry, then cache the result
+                        if let Some(ref cache) = *cache_ref {
+                            if let Some(previous) = cache.get_previous(&cache_key).await {
+                                apply_stars_delta(&mut repos, &previous);
+                            }
+                            let _ = cache.set(&cache_key, repos.clone()).await;
+                        }
+                        repos
+                    }
+                    Err(e) => {
+                        // Fall back to expired cache data rather than showing nothing
+                        let expired = match *cache_ref {
+                            Some(ref cache) if config_clone.general.stale_fallback => {
+                                cache.get_stale(&cache_key).await
+                            }
+                            _ => None,
+                        };
+                        let Some((cached_repos, age_secs)) = expired else {
+                            return Err(e);
+                        };
+                        if verbose_clone {
+                            eprintln!("  💾 {provider_id} (stale, {age_secs}s old): {e}");
+                        }
+                        stale = Some(StaleData {
+                            provider: provider_id.clone(),
+                            age_secs,
+                            error: e,
+                        });
+                        mark_cached(cached_repos, icon)
+                    }
+                }
+            };
+
+            // Enrichment filters (extra per-repo requests, opt-in via `enrich`);
+            // cached lists take a permit here
+            if config_clone.general.enrich && config_clone.general.require_readme {
+                if permit.is_none() {
+                    permit = Some(permits_ref.acquire().await?);
+                }
+                let checks_key = format!("readme-{cache_key}");
+                let mut known = match *cache_ref {
+                    Some(ref cache) if list_cached => cache
+                        .get_meta(&checks_key, config_clone.get_cache_ttl_mins(kind))
+                        .await
+                        .unwrap_or_default(),
+                    _ => HashMap::new(),
+                };
+                let known_before = known.len();
+                repos = retain_with_readme(
+                    provider.as_ref(),
+                    &provider_cfg,
+                    repos,
+                    &mut known,
+                    verbose_clone,
+                )
+                .await;
+                if let Some(ref cache) = *cache_ref {
+                    if known.len() != known_before {
+                        let _ = cache.set_meta(&checks_key, &known).await;
+                    }
+                }
+            }
+            if permit.take().is_some() && verbose_clone {
+                eprintln!("  🎫 {provider_id}: permit released");
+            }
+
+            if let Some(ref instance_icon) = instance_icon {
+                label_instance(&mut repos, &provider_id, instance_icon);
+            }
+
+            Ok::<_, anyhow::Error>(Some((provider_id, repos, stale)))
+        };
+
+        futures.push(async move { (index, future.await) });
+    }
+
+    let mut outcome = FetchOutcome::default();
+    let deadline = options
+        .deadline
+        .or_else(|| deadline_after(config.general.deadline_secs));
+
+    if let Some(sink) = sink {
+        // Streaming: pass each provider's repos on the moment it finishes
+        while let Some((index, result)) = next_before(&mut futures, deadline).await {
+            pending[index] = None;
+            if let Some(mut repos) = outcome.record(result, verbose) {
+                outcome.fetched += repos.len();
+                retain_filtered(config, &mut repos, verbose);
+                if !repos.is_empty() {
+                    sink(repos);
+                }
+            }
+        }
+        outcome.record_unfinished(pending, config.general.deadline_secs, verbose);
+        outcome.revalidations = std::mem::take(&mut *lock(&revalidations));
+        return Ok(outcome);
+    }
+
+    // Collect results, then restore provider order: cache hits finish first,
+    // so completion order would otherwise vary from run to run
+    let mut results = Vec::new();
+    while let Some(result) = next_before(&mut futures, deadline).await {
+        pending[result.0] = None;
+        results.push(result);
+    }
+    results.sort_by_key(|(index, _)| *index);
+
+    for (_, result) in results {
+        if let Some(repos) = outcome.record(result, verbose) {
+            outcome.repos.extend(repos);
+        }
+    }
+    outcome.record_unfinished(pending, config.general.deadline_secs, verbose);
+
+    outcome.fetched = outcome.repos.len();
+    outcome.revalidations = std::mem::take(&mut *lock(&revalidations));
+
+    apply_filters(config, &mut outcome.repos, verbose);
+
+    Ok(outcome)
+}
+
+/// Cutoff `secs` from now (`general.deadline_secs`)
+///
+/// `None` when unset, or too far in the future to represent, which is the
+/// same as having no deadline.
+pub fn deadline_after(secs: Option<u64>) -> Option<tokio::time::Instant> {
+    let secs = std::time::Duration::from_secs(secs?);
+    tokio::time::Instant::now().checked_add(secs)
+}
+
+/// Run `future` to completion, or cancel it with `None` once `deadline` has passed
+pub async fn before_deadline<F>(
+    deadline: Option<tokio::time::Instant>,
+    future: F,
+) -> Option<F::Output>
+where
+    F: std::future::Future,
+{
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+/// Next finished provider, or `None` once all are done or `deadline` has passed
+///
+/// Dropping the stream afterwards cancels the providers still running.
+async fn next_before<S>(futures: &mut S, deadline: Option<tokio::time::Instant>) -> Option<S::Item>
+where
+    S: futures::Stream + Unpin,
+{
+    before_deadline(deadline, futures.next()).await.flatten()
+}
+
+/// Refetch one provider and overwrite its cache entry
+///
+/// Runs detached from the render, under the same `max_concurrency` permits
+/// as live fetches; failures are only logged under `--verbose`.
+async fn revalidate(
+    provider: Arc<dyn Provider>,
+    cfg: ProviderCfg,
+    limit: usize,
+    langs: LanguageFilter,
+    cache: Arc<Option<Cache>>,
+    cache_key: String,
+    permits: Arc<Semaphore>,
+) {
+    let Some(ref cache) = *cache else {
+        return;
+    };
+    let Ok(_permit) = permits.acquire().await else {
+        return;
+    };
+    match provider.top_today(&cfg, limit, &langs).await {
+        Ok(mut repos) => {
+            if let Some(previous) = cache.get_previous(&cache_key).await {
+                apply_stars_delta(&mut repos, &previous);
+            }
+            let count = repos.len();
+            if let Err(e) = cache.set(&cache_key, repos).await {
+                if cfg.verbose {
+                    eprintln!("  ✗ {cache_key}: failed to update cache: {e}");
+                }
+            } else if cfg.verbose {
+                eprintln!("  🔄 {cache_key}: cache revalidated ({count} repos)");
+            }
+        }
+        Err(e) => {
+            if cfg.verbose {
+                eprintln!("  ✗ {cache_key}: revalidation failed: {e}");
+            }
+        }
+    }
+}
+
+/// Set `stars_delta` from each repo's total stars in the previous fetch
+///
+/// Repos new to the list, or without a total on either side, get no delta.
+fn apply_stars_delta(repos: &mut [Repo], previous: &HashMap<String, u64>) {
+    for repo in repos {
+        let before = previous
+            .get(&repo.name)
+            .and_then(|&n| i64::try_from(n).ok());
+        let now = repo.stars_total.and_then(|n| i64::try_from(n).ok());
+        repo.stars_delta = now.zip(before).map(|(now, before)| now - before);
+    }
+}
+
+/// Lock the revalidation task list, even if a panicking holder poisoned it
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Flag repositories as cache-served and refresh their display icon
+fn mark_cached(mut repos: Vec<Repo>, icon: &str) -> Vec<Repo> {
+    for repo in &mut repos {
+        repo.from_cache = true;
+        repo.icon = icon.to_string();
+    }
+    repos
+}
+
+/// Show an instance's repositories under its own provider id and icon
+fn label_instance(repos: &mut [Repo], provider_id: &str, icon: &str) {
+    for repo in repos {
+        repo.provider = provider_id.to_string();
+        repo.icon = icon.to_string();
+    }
+}
+
+/// Fetch the repositories listed in `general.pinned`, in config order
+///
+/// Pins are always fetched live (one request each, never cached). Entries
+/// that can't be resolved or fetched are returned as errors and skipped.
+/// Offline, there is nothing to show.
+pub async fn fetch_pinned(config: &Config, verbose: bool) -> (Vec<Repo>, Vec<anyhow::Error>) {
+    if config.general.offline {
+        return (vec![], vec![]);
+    }
+
+    let mut errors = Vec::new();
+    let mut pins = Vec::new();
+
+    for spec in &config.general.pinned {
+        match parse_pin(spec, config.gitea_base_url()) {
+            Some(pin) => pins.push(pin),
+            None => errors.push(anyhow::anyhow!("Can't resolve pinned repo: {spec}")),
+        }
+    }
+
+    let mut ids: Vec<&str> = pins.iter().map(|(id, _)| *id).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let providers = build_providers(config, &ids, verbose);
+
+    let lookups = pins.iter().map(|(provider_id, name)| {
+        let provider = providers.iter().find(|(id, _)| id == provider_id);
+        async move {
+            let Some((_, provider)) = provider else {
+                anyhow::bail!("Provider unavailable for pinned repo: {name}");
+            };
+            let cfg = provider_cfg(config, provider_id, verbose);
+            let mut repo = provider
+                .fetch_one(&cfg, name)
+                .await
+                .with_context(|| format!("Failed to fetch pinned repo {name}"))?;
+            repo.pinned = true;
+            Ok(repo)
+        }
+    });
+
+    let mut repos = Vec::new();
+    for result in futures::future::join_all(lookups).await {
+        match result {
+            Ok(repo) => repos.push(repo),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if verbose {
+        eprintln!("📌 Pinned: {} repos", repos.len());
+    }
+
+    (repos, errors)
+}
+
+/// Put pinned repos first, dropping their duplicates from the trending list
+pub fn merge_pinned(pinned: Vec<Repo>, trending: Vec<Repo>) -> Vec<Repo> {
+    let is_pinned = |repo: &Repo| {
+        pinned
+            .iter()
+            .any(|p| p.provider == repo.provider && p.name.eq_ignore_ascii_case(&repo.name))
+    };
+    let trending: Vec<Repo> = trending.into_iter().filter(|r| !is_pinned(r)).collect();
+
+    let mut merged = pinned;
+    merged.extend(trending);
+    merged
+}
+
+/// Collapse the same repository listed by several providers (e.g. GitHub mirrored on Codeberg)
+///
+/// Entries match on owner plus final path segment, case-insensitively. The
+/// one with more total stars wins and takes the earlier position; it keeps
+/// the union of topics and falls back to the other's description.
+pub fn dedupe_repos(repos: Vec<Repo>) -> Vec<Repo> {
+    let key = |repo: &Repo| {
+        let name = repo.name.to_lowercase();
+        let (owner, _) = name.split_once('/').unwrap_or(("", &name));
+        let last = name.rsplit('/').next().unwrap_or(&name);
+        format!("{owner}/{last}")
+    };
+
+    let mut merged: Vec<Repo> = Vec::with_capacity(repos.len());
+    for repo in repos {
+        let Some(existing) = merged.iter_mut().find(|r| key(r) == key(&repo)) else {
+            merged.push(repo);
+            continue;
+        };
+
+        let loser = if repo.stars_total > existing.stars_total {
+            std::mem::replace(existing, repo)
+        } else {
+            repo
+        };
+        for topic in loser.topics {
+            if !existing.topics.contains(&topic) {
+                existing.topics.push(topic);
+            }
+        }
+        if existing.description.is_none() {
+            existing.description = loser.description;
+        }
+    }
+    merged
+}
+
+/// Resolve a pin spec to a provider id and `owner/name`
+///
+/// Bare `owner/name` means GitHub; full URLs are matched by host against
+/// github.com, gitlab.com and the configured Gitea base URL.
+fn parse_pin(spec: &str, gitea_base_url: &str) -> Option<(&'static str, String)> {
+    let spec = spec.trim();
+
+    let Some(rest) = spec
+        .strip_prefix("https://")
+        .or_else(|| spec.strip_prefix("http://"))
+    else {
+        return spec.contains('/').then(|| ("github", spec.to_string()));
+    };
+
+    let (host, path) = rest.split_once('/')?;
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    if !path.contains('/') {
+        return None;
+    }
+
+    let gitea_host = gitea_base_url
+        .split("://")
+        .nth(1)
+        .unwrap_or(gitea_base_url)
+        .trim_end_matches('/');
+
+    let provider = match host {
+        "github.com" => "github",
+        "gitlab.com" => "gitlab",
+        h if h.eq_ignore_ascii_case(gitea_host) => "gitea",
+        h if h.eq_ignore_ascii_case("codeberg.org") => "codeberg",
+        _ => return None,
+    };
+
+    Some((provider, path.to_string()))
+}
+
+/// Cache key for one provider's list under its current settings
+///
+/// Other trending windows and spoken languages are cached separately from the
+/// default daily list, e.g. `github-weekly-en`. Provider-side filters add a
+/// short hash of their values, so changing them refetches instead of serving
+/// a list built under different settings; the defaults keep the bare key.
+fn cache_key(provider_id: &str, cfg: &ProviderCfg) -> String {
+    let mut key = match cfg.since {
+        Since::Daily => provider_id.to_string(),
+        since => format!("{provider_id}-{}", since.as_str()),
+    };
+    if let Some(code) = &cfg.spoken_language {
+        key = format!("{key}-{code}");
+    }
+
+    let mut filters = Vec::new();
+    for (name, topics) in [
+        ("topics_all", &cfg.topics_all),
+        ("topics_any", &cfg.topics_any),
+        ("exclude_topics", &cfg.exclude_topics),
+    ] {
+        if !topics.is_empty() {
+            filters.push(format!("{name}={}", topics.join(",")));
+        }
+    }
+    if cfg.merge_windows {
+        filters.push("merge_windows".to_string());
+    }
+    if cfg.use_graphql {
+        filters.push("use_graphql".to_string());
+    }
+    if cfg.use_languages_api {
+        filters.push("use_languages_api".to_string());
+    }
+    if let Some(min) = cfg.min_recent_commits {
+        filters.push(format!("min_recent_commits={min}"));
+    }
+    if !filters.is_empty() {
+        key = format!("{key}-f{:08x}", fnv1a(filters.join(";").as_bytes()));
+    }
+    key
+}
+
+/// 32-bit FNV-1a, stable across builds unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Build the per-provider config from the global config
+fn provider_cfg(config: &Config, provider_id: &str, verbose: bool) -> ProviderCfg {
+    ProviderCfg {
+        timeout_secs: config.general.timeout_secs,
+        token: match provider_id {
+            "github" => config.github_token_pool().into_iter().next(),
+            "gitlab" => config.auth.gitlab_token.clone(),
+            "gitea" => config.gitea_token_for(config.gitea_base_url()),
+            // Only its own host entry: the primary Gitea token never goes to Codeberg
+            "codeberg" => config
+                .auth
+                .gitea_tokens
+                .iter()
+                .find(|(host, _)| host.eq_ignore_ascii_case("codeberg.org"))
+                .map(|(_, token)| token.clone()),
+            "bitbucket" => config.auth.bitbucket_token.clone(),
+            _ => None,
+        },
+        tokens: if provider_id == "github" {
+            config.github_token_pool()
+        } else {
+            vec![]
+        },
+        base_url: if provider_id == "gitea" {
+            Some(config.gitea_base_url().to_string())
+        } else {
+            None
+        },
+        exclude_topics: match provider_id {
+            "github" => config.github.exclude_topics.clone(),
+            "gitlab" => config.gitlab.exclude_topics.clone(),
+            _ => vec![],
+        },
+        topics_all: if provider_id == "github" {
+            config.github.topics_all.clone()
+        } else {
+            vec![]
+        },
+        topics_any: if provider_id == "github" {
+            config.github.topics_any.clone()
+        } else {
+            vec![]
+        },
+        retry_on_empty: provider_id == "github" && config.github.retry_on_empty,
+        use_graphql: provider_id == "github" && config.github.use_graphql,
+        merge_windows: provider_id == "github" && config.github.merge_windows,
+        since: if provider_id == "github" {
+            config.github.since
+        } else {
+            Since::Daily
+        },
+        spoken_language: if provider_id == "github" {
+            config.github.spoken_language.clone()
+        } else {
+            None
+        },
+        use_languages_api: provider_id == "gitlab"
+            && config.general.enrich
+            && config.gitlab.use_languages_api,
+        min_recent_commits: if provider_id == "gitlab" && config.general.enrich {
+            config.gitlab.min_recent_commits
+        } else {
+            None
+        },
+        icons: config.general.icons,
+        verbose,
+    }
+}
+
+/// Drop repositories the provider reports as having no README
+///
+/// One request per repository not yet in `known` (README answers by URL),
+/// run concurrently. Repositories whose provider can't tell, or whose check
+/// fails, are kept and left out of `known` so they're retried next time.
+async fn retain_with_readme(
+    provider: &dyn Provider,
+    cfg: &ProviderCfg,
+    repos: Vec<Repo>,
+    known: &mut HashMap<String, bool>,
+    verbose: bool,
+) -> Vec<Repo> {
+    let unchecked: Vec<&Repo> = repos
+        .iter()
+        .filter(|r| !known.contains_key(&r.url))
+        .collect();
+    let checks =
+        futures::future::join_all(unchecked.iter().map(|r| provider.has_readme(cfg, r))).await;
+
+    for (repo, check) in unchecked.into_iter().zip(checks) {
+        match check {
+            Ok(Some(has_readme)) => {
+                known.insert(repo.url.clone(), has_readme);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                if verbose {
+                    eprintln!("  ⚠ README check failed for {}: {e}", repo.name);
+                }
+            }
+        }
+    }
+
+    repos
+        .into_iter()
+        .filter(|repo| {
+            let keep = known.get(&repo.url).copied().unwrap_or(true);
+            if !keep && verbose {
+                eprintln!("  📄 {}: no README, skipped", repo.name);
+            }
+            keep
+        })
+        .collect()
+}
+
+/// Instantiate the requested providers, skipping unknown or failing ones
+fn build_providers(
+    config: &Config,
+    enabled_providers: &[&str],
+    verbose: bool,
+) -> Vec<(String, Box<dyn Provider>)> {
+    enabled_providers
+        .iter()
+        .filter_map(|&provider_id| {
+            build_provider(config, provider_id, verbose).map(|p| (provider_id.to_string(), p))
+        })
+        .collect()
+}
+
+/// Provider ids and their short names, as accepted by `--provider`
+const PROVIDER_NAMES: [(&str, &str); 12] = [
+    ("github", "github"),
+    ("gitlab", "gitlab"),
+    ("gitea", "gitea"),
+    ("codeberg", "codeberg"),
+    ("bitbucket", "bitbucket"),
+    ("crates", "crates"),
+    ("gh", "github"),
+    ("gl", "gitlab"),
+    ("ge", "gitea"),
+    ("cb", "codeberg"),
+    ("bb", "bitbucket"),
+    ("cr", "crates"),
+];
+
+/// Whether `name` is a known provider id or short name, ignoring case
+pub fn is_known_provider(name: &str) -> bool {
+    PROVIDER_NAMES
+        .iter()
+        .any(|(known, _)| known.eq_ignore_ascii_case(name))
+}
+
+/// Closest provider id to a mistyped name (e.g. `guthub` -> `github`)
+///
+/// Only suggests within two edits (one for the two-letter short names), and
+/// never for names that are already known.
+pub fn suggest_provider(input: &str) -> Option<&'static str> {
+    let input = input.to_lowercase();
+    if is_known_provider(&input) {
+        return None;
+    }
+    PROVIDER_NAMES
+        .iter()
+        .map(|(name, id)| (edit_distance(&input, name), name.len(), *id))
+        .filter(|(distance, len, _)| *distance <= if *len <= 2 { 1 } else { 2 })
+        .min_by_key(|(distance, _, _)| *distance)
+        .map(|(_, _, id)| id)
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// Instantiate a single provider by kind, logging why if it can't be built
+fn build_provider(config: &Config, provider_id: &str, verbose: bool) -> Option<Box<dyn Provider>> {
+    let timeout_secs = match provider_id {
+        "github" => config.general.github_timeout_secs,
+        "gitlab" => config.general.gitlab_timeout_secs,
+        "gitea" => config.general.gitea_timeout_secs,
+        _ => config.general.timeout_secs,
+    };
+    let max_retries = config.get_max_retries(provider_id);
+    let http = || {
+        HttpClient::builder()
+            .timeout_secs(timeout_secs)
+            .connect_timeout_secs(config.general.connect_timeout_secs)
+            .extra_headers(&config.general.extra_headers)
+            .max_retries(max_retries)
+            .retry_base_ms(config.general.retry_base_ms)
+            .verbose(verbose)
+            .build()
+    };
+
+    let provider: Box<dyn Provider> = match provider_id {
+        "github" => match http() {
+            Ok(client) => Box::new(GitHub::with_client(client).with_concurrency(
+                config.github.html_concurrency,
+                config.github.api_concurrency,
+            )),
+            Err(e) => {
+                eprintln!("✗ Failed to initialize GitHub provider: {e}");
+                return None;
+            }
+        },
+        "gitlab" => match http() {
+            Ok(client) => Box::new(GitLab::with_client(client)),
+            Err(e) => {
+                eprintln!("✗ Failed to initialize GitLab provider: {e}");
+                return None;
+            }
+        },
+        "gitea" => match http() {
+            Ok(client) => Box::new(Gitea::with_client(client)),
+            Err(e) => {
+                eprintln!("✗ Failed to initialize Gitea provider: {e}");
+                return None;
+            }
+        },
+        "codeberg" => match http() {
+            Ok(client) => Box::new(Codeberg::with_client(client)),
+            Err(e) => {
+                eprintln!("✗ Failed to initialize Codeberg provider: {e}");
+                return None;
+            }
+        },
+        "bitbucket" => match http() {
+            Ok(client) => Box::new(Bitbucket::with_client(client)),
+            Err(e) => {
+                eprintln!("✗ Failed to initialize Bitbucket provider: {e}");
+                return None;
+            }
+        },
+        "crates" => match http() {
+            Ok(client) => Box::new(CratesIo::with_client(client)),
+            Err(e) => {
+                eprintln!("✗ Failed to initialize crates.io provider: {e}");
+                return None;
+            }
+        },
+        _ => {
+            eprintln!("⚠ Unknown provider: {provider_id}");
+            return None;
+        }
+    };
+
+    if verbose {
+        eprintln!(
+            "  ✓ {provider_id} provider initialized (timeout: {timeout_secs}s, retries: {max_retries})"
+        );
+    }
+    Some(provider)
+}
+
+/// Download the instance list from `general.instances_url`, cached with a TTL
+///
+/// The list is a JSON array whose entries are either bare base URLs (treated
+/// as Gitea) or `{"kind": "gitlab" | "gitea", "url": "..."}` objects.
+/// Unknown kinds are skipped.
+pub async fn load_instances(
+    config: &Config,
+    use_cache: bool,
+    verbose: bool,
+) -> Result<Vec<Instance>> {
+    let Some(ref list_url) = config.general.instances_url else {
+        return Ok(vec![]);
+    };
+
+    let cache = if use_cache {
+        Cache::new(config.general.cache_ttl_mins).ok()
+    } else {
+        None
+    };
+
+    // Offline, any cached list will do
+    let ttl_mins = if config.general.offline {
+        u64::MAX
+    } else {
+        config.general.instances_ttl_mins
+    };
+
+    if let Some(ref cache) = cache {
+        if let Some(instances) = cache.get_meta::<Vec<Instance>>("instances", ttl_mins).await {
+            if verbose {
+                eprintln!("🏢 {} instances (cached)", instances.len());
+            }
+            return Ok(instances);
+        }
+    }
+
+    if config.general.offline {
+        return Ok(vec![]);
+    }
+
+    let http = HttpClient::builder()
+        .timeout_secs(config.general.timeout_secs)
+        .connect_timeout_secs(config.general.connect_timeout_secs)
+        .extra_headers(&config.general.extra_headers)
+        .max_retries(config.get_max_retries("instances"))
+        .retry_base_ms(config.general.retry_base_ms)
+        .build()?;
+    let specs: Vec<InstanceSpec> = http
+        .get_json(list_url, None)
+        .await
+        .with_context(|| format!("Failed to load instance list from {list_url}"))?;
+
+    let instances: Vec<Instance> = specs
+        .into_iter()
+        .map(Instance::from)
+        .filter(|instance| {
+            let known = matches!(instance.kind.as_str(), "gitlab" | "gitea");
+            if !known {
+                eprintln!("⚠ Skipping instance with unknown kind: {}", instance.kind);
+            }
+            known
+        })
+        .map(|mut instance| {
+            instance.url = instance.url.trim_end_matches('/').to_string();
+            instance
+        })
+        .collect();
+
+    if let Some(ref cache) = cache {
+        let _ = cache.set_meta("instances", &instances).await;
+    }
+
+    if verbose {
+        eprintln!("🏢 {} instances from {list_url}", instances.len());
+    }
+
+    Ok(instances)
+}
+
+/// Apply the config-driven post-fetch filters
+fn apply_filters(config: &Config, repos: &mut Vec<Repo>, verbose: bool) {
+    retain_filtered(config, repos, verbose);
+
+    // Sort the merged list (relevance unless configured otherwise)
+    let sort = config.general.sort.unwrap_or_default();
+    sort.sort(repos);
+    if verbose {
+        eprintln!("↕ Sorted by {sort:?}");
+    }
+}
+
+/// Drop repositories rejected by the per-repo filters (scripts, languages, stars, activity)
+fn retain_filtered(config: &Config, repos: &mut Vec<Repo>, verbose: bool) {
+    // Keep names and descriptions written in Latin/Common or an allowed script
+    if config.general.ascii_only || !config.general.allowed_scripts.is_empty() {
+        let filter = ScriptFilter::new(&config.general.allowed_scripts);
+        let before_count = repos.len();
+        repos.retain(|repo| {
+            filter.allows(&repo.name)
+                && repo.description.as_deref().is_none_or(|d| filter.allows(d))
+        });
+        if verbose {
+            let filtered_count = before_count - repos.len();
+            eprintln!("🔤 Script filter: removed {filtered_count} repos in other scripts");
+        }
+    }
+
+    // Excluded languages, again here since cached entries may predate the exclusion
+    if !config.general.exclude_languages.is_empty() {
+        let filter =
+            LanguageFilter::new(vec![]).excluding(config.general.exclude_languages.clone());
+        let before_count = repos.len();
+        repos.retain(|repo| filter.matches(repo.language.as_ref()));
+        if verbose {
+            let filtered_count = before_count - repos.len();
+            eprintln!("🚫 Language exclusion: removed {filtered_count} repos");
+        }
+    }
+
+    // Apply minimum star filters (per-provider floors fall back to min_stars)
+    let before_count = repos.len();
+    repos.retain(|repo| {
+        config
+            .get_min_stars(repo.provider_kind())
+            .is_none_or(|min_stars| repo.stars_total.unwrap_or(0) >= min_stars)
+    });
+    let filtered_count = before_count - repos.len();
+    if verbose && filtered_count > 0 {
+        eprintln!("⭐ Star filter: removed {filtered_count} repos below their star floor");
+    }
+
+    // Drop stale repositories; unknown activity only goes with `require_activity`
+    let window = config.min_activity();
+    if window.is_some() || config.general.require_activity {
+        let now = chrono::Utc::now();
+        let before_count = repos.len();
+        repos.retain(|repo| match repo.last_activity {
+            Some(dt) => window.is_none_or(|window| {
+                now.signed_duration_since(dt).max(chrono::Duration::zero()) <= window
+            }),
+            None => !config.general.require_activity,
+        });
+        let filtered_count = before_count - repos.len();
+        if verbose && filtered_count > 0 {
+            eprintln!("🕒 Activity filter: removed {filtered_count} inactive repos");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(provider: &str, name: &str, pinned: bool) -> Repo {
+        Repo {
+            provider: provider.to_string(),
+            name: name.to_string(),
+            pinned,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_suggest_provider() {
+        assert_eq!(suggest_provider("guthub"), Some("github"));
+        assert_eq!(suggest_provider("GitLav"), Some("gitlab"));
+        assert_eq!(suggest_provider("gll"), Some("gitlab"));
+        assert_eq!(suggest_provider("xx"), None);
+        assert_eq!(suggest_provider("gitee"), Some("gitea"));
+        assert_eq!(suggest_provider("bitbucket"), None);
+        assert_eq!(suggest_provider("github"), None);
+        assert_eq!(suggest_provider("gl"), None);
+        assert_eq!(suggest_provider("GitHub"), None);
+    }
+
+    #[test]
+    fn test_is_known_provider() {
+        assert!(is_known_provider("github"));
+        assert!(is_known_provider("GitHub"));
+        assert!(is_known_provider("CB"));
+        assert!(!is_known_provider("guthub"));
+        assert!(!is_known_provider(""));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "gh"), 2);
+        assert_eq!(edit_distance("gitea", "gitea"), 0);
+    }
+
+    #[test]
+    fn test_parse_pin() {
+        let gitea = "https://codeberg.org";
+        assert_eq!(
+            parse_pin("rust-lang/rust", gitea),
+            Some(("github", "rust-lang/rust".to_string()))
+        );
+        assert_eq!(
+            parse_pin("https://gitlab.com/gitlab-org/gitlab/", gitea),
+            Some(("gitlab", "gitlab-org/gitlab".to_string()))
+        );
+        assert_eq!(
+            parse_pin("https://codeberg.org/forgejo/forgejo.git", gitea),
+            Some(("gitea", "forgejo/forgejo".to_string()))
+        );
+        assert_eq!(
+            parse_pin("https://codeberg.org/forgejo/forgejo", "https://gitea.com"),
+            Some(("codeberg", "forgejo/forgejo".to_string()))
+        );
+        assert_eq!(parse_pin("https://example.com/a/b", gitea), None);
+        assert_eq!(parse_pin("https://github.com/rust-lang", gitea), None);
+        assert_eq!(parse_pin("not-a-repo", gitea), None);
+    }
+
+    #[test]
+    fn test_instance_spec_parsing() {
+        let json = r#"[
+            "https://codeberg.org/",
+            {"kind": "GitLab", "url": "https://gitlab.gnome.org"}
+        ]"#;
+        let specs: Vec<InstanceSpec> = serde_json::from_str(json).unwrap();
+        let instances: Vec<Instance> = specs.into_iter().map(Instance::from).collect();
+
+        assert_eq!(instances[0].kind, "gitea");
+        assert_eq!(instances[0].id(), "gitea@codeberg.org");
+        assert_eq!(instances[1].kind, "gitlab");
+        assert_eq!(instances[1].id(), "gitlab@gitlab.gnome.org");
+    }
+
+    #[tokio::test]
+    async fn test_discovered_instances_get_no_token() {
+        let mut server = mockito::Server::new_async().await;
+        let path = "/api/v1/repos/search?sort=updated&order=desc&limit=100";
+        let body = r#"{"ok": true, "data": []}"#;
+        let primary = server
+            .mock("GET", path)
+            .match_header("authorization", "Bearer secret")
+            .with_body(body)
+            .expect(1)
+            .create_async()
+            .await;
+        let discovered = server
+            .mock("GET", path)
+            .match_header("authorization", mockito::Matcher::Missing)
+            .with_body(body)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut config = Config::default();
+        config.gitea.base_urls = vec![server.url()];
+        config.auth.gitea_token = Some("secret".to_string());
+        // A hostile list pointing back at the primary host must not collect its token
+        let options = FetchOptions {
+            providers: Some(vec!["gitea".to_string()]),
+            use_cache: false,
+            instances: vec![Instance {
+                kind: "gitea".to_string(),
+                url: server.url(),
+            }],
+            ..FetchOptions::default()
+        };
+        fetch(&config, &options).await.unwrap();
+
+        primary.assert_async().await;
+        discovered.assert_async().await;
+    }
+
+    #[test]
+    fn test_codeberg_token() {
+        let mut config = Config::default();
+        assert_eq!(provider_cfg(&config, "codeberg", false).token, None);
+
+        config
+            .auth
+            .gitea_tokens
+            .insert("codeberg.org".to_string(), "cb".to_string());
+        assert_eq!(
+            provider_cfg(&config, "codeberg", false).token.as_deref(),
+            Some("cb")
+        );
+        // The primary Gitea token stays with the gitea provider, even on Codeberg
+        config.auth.gitea_tokens.clear();
+        config.auth.gitea_token = Some("secret".to_string());
+        config.gitea.base_urls = vec!["https://codeberg.org".to_string()];
+        assert_eq!(provider_cfg(&config, "codeberg", false).token, None);
+    }
+
+    #[tokio::test]
+    async fn test_stale_served_before_revalidation() {
+        // The refetch is held until the test releases it
+        let (release, held) = std::sync::mpsc::channel::<()>();
+        let held = Mutex::new(held);
+        let mut server = mockito::Server::new_async().await;
+        let refetch = server
+            .mock("GET", "/api/v1/repos/search?sort=updated&order=desc&limit=100")
+            .with_body_from_request(move |_| {
+                let _ = lock(&held).recv();
+                br#"{"ok": true, "data": [{"full_name": "new/repo", "html_url": "u", "stars_count": 3}]}"#
+                    .to_vec()
+            })
+            .create_async()
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("trotd-swr-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let expired =
+            serde_json::json!({"timestamp": 1, "repos": [repo("gitea", "old/repo", false)]});
+        std::fs::write(dir.join("gitea.json"), expired.to_string()).unwrap();
+
+        let mut config = Config::default();
+        config.gitea.base_urls = vec![server.url()];
+        config.general.stale_while_revalidate = true;
+        let options = FetchOptions {
+            providers: Some(vec!["gitea".to_string()]),
+            ..FetchOptions::default()
+        };
+        let outcome = fetch_from(
+            &config,
+            &options,
+            Some(Cache::with_dir(dir.clone(), 60)),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome.repos.len(), 1);
+        assert_eq!(outcome.repos[0].name, "old/repo");
+        assert!(outcome.repos[0].from_cache);
+        assert_eq!(outcome.revalidations.len(), 1);
+        assert!(!outcome.revalidations[0].is_finished());
+
+        release.send(()).unwrap();
+        for task in outcome.revalidations {
+            task.await.unwrap();
+        }
+        refetch.assert_async().await;
+        let cache = Cache::with_dir(dir.clone(), 60);
+        let fresh = cache.get("gitea").await.unwrap();
+        assert_eq!(fresh[0].name, "new/repo");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_readme_checks_cached_with_list() {
+        let mut server = mockito::Server::new_async().await;
+        let list = server
+            .mock(
+                "GET",
+                "/api/v1/repos/search?sort=updated&order=desc&limit=100",
+            )
+            .with_body(
+                r#"{"ok": true, "data": [
+                    {"full_name": "a/docs", "html_url": "https://x/a/docs", "stars_count": 3},
+                    {"full_name": "b/bare", "html_url": "https://x/b/bare", "stars_count": 2}
+                ]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let docs = server
+            .mock("GET", "/api/v1/repos/a/docs/contents")
+            .with_body(r#"[{"name": "README.md", "type": "file"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let bare = server
+            .mock("GET", "/api/v1/repos/b/bare/contents")
+            .with_body(r#"[{"name": "main.go", "type": "file"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("trotd-readme-{}", std::process::id()));
+        let mut config = Config::default();
+        config.gitea.base_urls = vec![server.url()];
+        config.general.enrich = true;
+        config.general.require_readme = true;
+        let options = FetchOptions {
+            providers: Some(vec!["gitea".to_string()]),
+            ..FetchOptions::default()
+        };
+
+        // The second run is served from cache and mustn't probe again
+        for _ in 0..2 {
+            let cache = Cache::with_dir(dir.clone(), 60);
+            let outcome = fetch_from(&config, &options, Some(cache), None)
+                .await
+                .unwrap();
+            let names: Vec<_> = outcome.repos.iter().map(|r| r.name.as_str()).collect();
+            assert_eq!(names, ["a/docs"]);
+        }
+
+        list.assert_async().await;
+        docs.assert_async().await;
+        bare.assert_async().await;
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_dedupe_repos() {
+        let mut github = repo("github", "Owner/Tool", false);
+        github.stars_total = Some(500);
+        github.topics = vec!["cli".to_string()];
+        let mut codeberg = repo("codeberg", "owner/tool", false);
+        codeberg.stars_total = Some(20);
+        codeberg.description = Some("A tool".to_string());
+        codeberg.topics = vec!["cli".to_string(), "rust".to_string()];
+        let gitlab = repo("gitlab", "owner/group/tool", false);
+        let other = repo("gitea", "someone/tool", false);
+
+        let repos = dedupe_repos(vec![codeberg, other, github, gitlab]);
+
+        assert_eq!(repos.len(), 2);
+        assert_eq!(repos[0].provider, "github");
+        assert_eq!(repos[0].description.as_deref(), Some("A tool"));
+        assert_eq!(repos[0].topics, vec!["cli", "rust"]);
+        assert_eq!(repos[1].name, "someone/tool");
+    }
+
+    #[test]
+    fn test_apply_stars_delta() {
+        let mut repos = vec![
+            repo("github", "a/up", false),
+            repo("github", "a/down", false),
+            repo("github", "a/new", false),
+        ];
+        repos[0].stars_total = Some(142);
+        repos[1].stars_total = Some(7);
+        repos[2].stars_total = Some(5);
+        let previous = HashMap::from([("a/up".to_string(), 100), ("a/down".to_string(), 10)]);
+
+        apply_stars_delta(&mut repos, &previous);
+        assert_eq!(repos[0].stars_delta, Some(42));
+        assert_eq!(repos[1].stars_delta, Some(-3));
+        assert_eq!(repos[2].stars_delta, None);
+    }
+
+    #[test]
+    fn test_activity_filter() {
+        let now = chrono::Utc::now();
+        let mut repos = vec![
+            repo("gitlab", "a/fresh", false),
+            repo("gitlab", "a/stale", false),
+            repo("github", "a/unknown", false),
+        ];
+        repos[0].last_activity = Some(now - chrono::Duration::hours(3));
+        repos[1].last_activity = Some(now - chrono::Duration::days(3));
+
+        let mut config = Config::default();
+        config.general.min_activity = Some("24h".to_string());
+        let mut kept = repos.clone();
+        retain_filtered(&config, &mut kept, false);
+        let names: Vec<_> = kept.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["a/fresh", "a/unknown"]);
+
+        config.general.require_activity = true;
+        retain_filtered(&config, &mut repos, false);
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].name, "a/fresh");
+    }
+
+    #[test]
+    fn test_record_offline_skip() {
+        let mut outcome = FetchOutcome::default();
+        assert!(outcome.record(Ok(None), false).is_none());
+        assert!(outcome.empty_providers.is_empty());
+        assert!(!outcome.all_failed());
+
+        let repos = outcome.record(Ok(Some(("github".to_string(), vec![], None))), false);
+        assert!(repos.is_none());
+        assert_eq!(outcome.empty_providers, vec!["github".to_string()]);
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let mut cfg = ProviderCfg::default();
+        assert_eq!(cache_key("github", &cfg), "github");
+
+        cfg.since = Since::Weekly;
+        cfg.spoken_language = Some("en".to_string());
+        assert_eq!(cache_key("github", &cfg), "github-weekly-en");
+
+        // Provider-side filters get their own entry per setting
+        cfg.topics_any = vec!["rust".to_string()];
+        let rust = cache_key("github", &cfg);
+        assert!(rust.starts_with("github-weekly-en-f"), "{rust}");
+        assert_eq!(cache_key("github", &cfg), rust);
+        cfg.topics_any = vec!["go".to_string()];
+        assert_ne!(cache_key("github", &cfg), rust);
+
+        let mut gitlab = ProviderCfg::default();
+        let plain = cache_key("gitlab", &gitlab);
+        gitlab.min_recent_commits = Some(5);
+        let filtered = cache_key("gitlab", &gitlab);
+        gitlab.min_recent_commits = Some(10);
+        assert_ne!(plain, filtered);
+        assert_ne!(filtered, cache_key("gitlab", &gitlab));
+    }
+
+    #[test]
+    fn test_deadline_after() {
+        assert!(deadline_after(None).is_none());
+        assert!(deadline_after(Some(5)).is_some());
+        // Too far out to represent: no deadline rather than a panic
+        assert!(deadline_after(Some(u64::MAX)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_before_deadline() {
+        let past = Some(tokio::time::Instant::now());
+        assert_eq!(before_deadline(None, async { 1 }).await, Some(1));
+        assert_eq!(
+            before_deadline(past, std::future::pending::<u8>()).await,
+            None
+        );
+    }
+
+    #[test]
+    fn test_record_unfinished() {
+        let mut outcome = FetchOutcome::default();
+        outcome.record_unfinished(vec![None, None], Some(5), false);
+        assert!(outcome.errors.is_empty());
+
+        outcome.record_unfinished(
+            vec![None, Some("gitea@codeberg.org".to_string())],
+            Some(5),
+            false,
+        );
+        assert_eq!(
+            outcome.errors[0].to_string(),
+            "gitea@codeberg.org: no response within the 5s deadline"
+        );
+        assert!(outcome.all_failed());
+    }
+
+    #[test]
+    fn test_merge_pinned_dedupes() {
+        let pinned = vec![repo("github", "me/tool", true)];
+        let trending = vec![
+            repo("github", "Me/Tool", false),
+            repo("gitlab", "me/tool", false),
+        ];
+        let merged = merge_pinned(pinned, trending);
+
+        assert_eq!(merged.len(), 2);
+        assert!(merged[0].pinned);
+        assert_eq!(merged[1].provider, "gitlab");
+    }
+}
@@ -1,31 +1,125 @@
 use anyhow::{Context, Result};
-use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
+use chrono::{DateTime, Local, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::time::Duration;
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
-use tokio_retry::Retry;
+use tokio_retry::RetryIf;
 
 /// HTTP client wrapper with timeout and authentication support
 pub struct HttpClient {
     client: reqwest::Client,
+    /// User-configured headers sent with every request
+    extra_headers: HeaderMap,
     timeout: Duration,
     max_retries: usize,
     retry_base_ms: u64,
+    /// Log rate-limit headers of API responses
+    verbose: bool,
 }
 
-/// Builder for HttpClient with configurable retry and timeout settings
+/// Non-success HTTP status, kept structured so callers can react to specific codes
+#[derive(Debug)]
+pub struct StatusError {
+    pub status: reqwest::StatusCode,
+    pub url: String,
+    /// When the exhausted rate limit resets, for a 403/429 with `X-RateLimit-Remaining: 0`
+    pub rate_limit_reset: Option<DateTime<Utc>>,
+    /// Whether the request carried an `Authorization` header
+    pub authenticated: bool,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(reset) = self.rate_limit_reset {
+            let hint = if self.authenticated {
+                "the token's quota is used up"
+            } else {
+                "set a token to raise the limit"
+            };
+            return write!(
+                f,
+                "Rate limit exceeded for {}, resets at {}; {hint}",
+                host_of(&self.url),
+                reset.with_timezone(&Local).format("%H:%M:%S")
+            );
+        }
+        let kind = if self.status.is_client_error() {
+            "client error"
+        } else {
+            "status"
+        };
+        write!(
+            f,
+            "HTTP request failed with {kind} {}: {}",
+            self.status, self.url
+        )
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// Rate limit reported in the body of a successful response, as GitHub's
+/// GraphQL API does with a `RATE_LIMITED` error and status 200
+#[derive(Debug)]
+pub struct RateLimitedError {
+    pub url: String,
+}
+
+impl std::fmt::Display for RateLimitedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rate limit exceeded for {}", host_of(&self.url))
+    }
+}
+
+impl std::error::Error for RateLimitedError {}
+
+/// Whether an error is a rate limit: an HTTP 403/429, which APIs like GitHub's
+/// use for rate limits, or a `RateLimitedError`
+pub fn is_rate_limited(err: &anyhow::Error) -> bool {
+    err.is::<RateLimitedError>()
+        || err.downcast_ref::<StatusError>().is_some_and(|e| {
+            matches!(
+                e.status,
+                reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS
+            )
+        })
+}
+
+/// Client errors (4xx) won't succeed on retry
+fn is_retryable(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<StatusError>()
+        .is_none_or(|e| !e.status.is_client_error())
+}
+
+/// One page of a paginated JSON response
+pub struct JsonPage<T> {
+    pub items: T,
+    /// URL of the next page, if the server advertised one
+    pub next_url: Option<String>,
+}
+
+/// Builder for `HttpClient` with configurable retry and timeout settings
 pub struct HttpClientBuilder {
     timeout_secs: u64,
+    connect_timeout_secs: Option<u64>,
     max_retries: usize,
     retry_base_ms: u64,
+    extra_headers: Vec<(String, String)>,
+    verbose: bool,
 }
 
 impl Default for HttpClientBuilder {
     fn default() -> Self {
         Self {
             timeout_secs: 10,
+            connect_timeout_secs: None,
+            extra_headers: vec![],
             max_retries: 3,
             retry_base_ms: 1000,
+            verbose: false,
         }
     }
 }
@@ -43,6 +137,15 @@ impl HttpClientBuilder {
         self
     }
 
+    /// Set the connection timeout in seconds (default: bounded only by `timeout_secs`)
+    ///
+    /// A short value fails fast on dead hosts while the overall timeout still
+    /// gives slow-but-alive servers time to respond.
+    pub fn connect_timeout_secs(mut self, secs: Option<u64>) -> Self {
+        self.connect_timeout_secs = secs;
+        self
+    }
+
     /// Set the maximum number of retries (default: 3)
     #[allow(dead_code)]
     pub fn max_retries(mut self, retries: usize) -> Self {
@@ -57,18 +160,52 @@ impl HttpClientBuilder {
         self
     }
 
-    /// Build the HttpClient
+    /// Log each API response's remaining rate limit to stderr (default: off)
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Add headers sent with every request (validated in `build`)
+    pub fn extra_headers(mut self, headers: &BTreeMap<String, String>) -> Self {
+        self.extra_headers
+            .extend(headers.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self
+    }
+
+    /// Build the `HttpClient`
+    ///
+    /// Fails on invalid extra header names or values, and on attempts to set
+    /// `Authorization` (tokens are configured under `[auth]`).
     pub fn build(self) -> Result<HttpClient> {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(self.timeout_secs))
-            .build()
-            .context("Failed to create HTTP client")?;
+        let mut extra_headers = HeaderMap::new();
+        for (name, value) in &self.extra_headers {
+            let header = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid header name in extra_headers: {name:?}"))?;
+            if header == AUTHORIZATION {
+                anyhow::bail!(
+                    "extra_headers can't set Authorization; configure tokens under [auth] instead"
+                );
+            }
+            let value = HeaderValue::from_str(value)
+                .with_context(|| format!("Invalid value for header {name} in extra_headers"))?;
+            extra_headers.insert(header, value);
+        }
+
+        let mut builder =
+            reqwest::Client::builder().timeout(Duration::from_secs(self.timeout_secs));
+        if let Some(secs) = self.connect_timeout_secs {
+            builder = builder.connect_timeout(Duration::from_secs(secs));
+        }
+        let client = builder.build().context("Failed to create HTTP client")?;
 
         Ok(HttpClient {
             client,
+            extra_headers,
             timeout: Duration::from_secs(self.timeout_secs),
             max_retries: self.max_retries,
             retry_base_ms: self.retry_base_ms,
+            verbose: self.verbose,
         })
     }
 }
@@ -87,6 +224,76 @@ impl HttpClient {
         HttpClientBuilder::new()
     }
 
+    /// Default headers, then the configured extras, then the bearer token if any
+    fn request_headers(&self, accept: &'static str, token: Option<&str>) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("trotd/0.1.0"));
+        headers.insert(ACCEPT, HeaderValue::from_static(accept));
+        for (name, value) in &self.extra_headers {
+            headers.insert(name, value.clone());
+        }
+
+        if let Some(token) = token {
+            let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
+                .context("Invalid authentication token")?;
+            headers.insert(AUTHORIZATION, auth_value);
+        }
+        Ok(headers)
+    }
+
+    /// Fail on a non-success status, logging rate-limit headers under verbose
+    ///
+    /// A 403/429 with no requests left becomes a rate-limit error naming the reset time.
+    fn check_status(
+        &self,
+        url: &str,
+        response: &reqwest::Response,
+        authenticated: bool,
+    ) -> Result<()> {
+        let rate_limit = rate_limit(response.headers());
+        if self.verbose {
+            if let Some((remaining, reset)) = rate_limit {
+                let reset = reset.map_or_else(String::new, |r| {
+                    format!(", resets at {}", r.with_timezone(&Local).format("%H:%M:%S"))
+                });
+                eprintln!("  ⏱ {}: {remaining} requests left{reset}", host_of(url));
+            }
+        }
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let limited = matches!(
+            status,
+            reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS
+        );
+        let rate_limit_reset = match rate_limit {
+            Some((0, reset)) if limited => reset,
+            _ => None,
+        };
+        Err(StatusError {
+            status,
+            url: url.to_string(),
+            rate_limit_reset,
+            authenticated,
+        }
+        .into())
+    }
+
+    /// Jittered retry delays of up to `retry_base_ms`, then twice that, and so on
+    ///
+    /// Only the upper half of each delay is random, so delays never drop to
+    /// zero and always grow from one attempt to the next.
+    fn backoff(&self) -> impl Iterator<Item = std::time::Duration> {
+        // `from_millis(2)` doubles each step; the factor scales it to the base delay
+        ExponentialBackoff::from_millis(2)
+            .factor(self.retry_base_ms.div_ceil(2))
+            .map(|delay| delay / 2 + jitter(delay / 2))
+            .take(self.max_retries)
+    }
+
     /// Fetch JSON data from URL with optional authentication token
     pub async fn get_json<T: DeserializeOwned>(&self, url: &str, token: Option<&str>) -> Result<T> {
         if self.max_retries == 0 {
@@ -94,28 +301,72 @@ impl HttpClient {
             return self.get_json_once(url, token).await;
         }
 
-        let retry_strategy = ExponentialBackoff::from_millis(self.retry_base_ms)
-            .map(jitter)
-            .take(self.max_retries);
+        let retry_strategy = self.backoff();
 
-        Retry::spawn(retry_strategy, || async {
-            self.get_json_once(url, token).await
-        })
+        RetryIf::spawn(
+            retry_strategy,
+            || async { self.get_json_once(url, token).await },
+            is_retryable,
+        )
         .await
     }
 
     /// Internal method to fetch JSON once (used by retry logic)
-    async fn get_json_once<T: DeserializeOwned>(&self, url: &str, token: Option<&str>) -> Result<T> {
-        let mut headers = HeaderMap::new();
-        headers.insert(USER_AGENT, HeaderValue::from_static("trotd/0.1.0"));
-        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+    async fn get_json_once<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        token: Option<&str>,
+    ) -> Result<T> {
+        let headers = self.request_headers("application/json", token)?;
+        let authenticated = headers.contains_key(AUTHORIZATION);
 
-        if let Some(token) = token {
-            let auth_value = HeaderValue::from_str(&format!("Bearer {token}"))
-                .context("Invalid authentication token")?;
-            headers.insert(AUTHORIZATION, auth_value);
+        let response = self
+            .client
+            .get(url)
+            .headers(headers)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch URL: {url}"))?;
+
+        // 4xx client errors aren't retried (see `is_retryable`)
+        self.check_status(url, &response, authenticated)?;
+
+        response
+            .json::<T>()
+            .await
+            .with_context(|| format!("Failed to parse JSON response from {url}"))
+    }
+
+    /// Fetch one page of a paginated JSON API, returning the next page's URL if any
+    pub async fn get_json_page<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        token: Option<&str>,
+    ) -> Result<JsonPage<T>> {
+        if self.max_retries == 0 {
+            return self.get_json_page_once(url, token).await;
         }
 
+        let retry_strategy = self.backoff();
+
+        RetryIf::spawn(
+            retry_strategy,
+            || async { self.get_json_page_once(url, token).await },
+            is_retryable,
+        )
+        .await
+    }
+
+    /// Internal method to fetch a JSON page once (used by retry logic)
+    async fn get_json_page_once<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        token: Option<&str>,
+    ) -> Result<JsonPage<T>> {
+        let headers = self.request_headers("application/json", token)?;
+        let authenticated = headers.contains_key(AUTHORIZATION);
+
         let response = self
             .client
             .get(url)
@@ -125,23 +376,95 @@ impl HttpClient {
             .await
             .with_context(|| format!("Failed to fetch URL: {url}"))?;
 
-        let status = response.status();
+        self.check_status(url, &response, authenticated)?;
 
-        // Don't retry on 4xx client errors
-        if status.is_client_error() {
-            anyhow::bail!("HTTP request failed with client error {status}: {url}");
-        }
+        let next_url = next_page_url(url, response.headers());
 
-        if !status.is_success() {
-            anyhow::bail!("HTTP request failed with status {status}: {url}");
+        let items = response
+            .json::<T>()
+            .await
+            .with_context(|| format!("Failed to parse JSON response from {url}"))?;
+
+        Ok(JsonPage { items, next_url })
+    }
+
+    /// POST a JSON body and parse the JSON response (e.g. a GraphQL query)
+    ///
+    /// Only for requests that are safe to repeat, since failures are retried
+    /// like `get_json`.
+    pub async fn post_json<B, T>(&self, url: &str, body: &B, token: Option<&str>) -> Result<T>
+    where
+        B: Serialize + Sync,
+        T: DeserializeOwned,
+    {
+        if self.max_retries == 0 {
+            return self.post_json_once(url, body, token).await;
         }
 
+        let retry_strategy = self.backoff();
+
+        RetryIf::spawn(
+            retry_strategy,
+            || async { self.post_json_once(url, body, token).await },
+            is_retryable,
+        )
+        .await
+    }
+
+    /// Internal method to POST JSON once (used by retry logic)
+    async fn post_json_once<B, T>(&self, url: &str, body: &B, token: Option<&str>) -> Result<T>
+    where
+        B: Serialize + Sync,
+        T: DeserializeOwned,
+    {
+        let headers = self.request_headers("application/json", token)?;
+        let authenticated = headers.contains_key(AUTHORIZATION);
+
+        let response = self
+            .client
+            .post(url)
+            .headers(headers)
+            .json(body)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch URL: {url}"))?;
+
+        self.check_status(url, &response, authenticated)?;
+
         response
             .json::<T>()
             .await
             .with_context(|| format!("Failed to parse JSON response from {url}"))
     }
 
+    /// Check whether a URL exists: `true` on success, `false` on 404
+    ///
+    /// Makes a single attempt (no retries) since it's used for cheap per-repo probes.
+    pub async fn url_exists(&self, url: &str, token: Option<&str>) -> Result<bool> {
+        let headers = self.request_headers("application/json", token)?;
+        let authenticated = headers.contains_key(AUTHORIZATION);
+
+        let response = self
+            .client
+            .get(url)
+            .headers(headers)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch URL: {url}"))?;
+
+        let status = response.status();
+
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+
+        self.check_status(url, &response, authenticated)?;
+
+        Ok(true)
+    }
+
     /// Fetch HTML content from URL (for web scraping)
     pub async fn get_html(&self, url: &str) -> Result<String> {
         if self.max_retries == 0 {
@@ -149,21 +472,20 @@ impl HttpClient {
             return self.get_html_once(url).await;
         }
 
-        let retry_strategy = ExponentialBackoff::from_millis(self.retry_base_ms)
-            .map(jitter)
-            .take(self.max_retries);
+        let retry_strategy = self.backoff();
 
-        Retry::spawn(retry_strategy, || async {
-            self.get_html_once(url).await
-        })
+        RetryIf::spawn(
+            retry_strategy,
+            || async { self.get_html_once(url).await },
+            is_retryable,
+        )
         .await
     }
 
     /// Internal method to fetch HTML once (used by retry logic)
     async fn get_html_once(&self, url: &str) -> Result<String> {
-        let mut headers = HeaderMap::new();
-        headers.insert(USER_AGENT, HeaderValue::from_static("trotd/0.1.0"));
-        headers.insert(ACCEPT, HeaderValue::from_static("text/html"));
+        let headers = self.request_headers("text/html", None)?;
+        let authenticated = headers.contains_key(AUTHORIZATION);
 
         let response = self
             .client
@@ -174,16 +496,8 @@ impl HttpClient {
             .await
             .with_context(|| format!("Failed to fetch URL: {url}"))?;
 
-        let status = response.status();
-
-        // Don't retry on 4xx client errors
-        if status.is_client_error() {
-            anyhow::bail!("HTTP request failed with client error {status}: {url}");
-        }
-
-        if !status.is_success() {
-            anyhow::bail!("HTTP request failed with status {status}: {url}");
-        }
+        // 4xx client errors aren't retried (see `is_retryable`)
+        self.check_status(url, &response, authenticated)?;
 
         response
             .text()
@@ -192,6 +506,66 @@ impl HttpClient {
     }
 }
 
+/// Host part of a URL, for messages
+fn host_of(url: &str) -> &str {
+    let rest = url.split("://").nth(1).unwrap_or(url);
+    rest.split('/').next().unwrap_or(rest)
+}
+
+/// Remaining requests and reset time from `X-RateLimit-Remaining` / `X-RateLimit-Reset`
+///
+/// The reset header holds a Unix timestamp (GitHub, Gitea). `None` when the
+/// server sends no remaining count.
+fn rate_limit(headers: &HeaderMap) -> Option<(u64, Option<DateTime<Utc>>)> {
+    let header = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<i64>().ok();
+    let remaining = u64::try_from(header("x-ratelimit-remaining")?).ok()?;
+    let reset = header("x-ratelimit-reset").and_then(|secs| DateTime::from_timestamp(secs, 0));
+    Some((remaining, reset))
+}
+
+/// Work out the next page's URL from pagination headers
+///
+/// Prefers the `Link: <...>; rel="next"` header and falls back to GitLab's
+/// `X-Next-Page`, which only carries the page number.
+fn next_page_url(url: &str, headers: &HeaderMap) -> Option<String> {
+    if let Some(link) = headers
+        .get(reqwest::header::LINK)
+        .and_then(|v| v.to_str().ok())
+    {
+        let next = link.split(',').find_map(|part| {
+            let (target, params) = part.split_once(';')?;
+            params
+                .split(';')
+                .any(|p| p.trim() == "rel=\"next\"")
+                .then(|| {
+                    target
+                        .trim()
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .to_string()
+                })
+        });
+        if next.is_some() {
+            return next;
+        }
+    }
+
+    let page = headers.get("x-next-page")?.to_str().ok()?.trim();
+    if page.is_empty() {
+        return None;
+    }
+
+    // Replace an existing `page=` parameter or append one
+    let (base, query) = url.split_once('?').unwrap_or((url, ""));
+    let mut params: Vec<String> = query
+        .split('&')
+        .filter(|p| !p.is_empty() && !p.starts_with("page="))
+        .map(ToString::to_string)
+        .collect();
+    params.push(format!("page={page}"));
+    Some(format!("{base}?{}", params.join("&")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,6 +590,210 @@ mod tests {
         assert_eq!(client.retry_base_ms, 500);
     }
 
+    #[test]
+    fn test_backoff_doubles_from_base() {
+        let client = HttpClient::builder()
+            .max_retries(3)
+            .retry_base_ms(500)
+            .build()
+            .unwrap();
+        let delays: Vec<u128> = client.backoff().map(|d| d.as_millis()).collect();
+        assert_eq!(delays.len(), 3);
+        for (delay, cap) in delays.iter().zip([500, 1000, 2000]) {
+            assert!(
+                (cap / 2..=cap).contains(delay),
+                "{delay} outside {}..={cap}",
+                cap / 2
+            );
+        }
+        assert!(delays[0] > 0);
+        assert!(
+            delays.windows(2).all(|pair| pair[0] < pair[1]),
+            "{delays:?}"
+        );
+    }
+
+    #[test]
+    fn test_extra_headers_validation() {
+        let headers = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let client = HttpClient::builder()
+            .extra_headers(&headers(&[
+                ("X-Corp-Auth", "abc"),
+                ("User-Agent", "corp/1.0"),
+            ]))
+            .build()
+            .unwrap();
+        let sent = client
+            .request_headers("application/json", Some("tok"))
+            .unwrap();
+        assert_eq!(sent["x-corp-auth"], "abc");
+        assert_eq!(sent[USER_AGENT], "corp/1.0");
+        assert_eq!(sent[AUTHORIZATION], "Bearer tok");
+
+        let err = HttpClient::builder()
+            .extra_headers(&headers(&[("authorization", "Bearer x")]))
+            .build()
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Authorization"));
+
+        assert!(HttpClient::builder()
+            .extra_headers(&headers(&[("bad header", "x")]))
+            .build()
+            .is_err());
+        assert!(HttpClient::builder()
+            .extra_headers(&headers(&[("X-Ok", "line\nbreak")]))
+            .build()
+            .is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs network access"]
+    async fn test_connect_timeout_fails_fast() {
+        // Non-routable address: the connect timeout fires long before the overall one
+        let client = HttpClient::builder()
+            .timeout_secs(30)
+            .connect_timeout_secs(Some(1))
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let result = client
+            .get_json::<serde_json::Value>("http://10.255.255.1/", None)
+            .await;
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_next_page_url() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            next_page_url("https://x.test/api?per_page=100", &headers),
+            None
+        );
+
+        headers.insert("x-next-page", HeaderValue::from_static("2"));
+        assert_eq!(
+            next_page_url("https://x.test/api?per_page=100&page=1", &headers).as_deref(),
+            Some("https://x.test/api?per_page=100&page=2")
+        );
+
+        // Link header wins over X-Next-Page
+        headers.insert(
+            reqwest::header::LINK,
+            HeaderValue::from_static(
+                "<https://x.test/api?page=1>; rel=\"first\", <https://x.test/api?page=3>; rel=\"next\"",
+            ),
+        );
+        assert_eq!(
+            next_page_url("https://x.test/api", &headers).as_deref(),
+            Some("https://x.test/api?page=3")
+        );
+
+        // Empty X-Next-Page means last page
+        let mut last = HeaderMap::new();
+        last.insert("x-next-page", HeaderValue::from_static(""));
+        assert_eq!(next_page_url("https://x.test/api", &last), None);
+    }
+
+    #[test]
+    fn test_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(rate_limit(&headers), None);
+
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("7"));
+        assert_eq!(rate_limit(&headers), Some((7, None)));
+
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1700000000"));
+        let reset = DateTime::from_timestamp(1_700_000_000, 0);
+        assert_eq!(rate_limit(&headers), Some((7, reset)));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_error() {
+        let mut server = mockito::Server::new_async().await;
+        let exhausted = server
+            .mock("GET", "/search")
+            .with_status(403)
+            .with_header("x-ratelimit-remaining", "0")
+            .with_header("x-ratelimit-reset", "1700000000")
+            .expect(2)
+            .create_async()
+            .await;
+        let forbidden = server
+            .mock("GET", "/private")
+            .with_status(403)
+            .create_async()
+            .await;
+
+        let client = HttpClient::builder().max_retries(0).build().unwrap();
+        let err = client
+            .get_json::<serde_json::Value>(&format!("{}/search", server.url()), None)
+            .await
+            .unwrap_err();
+        assert!(is_rate_limited(&err));
+        let message = err.to_string();
+        assert!(
+            message.starts_with("Rate limit exceeded for 127.0.0.1"),
+            "{message}"
+        );
+        assert!(message.contains("set a token"));
+
+        // With a token, suggesting one would be misleading
+        let err = client
+            .get_json::<serde_json::Value>(&format!("{}/search", server.url()), Some("secret"))
+            .await
+            .unwrap_err();
+        assert!(is_rate_limited(&err));
+        let message = err.to_string();
+        assert!(
+            message.contains("the token's quota is used up"),
+            "{message}"
+        );
+        assert!(!message.contains("set a token"), "{message}");
+
+        // A plain 403 keeps the generic message
+        let err = client
+            .get_json::<serde_json::Value>(&format!("{}/private", server.url()), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("client error 403"));
+
+        exhausted.assert_async().await;
+        forbidden.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_post_json() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/graphql")
+            .match_header("authorization", "Bearer t0ken")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"query": "{ viewer { login } }"}),
+            ))
+            .with_body(r#"{"data": {"viewer": {"login": "octocat"}}}"#)
+            .create_async()
+            .await;
+
+        let client = HttpClient::builder().max_retries(0).build().unwrap();
+        let body = serde_json::json!({"query": "{ viewer { login } }"});
+        let response: serde_json::Value = client
+            .post_json(&format!("{}/graphql", server.url()), &body, Some("t0ken"))
+            .await
+            .unwrap();
+        assert_eq!(response["data"]["viewer"]["login"], "octocat");
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_get_json_with_mock() {
         // Integration tests with mockito will be added in provider tests
@@ -338,6 +916,24 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_url_exists() {
+        let client = HttpClient::builder()
+            .timeout_secs(10)
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        // Allow for network issues - httpbin.org might be down
+        match client
+            .url_exists("https://httpbin.org/status/404", None)
+            .await
+        {
+            Ok(exists) => assert!(!exists),
+            Err(e) => eprintln!("httpbin.org test skipped: {e}"),
+        }
+    }
+
     #[tokio::test]
     async fn test_network_error_handling() {
         // Use max_retries(0) to avoid retry delays in tests
@@ -0,0 +1,37 @@
+//! trotd - trending repositories of the day
+//!
+//! The binary is a thin CLI over this library. To embed trotd's fetching in
+//! another program, load a [`Config`] and call [`fetch_trending`]:
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! let config = trotd::Config::load()?;
+//! for repo in trotd::fetch_trending(&config).await? {
+//!     println!("{} {}", repo.name, repo.url);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#![allow(
+    clippy::missing_errors_doc,
+    clippy::must_use_candidate,
+    clippy::return_self_not_must_use
+)]
+
+pub mod cache;
+pub mod config;
+pub mod fetch;
+pub mod http;
+pub mod model;
+pub mod providers;
+pub mod render;
+pub mod script;
+
+pub use config::Config;
+pub use fetch::{
+    before_deadline, deadline_after, dedupe_repos, fetch, fetch_pinned, fetch_streaming,
+    fetch_trending, is_known_provider, load_instances, merge_pinned, suggest_provider,
+    FetchOptions, FetchOutcome, Instance, StaleData,
+};
+pub use model::{LanguageFilter, Provider, ProviderCfg, Repo};
@@ -1,26 +1,29 @@
 use anyhow::{Context, Result};
+use chrono::Datelike;
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell};
-use futures::stream::{FuturesUnordered, StreamExt};
-use std::io;
-use std::sync::Arc;
-
-mod cache;
-mod config;
-mod http;
-mod model;
-mod providers;
-mod render;
-
-use cache::Cache;
-use config::Config;
-use model::{LanguageFilter, Provider, ProviderCfg};
-use providers::{GitHub, GitLab, Gitea};
-use render::{render, OutputFormat};
+use colored::Colorize;
+use regex::Regex;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+
+use trotd::cache::Cache;
+use trotd::config::{parse_duration, Source, StarSeparator, SAMPLE_CONFIG};
+use trotd::model::{is_web_url, owner_of, IconSet, Repo, Since, SortKey};
+use trotd::render::{
+    format_ndjson_line, init_color, parse_json_field, render, write_export, AgingGradient,
+    ColorChoice, ExportFormat, OutputFormat, RenderOptions,
+};
+use trotd::{
+    before_deadline, deadline_after, dedupe_repos, fetch, fetch_pinned, fetch_streaming,
+    is_known_provider, load_instances, merge_pinned, suggest_provider, Config, FetchOptions,
+    StaleData,
+};
 
 /// Trending repositories of the day - minimal MOTD CLI
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[allow(clippy::struct_excessive_bools)]
 struct Args {
     #[command(subcommand)]
     command: Option<Commands>,
@@ -37,25 +40,351 @@ struct Args {
     #[arg(short, long, value_name = "LIST", value_delimiter = ',', global = true)]
     lang: Option<Vec<String>>,
 
+    /// Hide these languages (comma-separated: javascript,php)
+    #[arg(
+        long = "exclude-lang",
+        value_name = "LIST",
+        value_delimiter = ',',
+        global = true
+    )]
+    exclude_lang: Option<Vec<String>>,
+
     /// Disable cache
     #[arg(long, global = true)]
     no_cache: bool,
 
+    /// Fetch fresh results, ignoring cached ones, and write them to the cache
+    #[arg(long, global = true)]
+    refresh: bool,
+
+    /// Add custom providers from a TOML or JSON file (same fields as `[[custom_provider]]`)
+    #[arg(
+        long = "providers-file",
+        alias = "providers-from-file",
+        value_name = "PATH",
+        global = true
+    )]
+    providers_file: Option<PathBuf>,
+
+    /// Never fetch: show cached results of any age and skip uncached providers
+    #[arg(long, global = true, conflicts_with_all = ["no_cache", "refresh"])]
+    offline: bool,
+
+    /// Show what arrived after SECS seconds; unfinished providers count as failed
+    #[arg(
+        long,
+        value_name = "SECS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        global = true
+    )]
+    deadline: Option<u64>,
+
     /// Output as JSON instead of MOTD
     #[arg(long, global = true)]
     json: bool,
 
+    /// JSON output with only these fields (comma-separated, e.g. name,url,stars)
+    #[arg(
+        long = "json-fields",
+        value_name = "LIST",
+        value_delimiter = ',',
+        value_parser = parse_json_field,
+        conflicts_with = "urls",
+        global = true
+    )]
+    json_fields: Option<Vec<String>>,
+
+    /// Minimal JSON output: name, url, and total stars only
+    #[arg(long = "compact-json", conflicts_with_all = ["urls", "json_fields"], global = true)]
+    compact_json: bool,
+
+    /// Stream one JSON object per line as each provider completes (unsorted)
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "urls", "sort", "pick", "also_json", "also_csv"],
+        global = true
+    )]
+    ndjson: bool,
+
+    /// Show only the top repository as a one-line banner
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "urls", "ndjson", "json_fields", "compact_json"],
+        global = true
+    )]
+    one: bool,
+
+    /// Output as CSV with a header row (for spreadsheets)
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "urls", "ndjson", "one", "json_fields", "compact_json"],
+        global = true
+    )]
+    csv: bool,
+
+    /// Output as a GitHub-flavored Markdown table (for issues and chat)
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "json", "urls", "ndjson", "one", "csv", "json_fields", "compact_json"
+        ],
+        global = true
+    )]
+    markdown: bool,
+
+    /// Output as YAML (same fields as --json)
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "json", "urls", "ndjson", "one", "csv", "markdown", "json_fields", "compact_json"
+        ],
+        global = true
+    )]
+    yaml: bool,
+
+    /// Output as JSON Lines: one compact object per line, sorted like --json
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "urls", "ndjson", "one", "csv", "markdown", "yaml"],
+        global = true
+    )]
+    jsonl: bool,
+
+    /// Output as an HTML table with per-provider CSS classes (for web pages)
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "urls", "ndjson", "one", "csv", "markdown", "yaml", "jsonl"],
+        global = true
+    )]
+    html: bool,
+
+    /// Print only repository URLs, one per line (for piping)
+    #[arg(long, conflicts_with = "json", global = true)]
+    urls: bool,
+
+    /// When to use colors (auto follows the color env vars and whether stdout is a terminal)
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t, global = true)]
+    color: ColorChoice,
+
+    /// Also write the results as JSON to FILE (in addition to stdout)
+    #[arg(long = "also-json", value_name = "FILE", global = true)]
+    also_json: Option<PathBuf>,
+
+    /// Also write the results as CSV to FILE (in addition to stdout)
+    #[arg(long = "also-csv", value_name = "FILE", global = true)]
+    also_csv: Option<PathBuf>,
+
+    /// Keep only the first N repositories after filtering and sorting
+    /// (overrides `general.total_limit`)
+    #[arg(long, value_name = "N", global = true)]
+    limit: Option<usize>,
+
+    /// Suppress informational headers such as the stale-data warning
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
     /// Enable verbose output for debugging
     #[arg(short, long, global = true)]
     verbose: bool,
 
     /// Minimum star count threshold
     #[arg(long = "min-stars", value_name = "N", global = true)]
-    min_stars: Option<u32>,
+    min_stars: Option<u64>,
+
+    /// Drop repositories last active longer ago than this (e.g. 24h, 7d, 2w)
+    #[arg(long = "min-activity", value_name = "DURATION", value_parser = parse_min_activity, global = true)]
+    min_activity: Option<String>,
+
+    /// Drop repositories with no known last activity
+    #[arg(long = "require-activity", global = true)]
+    require_activity: bool,
 
     /// Exclude GitHub repositories with these topics (comma-separated)
     #[arg(long = "exclude-topics", value_name = "LIST", value_delimiter = ',', global = true)]
     exclude_topics: Option<Vec<String>>,
+
+    /// Keep only repositories owned by these users or orgs (comma-separated)
+    #[arg(long, value_name = "LIST", value_delimiter = ',', global = true)]
+    owner: Option<Vec<String>>,
+
+    /// Drop repositories owned by these users or orgs (comma-separated)
+    #[arg(
+        long = "exclude-owner",
+        value_name = "LIST",
+        value_delimiter = ',',
+        global = true
+    )]
+    exclude_owner: Option<Vec<String>>,
+
+    /// Drop repositories whose owner/name matches this regex (repeatable)
+    #[arg(long = "exclude-name", value_name = "REGEX", global = true)]
+    exclude_name: Vec<String>,
+
+    /// Keep only GitHub repositories with ALL of these topics (comma-separated)
+    #[arg(
+        long = "topics-all",
+        value_name = "LIST",
+        value_delimiter = ',',
+        global = true
+    )]
+    topics_all: Option<Vec<String>>,
+
+    /// Keep only GitHub repositories with ANY of these topics (comma-separated)
+    #[arg(
+        long = "topics-any",
+        visible_alias = "include-topics",
+        value_name = "LIST",
+        value_delimiter = ',',
+        global = true
+    )]
+    topics_any: Option<Vec<String>>,
+
+    /// Remove emoji from repository names and descriptions
+    #[arg(long = "strip-emoji", global = true)]
+    strip_emoji: bool,
+
+    /// Show N repositories picked from the fetched pool, stable for the day
+    #[arg(long, value_name = "N", global = true)]
+    pick: Option<usize>,
+
+    /// Mark rows that were served from cache with a dim ⟳
+    #[arg(long = "mark-cached", global = true)]
+    mark_cached: bool,
+
+    /// Exit with status 2 if any provider failed, even when others succeeded
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// Merge GitHub daily and weekly trending, repos in both windows first
+    #[arg(long = "merge-window", global = true)]
+    merge_window: bool,
+
+    /// GitHub trending window
+    #[arg(
+        long,
+        value_enum,
+        value_name = "WINDOW",
+        conflicts_with = "merge_window",
+        global = true
+    )]
+    since: Option<Since>,
+
+    /// Only GitHub trending repos whose README is in this language (e.g. en)
+    #[arg(long = "spoken-language", value_name = "CODE", global = true)]
+    spoken_language: Option<String>,
+
+    /// Show at most N repositories followed by a "── N more ──" marker
+    #[arg(long = "page-size", value_name = "N", global = true)]
+    page_size: Option<usize>,
+
+    /// Show every repository, ignoring --page-size
+    #[arg(long, global = true)]
+    all: bool,
+
+    /// Per-provider retry overrides (comma-separated: gh=0,ge=1)
+    #[arg(
+        long = "provider-retries",
+        value_name = "LIST",
+        value_delimiter = ',',
+        value_parser = parse_provider_retries,
+        global = true
+    )]
+    provider_retries: Option<Vec<(String, usize)>>,
+
+    /// Print a footer explaining the provider icons shown
+    #[arg(long, global = true)]
+    legend: bool,
+
+    /// Print a footer counting repos per provider and summing total stars
+    #[arg(long, global = true)]
+    summary: bool,
+
+    /// Also query GitLab/Gitea instances listed in a JSON array at this URL
+    #[arg(long = "instances-url", value_name = "URL", global = true)]
+    instances_url: Option<String>,
+
+    /// Disable retries for all providers (fail fast)
+    #[arg(long = "no-retry", global = true)]
+    no_retry: bool,
+
+    /// HTTP retries per request, replacing configured per-provider values (--provider-retries still wins)
+    #[arg(
+        long = "max-retries",
+        value_name = "N",
+        conflicts_with = "no_retry",
+        global = true
+    )]
+    max_retries: Option<usize>,
+
+    /// Never create a default config file (also: `TROTD_NO_CONFIG_WRITE=1`)
+    #[arg(long = "no-config-write", global = true)]
+    no_config_write: bool,
+
+    /// Allow extra per-repository API requests for enrichment options
+    #[arg(long, global = true)]
+    enrich: bool,
+
+    /// Keep only repositories with a README (GitHub/Gitea, needs --enrich)
+    #[arg(long = "require-readme", requires = "enrich", global = true)]
+    require_readme: bool,
+
+    /// Use GitLab's languages API instead of topic guessing (needs --enrich)
+    #[arg(long = "gitlab-languages", requires = "enrich", global = true)]
+    gitlab_languages: bool,
+
+    /// Sort the merged list (default: relevance)
+    #[arg(long, value_enum, value_name = "KEY", global = true)]
+    sort: Option<SortKey>,
+
+    /// Keep provider order instead of sorting (same as --sort provider)
+    #[arg(long = "no-sort", conflicts_with = "sort", global = true)]
+    no_sort: bool,
+
+    /// Show a stars-per-day velocity column (API providers only)
+    #[arg(long = "show-velocity", global = true)]
+    show_velocity: bool,
+
+    /// Provider icon preset
+    #[arg(long, value_enum, value_name = "SET", global = true)]
+    icons: Option<IconSet>,
+
+    /// Show a compact stars/forks/issues badge cluster (API providers)
+    #[arg(long, global = true)]
+    badges: bool,
+
+    /// Make repo names clickable links in terminals that support OSC 8
+    #[arg(long, global = true)]
+    hyperlinks: bool,
+
+    /// Collapse the same repository listed by several providers
+    #[arg(long, global = true, conflicts_with = "ndjson")]
+    dedupe: bool,
+
+    /// Progressively dim older repos (whole row)
+    #[arg(long = "dim-old", global = true)]
+    dim_old: bool,
+
+    /// Truncate descriptions to N columns (default 45, 0 hides them)
+    #[arg(long = "desc-width", value_name = "N", global = true)]
+    desc_width: Option<usize>,
+
+    /// Print full descriptions word-wrapped below each row instead of truncating
+    #[arg(long = "wrap-desc", global = true)]
+    wrap_desc: bool,
+
+    /// After printing, open the first N repositories in the browser
+    #[arg(long, value_name = "N", conflicts_with = "ndjson", global = true)]
+    open: Option<usize>,
+
+    /// Redraw every N minutes until Ctrl-C (cached results are reused until they expire)
+    #[arg(
+        long,
+        value_name = "MINUTES",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["ndjson", "open", "strict"],
+        global = true
+    )]
+    watch: Option<u64>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -66,20 +395,113 @@ enum Commands {
         #[arg(value_enum)]
         shell: Shell,
     },
+    /// Inspect the local cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+    /// Print a fully commented example trotd.toml to stdout
+    SampleConfig,
+    /// Inspect the effective configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Print the effective config as TOML (with --json: JSON plus where each value came from)
+    Show,
+    /// Print the config files that are loaded, lowest precedence first
+    Path,
+    /// Check the config files, failing on unknown keys instead of warning
+    Check,
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+    /// List cached providers with their age, TTL status, and repo count
+    List,
+    /// Show the cache directory and each cached file's age and TTL status
+    Info,
+    /// Remove one provider's cached results (all of them without PROVIDER)
+    Clear {
+        /// Cache entry to remove, e.g. github, gl, or gitea@codeberg.org
+        provider: Option<String>,
+    },
+    /// Remove every cached file, including the instance list
+    ClearAll,
 }
 
 #[tokio::main]
 #[allow(clippy::too_many_lines)]
 async fn main() -> Result<()> {
     let args = Args::parse();
+    init_color(args.color);
 
     // Handle subcommands
-    if let Some(command) = args.command {
+    if let Some(command) = &args.command {
         match command {
             Commands::Completions { shell } => {
                 let mut cmd = Args::command();
                 let bin_name = cmd.get_name().to_string();
-                generate(shell, &mut cmd, bin_name, &mut io::stdout());
+                generate(*shell, &mut cmd, bin_name, &mut io::stdout());
+                return Ok(());
+            }
+            Commands::Cache { action } => {
+                let config = Config::load_with(!args.no_config_write)
+                    .context("Failed to load configuration")?;
+                let cache = Cache::for_config(&config).context("Failed to initialize cache")?;
+                match action {
+                    CacheCommand::List => print_cache_entries(&cache).await,
+                    CacheCommand::Info => print_cache_info(&cache).await,
+                    CacheCommand::Clear {
+                        provider: Some(provider),
+                    } => {
+                        let provider = provider_alias(provider);
+                        match cache.clear(provider).await? {
+                            0 => println!("No cache entry for {provider}"),
+                            1 => println!("Cleared cache for {provider}"),
+                            n => println!("Cleared {n} cache entries for {provider}"),
+                        }
+                    }
+                    CacheCommand::Clear { provider: None } | CacheCommand::ClearAll => {
+                        cache.clear_all().await?;
+                        println!("Cleared cache at {}", cache.dir().display());
+                    }
+                }
+                return Ok(());
+            }
+            Commands::SampleConfig => {
+                print!("{SAMPLE_CONFIG}");
+                return Ok(());
+            }
+            Commands::Config {
+                action: ConfigCommand::Path,
+            } => {
+                print_config_paths();
+                return Ok(());
+            }
+            Commands::Config {
+                action: ConfigCommand::Check,
+            } => {
+                Config::check().context("Configuration check failed")?;
+                println!("✓ Configuration OK ({} file(s))", Config::files().len());
+                return Ok(());
+            }
+            Commands::Config {
+                action: ConfigCommand::Show,
+            } => {
+                let mut config = Config::load_with(!args.no_config_write)
+                    .context("Failed to load configuration")?;
+                let before = config.clone();
+                apply_cli_overrides(&mut config, &args);
+                if let Some(path) = &args.providers_file {
+                    config.add_providers_file(path)?;
+                }
+                config.mark_changed_since(&before, Source::Cli);
+                print_config(&config, args.json)?;
                 return Ok(());
             }
         }
@@ -88,276 +510,921 @@ async fn main() -> Result<()> {
     let verbose = args.verbose;
 
     // Load configuration
-    let mut config = Config::load().context("Failed to load configuration")?;
+    let mut config =
+        Config::load_with(!args.no_config_write).context("Failed to load configuration")?;
 
     if verbose {
         eprintln!("📋 Config loaded successfully");
     }
 
-    // Apply CLI overrides
-    if let Some(max) = args.max_per_provider {
-        config.general.max_per_provider = max;
-    }
-
-    if let Some(langs) = args.lang {
-        config.general.language_filter = langs;
-    }
-
-    if let Some(min) = args.min_stars {
-        config.general.min_stars = Some(min);
-    }
-
-    if let Some(topics) = args.exclude_topics {
-        config.github.exclude_topics = topics;
+    apply_cli_overrides(&mut config, &args);
+    if let Some(path) = &args.providers_file {
+        config.add_providers_file(path)?;
     }
+    let exclude_names = compile_name_patterns(&config.general.exclude_name_patterns)?;
 
     // Determine output format
-    let format = if args.json {
+    let format = if args.jsonl {
+        OutputFormat::JsonLines
+    } else if args.json || args.json_fields.is_some() || args.compact_json {
         OutputFormat::Json
+    } else if args.urls {
+        OutputFormat::Urls
+    } else if args.one {
+        OutputFormat::One
+    } else if args.csv {
+        OutputFormat::Csv
+    } else if args.markdown {
+        OutputFormat::Markdown
+    } else if args.yaml {
+        OutputFormat::Yaml
+    } else if args.html {
+        OutputFormat::Html
     } else {
         OutputFormat::Motd
     };
 
-    // Initialize cache
-    let cache = if args.no_cache {
-        if verbose {
-            eprintln!("🚫 Cache disabled");
-        }
-        None
-    } else {
-        let c = Cache::new(config.general.cache_ttl_mins).context("Failed to initialize cache")?;
-        if verbose {
-            eprintln!("💾 Cache initialized (TTL: {} mins)", config.general.cache_ttl_mins);
-        }
-        Some(c)
+    // Parse short provider names: gh -> github, gl -> gitlab, ge -> gitea, cb -> codeberg,
+    // bb -> bitbucket, cr -> crates
+    let providers = match &args.provider {
+        Some(names) => Some(validate_providers(names)?),
+        None => None,
     };
 
-    // Determine enabled providers
-    let enabled_providers = if let Some(ref providers) = args.provider {
-        // Parse short names: gh -> github, gl -> gitlab, ge -> gitea
-        providers
-            .iter()
-            .map(|p| match p.as_str() {
-                "gh" => "github",
-                "gl" => "gitlab",
-                "ge" => "gitea",
-                _ => p.as_str(),
-            })
-            .collect::<Vec<_>>()
-    } else {
-        config.enabled_providers()
+    // CI token check, against the providers this run will actually query
+    let queried = match &providers {
+        Some(ids) => ids.iter().map(String::as_str).collect(),
+        None => config.enabled_providers(),
     };
+    config.check_env_tokens(&queried, |name| std::env::var(name).ok())?;
 
-    if verbose {
-        eprintln!("🔌 Enabled providers: {:?}", enabled_providers);
+    // One budget for the whole run: instance list, providers, pins and cache refreshes
+    let deadline = deadline_after(config.general.deadline_secs);
+
+    // Expand the published instance list into extra providers
+    let instances =
+        match before_deadline(deadline, load_instances(&config, !args.no_cache, verbose)).await {
+            Some(Ok(instances)) => instances,
+            Some(Err(e)) => {
+                eprintln!("⚠ {e}");
+                vec![]
+            }
+            None => {
+                eprintln!("⚠ Instance list: no response within the deadline");
+                vec![]
+            }
+        };
+
+    let options = FetchOptions {
+        providers,
+        use_cache: !args.no_cache,
+        refresh: args.refresh,
+        verbose,
+        instances,
+        deadline,
+    };
+
+    if args.ndjson {
+        return run_ndjson(&config, &options, &args, &exclude_names).await;
     }
 
-    // Build provider instances
-    let mut provider_instances: Vec<(String, Box<dyn Provider>)> = Vec::new();
+    if let Some(minutes) = args.watch {
+        return run_watch(&config, &options, &args, format, &exclude_names, minutes).await;
+    }
 
-    for provider_id in enabled_providers {
-        match provider_id {
-            "github" => match GitHub::new(config.general.github_timeout_secs) {
-                Ok(gh) => {
-                    if verbose {
-                        eprintln!("  ✓ GitHub provider initialized (timeout: {}s)", config.general.github_timeout_secs);
-                    }
-                    provider_instances.push(("github".to_string(), Box::new(gh)));
-                }
-                Err(e) => eprintln!("✗ Failed to initialize GitHub provider: {e}"),
-            },
-            "gitlab" => match GitLab::new(config.general.gitlab_timeout_secs) {
-                Ok(gl) => {
-                    if verbose {
-                        eprintln!("  ✓ GitLab provider initialized (timeout: {}s)", config.general.gitlab_timeout_secs);
-                    }
-                    provider_instances.push(("gitlab".to_string(), Box::new(gl)));
-                }
-                Err(e) => eprintln!("✗ Failed to initialize GitLab provider: {e}"),
-            },
-            "gitea" => match Gitea::new(config.general.gitea_timeout_secs) {
-                Ok(ge) => {
-                    if verbose {
-                        eprintln!("  ✓ Gitea provider initialized (timeout: {}s)", config.general.gitea_timeout_secs);
-                    }
-                    provider_instances.push(("gitea".to_string(), Box::new(ge)));
-                }
-                Err(e) => eprintln!("✗ Failed to initialize Gitea provider: {e}"),
-            },
-            _ => eprintln!("⚠ Unknown provider: {provider_id}"),
+    let failed = run_once(&config, &options, &args, format, &exclude_names).await?;
+
+    // In strict mode, partial failure is still a failure (exit code 2)
+    if args.strict && failed > 0 {
+        if verbose {
+            eprintln!("🚨 Strict mode: {failed} provider(s) failed");
         }
+        std::process::exit(2);
     }
 
-    if provider_instances.is_empty() {
-        anyhow::bail!("No providers enabled or available");
+    Ok(())
+}
+
+/// Fetch, filter, and render once, returning how many providers failed
+///
+/// Fails only when every provider failed or an export can't be written.
+#[allow(clippy::too_many_lines)]
+async fn run_once(
+    config: &Config,
+    options: &FetchOptions,
+    args: &Args,
+    format: OutputFormat,
+    exclude_names: &[Regex],
+) -> Result<usize> {
+    let verbose = options.verbose;
+    let outcome = fetch(config, options).await?;
+
+    if matches!(format, OutputFormat::Motd) {
+        for provider_id in &outcome.empty_providers {
+            eprintln!("⚠ No repositories found for {provider_id}");
+        }
     }
 
-    // Create language filter
-    let lang_filter = LanguageFilter::new(config.general.language_filter.clone());
+    // Handle errors
+    for error in &outcome.errors {
+        eprintln!("✗ Error: {error}");
+    }
 
-    if verbose {
-        if config.general.language_filter.is_empty() {
-            eprintln!("🌐 Language filter: all languages");
-        } else {
-            eprintln!("🌐 Language filter: {:?}", config.general.language_filter);
+    // If all providers failed and we have no repos, exit with error
+    if outcome.all_failed() {
+        anyhow::bail!("All providers failed");
+    }
+
+    // Warn when expired cache data is shown because a live fetch failed
+    if matches!(format, OutputFormat::Motd) && !args.quiet {
+        if let Some(header) = stale_header(&outcome.stale, config.general.stale_warn_mins) {
+            println!("{}", header.yellow());
         }
-        eprintln!("🚀 Fetching repositories...");
     }
 
-    // Fetch repositories in parallel
-    let cache_arc = Arc::new(cache);
-    let mut futures = FuturesUnordered::new();
+    // Stale fallbacks still mean a provider failed
+    let failed = outcome.errors.len() + outcome.stale.len();
+    let mut all_repos = outcome.repos;
+    all_repos.retain(|repo| owner_allowed(args, repo) && !name_excluded(exclude_names, repo));
 
-    for (provider_id, provider) in provider_instances {
-        let cache_ref = Arc::clone(&cache_arc);
-        let lang_filter_clone = lang_filter.clone();
-        let config_clone = config.clone();
-        let verbose_clone = verbose;
+    if config.general.dedupe {
+        all_repos = dedupe_repos(all_repos);
+    }
 
-        let future = async move {
-            // Try cache first
-            if let Some(ref cache) = *cache_ref {
-                if let Some(cached_repos) = cache.get(&provider_id).await {
-                    if verbose_clone {
-                        eprintln!("  💾 {} (cached)", provider_id);
-                    }
-                    return Ok((provider_id.clone(), cached_repos));
-                }
-            }
+    // Pick a daily subset if requested
+    if let Some(n) = args.pick {
+        let seed = daily_seed();
+        pick_seeded(&mut all_repos, n, seed);
+        if verbose {
+            eprintln!("🎲 Picked {} repos (seed: {seed})", all_repos.len());
+        }
+    }
 
-            // Build provider config
-            let provider_cfg = ProviderCfg {
-                timeout_secs: config_clone.general.timeout_secs,
-                token: match provider_id.as_str() {
-                    "github" => config_clone.auth.github_token.clone(),
-                    "gitlab" => config_clone.auth.gitlab_token.clone(),
-                    "gitea" => config_clone.auth.gitea_token.clone(),
-                    _ => None,
-                },
-                base_url: if provider_id == "gitea" {
-                    Some(config_clone.gitea.base_url.clone())
-                } else {
-                    None
-                },
-                exclude_topics: if provider_id == "github" {
-                    config_clone.github.exclude_topics.clone()
-                } else {
-                    vec![]
-                },
-            };
-
-            // Fetch from provider
-            let repos = provider
-                .top_today(
-                    &provider_cfg,
-                    config_clone.get_max_entries(&provider_id),
-                    &lang_filter_clone,
-                )
-                .await?;
-
-            // Cache the result
-            if let Some(ref cache) = *cache_ref {
-                let _ = cache.set(&provider_id, repos.clone()).await;
-            }
+    // Pinned repos go on top, regardless of filters and picks
+    if !config.general.pinned.is_empty() {
+        let (pinned, pin_errors) = fetch_pins(config, options).await;
+        for error in &pin_errors {
+            eprintln!("⚠ {error}");
+        }
+        all_repos = merge_pinned(pinned, all_repos);
+    }
 
-            Ok::<_, anyhow::Error>((provider_id, repos))
-        };
+    if let Some(limit) = config.general.total_limit {
+        all_repos.truncate(limit);
+    }
 
-        futures.push(future);
+    apply_icon_overrides(config, &mut all_repos);
+
+    if verbose {
+        eprintln!("📊 Total repositories: {}", all_repos.len());
     }
 
-    // Collect results
-    let mut all_repos = Vec::new();
-    let mut errors = Vec::new();
+    // Render output
+    let render_options = RenderOptions {
+        strip_emoji: config.general.strip_emoji,
+        mark_cached: args.mark_cached,
+        page_size: if args.all { None } else { args.page_size },
+        star_separator: config.general.star_separator.resolve(),
+        // An explicit thousands separator asks for exact counts
+        humanize_stars: config.general.humanize_stars
+            && config.general.star_separator == StarSeparator::None,
+        legend: args.legend,
+        summary: config.general.summary,
+        show_velocity: args.show_velocity,
+        badges: args.badges,
+        dim_old: config.general.dim_old,
+        aging: AgingGradient {
+            days: config.general.dim_old_days,
+        },
+        wrap_desc: args.wrap_desc.then(terminal_width),
+        desc_width: config.general.description_width,
+        term_width: detected_terminal_width(),
+        json_fields: json_fields(args),
+        hyperlinks: config.general.hyperlinks,
+        icon_colors: config.icon_colors(),
+    };
+    render(&all_repos, format, &render_options);
 
-    while let Some(result) = futures.next().await {
-        match result {
-            Ok((provider_id, repos)) => {
-                if verbose {
-                    eprintln!("  📦 {}: {} repos", provider_id, repos.len());
-                }
-                if !repos.is_empty() {
-                    all_repos.extend(repos);
-                } else if format!("{format:?}") == "Motd" {
-                    eprintln!("⚠ No repositories found for {provider_id}");
-                }
+    if let Some(n) = args.open {
+        for repo in all_repos.iter().take(n) {
+            if let Err(e) = open_in_browser(&repo.url) {
+                eprintln!("⚠ Failed to open {}: {e}", repo.url);
             }
-            Err(e) => {
-                if verbose {
-                    eprintln!("  ✗ Provider error: {e}");
+        }
+    }
+
+    // Extra machine-readable copies from the same fetch
+    let exports = [
+        (&args.also_json, ExportFormat::Json),
+        (&args.also_csv, ExportFormat::Csv),
+    ];
+    for (path, export) in exports {
+        if let Some(path) = path {
+            write_export(&all_repos, export, path)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+    }
+
+    finish_revalidation(outcome.revalidations, options.deadline).await;
+
+    Ok(failed)
+}
+
+/// Redraw the output every `minutes` until Ctrl-C, then exit cleanly
+///
+/// Each refresh goes through the cache as usual, so providers are only
+/// refetched once their TTL expires. A failed refresh is shown in place of
+/// the list rather than ending the loop. The screen is cleared between
+/// refreshes only for MOTD output on a terminal; other formats are appended.
+async fn run_watch(
+    config: &Config,
+    options: &FetchOptions,
+    args: &Args,
+    format: OutputFormat,
+    exclude_names: &[Regex],
+    minutes: u64,
+) -> Result<()> {
+    let interval = std::time::Duration::from_secs(minutes.saturating_mul(60));
+    // Only clear an interactive MOTD; machine formats just append each refresh
+    let clear = matches!(format, OutputFormat::Motd) && io::stdout().is_terminal();
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut options = options.clone();
+
+    loop {
+        tokio::select! {
+            result = run_once(config, &options, args, format, exclude_names) => {
+                if let Err(e) = result {
+                    println!("{}", format!("✗ {e}").red());
                 }
-                errors.push(e);
             }
+            _ = &mut ctrl_c => return Ok(()),
         }
-    }
+        let _ = io::stdout().flush();
 
-    // Handle errors
-    if !errors.is_empty() {
-        for error in &errors {
-            eprintln!("✗ Error: {error}");
+        tokio::select! {
+            () = tokio::time::sleep(interval) => {}
+            _ = &mut ctrl_c => return Ok(()),
+        }
+        // Each refresh gets the full deadline again
+        options.deadline = deadline_after(config.general.deadline_secs);
+        if clear {
+            print!("\x1b[2J\x1b[H");
         }
     }
+}
 
-    // If all providers failed and we have no repos, exit with error
-    if all_repos.is_empty() && !errors.is_empty() {
+/// Stream results as NDJSON, one line per repo as each provider completes
+///
+/// Pinned repos come first. Sorting needs the full set, so lines follow
+/// completion order; `--limit` / `general.total_limit` caps the total number of lines.
+async fn run_ndjson(
+    config: &Config,
+    options: &FetchOptions,
+    args: &Args,
+    exclude_names: &[Regex],
+) -> Result<()> {
+    if matches!(config.general.sort, Some(sort) if sort != SortKey::Provider) {
+        eprintln!("⚠ --ndjson streams in completion order; general.sort is ignored");
+    }
+
+    let fields = json_fields(args);
+    let limit = config.general.total_limit.unwrap_or(usize::MAX);
+    let mut written = 0;
+
+    let (pinned, pin_errors) = if config.general.pinned.is_empty() {
+        (vec![], vec![])
+    } else {
+        fetch_pins(config, options).await
+    };
+    for error in &pin_errors {
+        eprintln!("⚠ {error}");
+    }
+
+    // Owner and name filters apply to trending repos only; pins were written up front
+    let skip = |repo: &Repo| {
+        let duplicate = pinned
+            .iter()
+            .any(|p| p.provider == repo.provider && p.name.eq_ignore_ascii_case(&repo.name));
+        !repo.pinned
+            && (duplicate || !owner_allowed(args, repo) || name_excluded(exclude_names, repo))
+    };
+    let mut emit = |mut repos: Vec<Repo>| {
+        apply_icon_overrides(config, &mut repos);
+        for repo in repos.iter().filter(|repo| !skip(repo)) {
+            if written >= limit {
+                return;
+            }
+            println!("{}", format_ndjson_line(repo, fields.as_deref()));
+            written += 1;
+        }
+    };
+
+    emit(pinned.clone());
+    let outcome = fetch_streaming(config, options, &mut emit).await?;
+
+    for error in &outcome.errors {
+        eprintln!("✗ Error: {error}");
+    }
+    if outcome.all_failed() {
         anyhow::bail!("All providers failed");
     }
 
-    // Apply ASCII-only filter if enabled
-    if config.general.ascii_only {
-        let before_count = all_repos.len();
-        all_repos.retain(|repo| is_mostly_ascii(repo));
-        if verbose {
-            let filtered_count = before_count - all_repos.len();
-            eprintln!("🔤 ASCII filter: removed {filtered_count} non-ASCII repos");
+    let failed = outcome.errors.len() + outcome.stale.len();
+    finish_revalidation(outcome.revalidations, options.deadline).await;
+    if args.strict && failed > 0 {
+        std::process::exit(2);
+    }
+
+    Ok(())
+}
+
+/// Let background cache refreshes finish once the output has been shown
+///
+/// Exiting would otherwise cancel them. Their errors are never fatal, and
+/// refreshes still running at the deadline are cancelled.
+async fn finish_revalidation(
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+    deadline: Option<tokio::time::Instant>,
+) {
+    if tasks.is_empty() {
+        return;
+    }
+    let _ = io::stdout().flush();
+    let all_done = async {
+        for task in tasks {
+            let _ = task.await;
         }
+    };
+    let _ = before_deadline(deadline, all_done).await;
+}
+
+/// Fetch `general.pinned`, giving up on pins still running at the deadline
+async fn fetch_pins(config: &Config, options: &FetchOptions) -> (Vec<Repo>, Vec<anyhow::Error>) {
+    let pins = fetch_pinned(config, options.verbose);
+    before_deadline(options.deadline, pins)
+        .await
+        .unwrap_or_else(|| {
+            let error = anyhow::anyhow!("pinned repos: no response within the deadline");
+            (vec![], vec![error])
+        })
+}
+
+/// Replace provider icons with the glyphs configured in `[icons]`
+fn apply_icon_overrides(config: &Config, repos: &mut [Repo]) {
+    if config.icons.is_empty() {
+        return;
     }
+    for repo in repos {
+        if let Some(icon) = config.icon_override(&repo.provider, &repo.icon) {
+            repo.icon = icon;
+        }
+    }
+}
 
-    // Apply minimum star filter if configured
-    if let Some(min_stars) = config.general.min_stars {
-        let before_count = all_repos.len();
-        all_repos.retain(|repo| repo.stars_total.unwrap_or(0) >= min_stars.into());
-        if verbose {
-            let filtered_count = before_count - all_repos.len();
-            eprintln!("⭐ Star filter: removed {filtered_count} repos below {min_stars} stars");
+/// Apply `--owner` and `--exclude-owner` (case-insensitive) to one repo
+fn owner_allowed(args: &Args, repo: &Repo) -> bool {
+    let owner = owner_of(repo).unwrap_or_default();
+    let listed = |owners: &[String]| owners.iter().any(|o| o.trim().eq_ignore_ascii_case(owner));
+
+    args.owner.as_deref().is_none_or(listed) && !args.exclude_owner.as_deref().is_some_and(listed)
+}
+
+/// Compile `general.exclude_name_patterns`, naming the first invalid pattern
+fn compile_name_patterns(patterns: &[String]) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).with_context(|| {
+                format!("Invalid exclude-name pattern '{pattern}' (--exclude-name or general.exclude_name_patterns)")
+            })
+        })
+        .collect()
+}
+
+/// True if the repo's `owner/name` matches any exclude pattern
+fn name_excluded(patterns: &[Regex], repo: &Repo) -> bool {
+    patterns.iter().any(|re| re.is_match(&repo.name))
+}
+
+/// Fields to keep in JSON output, if projected
+fn json_fields(args: &Args) -> Option<Vec<String>> {
+    if args.compact_json {
+        Some(
+            COMPACT_JSON_FIELDS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        )
+    } else {
+        args.json_fields.clone()
+    }
+}
+
+/// Print the effective config; JSON output pairs it with a per-key provenance map
+fn print_config(config: &Config, json: bool) -> Result<()> {
+    let redacted = config.redacted();
+    if json {
+        let out = serde_json::json!({
+            "config": redacted,
+            "provenance": config.provenance_map(),
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+    } else {
+        print!(
+            "{}",
+            toml::to_string_pretty(&redacted).context("Failed to serialize config")?
+        );
+    }
+    Ok(())
+}
+
+/// Print each config file merged by `Config::load`, or that defaults are in use
+fn print_config_paths() {
+    let files = Config::files();
+    if files.is_empty() {
+        println!("No config file found; using built-in defaults");
+    }
+    for path in files {
+        // `trotd.toml` in the working directory is found by a relative path
+        let path = std::fs::canonicalize(&path).unwrap_or(path);
+        println!("{}", path.display());
+    }
+}
+
+/// Apply the command-line filter flags (languages, stars, activity, topics, names)
+fn apply_filter_overrides(config: &mut Config, args: &Args) {
+    if let Some(langs) = &args.lang {
+        config.general.language_filter.clone_from(langs);
+    }
+
+    if let Some(langs) = &args.exclude_lang {
+        config.general.exclude_languages.clone_from(langs);
+    }
+
+    if let Some(min) = args.min_stars {
+        config.general.min_stars = Some(min);
+    }
+
+    if let Some(window) = &args.min_activity {
+        config.general.min_activity = Some(window.clone());
+    }
+
+    if args.require_activity {
+        config.general.require_activity = true;
+    }
+
+    if let Some(topics) = &args.exclude_topics {
+        config.github.exclude_topics.clone_from(topics);
+    }
+
+    // Patterns given on the command line add to the configured ones
+    config
+        .general
+        .exclude_name_patterns
+        .extend(args.exclude_name.iter().cloned());
+
+    if let Some(topics) = &args.topics_all {
+        config.github.topics_all.clone_from(topics);
+    }
+
+    if let Some(topics) = &args.topics_any {
+        config.github.topics_any.clone_from(topics);
+    }
+}
+
+/// Apply command-line overrides on top of the loaded configuration
+fn apply_cli_overrides(config: &mut Config, args: &Args) {
+    if let Some(max) = args.max_per_provider {
+        config.general.max_per_provider = max;
+    }
+
+    if let Some(limit) = args.limit {
+        config.general.total_limit = Some(limit);
+    }
+
+    apply_filter_overrides(config, args);
+
+    if args.strip_emoji {
+        config.general.strip_emoji = true;
+    }
+
+    if args.merge_window {
+        config.github.merge_windows = true;
+    }
+
+    if let Some(since) = args.since {
+        config.github.since = since;
+    }
+
+    if let Some(code) = &args.spoken_language {
+        config.github.spoken_language = Some(code.trim().to_lowercase());
+    }
+
+    if args.enrich {
+        config.general.enrich = true;
+    }
+
+    if args.no_retry {
+        config.general.retries_enabled = false;
+    }
+
+    // The command line beats the config file, per-provider values included
+    if let Some(retries) = args.max_retries {
+        config.general.max_retries = retries;
+        config.github.max_retries = None;
+        config.gitlab.max_retries = None;
+        config.gitea.max_retries = None;
+        config.codeberg.max_retries = None;
+        config.bitbucket.max_retries = None;
+        config.crates.max_retries = None;
+    }
+
+    if args.offline {
+        config.general.offline = true;
+    }
+
+    if let Some(secs) = args.deadline {
+        config.general.deadline_secs = Some(secs);
+    }
+
+    if let Some(url) = &args.instances_url {
+        config.general.instances_url = Some(url.clone());
+    }
+
+    if let Some(sort) = args.sort {
+        config.general.sort = Some(sort);
+    }
+
+    if args.no_sort {
+        config.general.sort = Some(SortKey::Provider);
+    }
+
+    if let Some(icons) = args.icons {
+        config.general.icons = icons;
+    }
+
+    if args.dim_old {
+        config.general.dim_old = true;
+    }
+
+    if args.dedupe {
+        config.general.dedupe = true;
+    }
+
+    if args.hyperlinks {
+        config.general.hyperlinks = true;
+    }
+
+    if args.summary {
+        config.general.summary = true;
+    }
+
+    if let Some(width) = args.desc_width {
+        config.general.description_width = Some(width);
+    }
+
+    if args.require_readme {
+        config.general.require_readme = true;
+    }
+
+    if args.gitlab_languages {
+        config.gitlab.use_languages_api = true;
+    }
+
+    for (provider_id, retries) in args.provider_retries.iter().flatten() {
+        match provider_id.as_str() {
+            "github" => config.github.max_retries = Some(*retries),
+            "gitlab" => config.gitlab.max_retries = Some(*retries),
+            "gitea" => config.gitea.max_retries = Some(*retries),
+            "codeberg" => config.codeberg.max_retries = Some(*retries),
+            "bitbucket" => config.bitbucket.max_retries = Some(*retries),
+            "crates" => config.crates.max_retries = Some(*retries),
+            _ => eprintln!("⚠ Unknown provider in --provider-retries: {provider_id}"),
         }
     }
 
-    if verbose {
-        eprintln!("📊 Total repositories: {}", all_repos.len());
+    if config.general.require_readme && !config.general.enrich {
+        eprintln!("⚠ general.require_readme has no effect without general.enrich");
     }
+}
 
-    // Render output
-    render(&all_repos, format);
+/// Fields kept by `--compact-json`
+const COMPACT_JSON_FIELDS: [&str; 3] = ["name", "url", "stars_total"];
 
-    Ok(())
+/// Terminal width, falling back to `COLUMNS` and then 80
+fn terminal_width() -> usize {
+    columns().unwrap_or(80)
 }
 
-/// Check if a repository is mostly ASCII (filters out CJK/non-Latin scripts)
-fn is_mostly_ascii(repo: &model::Repo) -> bool {
-    // Check name - should be primarily ASCII
-    let name_ascii_ratio = ascii_ratio(&repo.name);
-    if name_ascii_ratio < 0.8 {
-        return false;
+/// Width to fit MOTD rows into, only when stdout is a terminal
+fn detected_terminal_width() -> Option<usize> {
+    io::stdout().is_terminal().then(columns).flatten()
+}
+
+/// Width of the terminal on stdout, or a positive `COLUMNS` value
+fn columns() -> Option<usize> {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(w), _)| usize::from(w))
+        .filter(|&w| w > 0)
+        .or_else(|| {
+            std::env::var("COLUMNS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&w| w > 0)
+        })
+}
+
+/// Open a URL with the platform's default handler (`open`, `url.dll`, or `xdg-open`)
+///
+/// Only `http(s)://` URLs are opened, and never through a shell: repo URLs
+/// come from providers and untrusted instances.
+fn open_in_browser(url: &str) -> io::Result<()> {
+    if !is_web_url(url) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "not an http(s) URL",
+        ));
     }
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = std::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(drop)
+}
 
-    // Check description if present
-    if let Some(ref desc) = repo.description {
-        let desc_ascii_ratio = ascii_ratio(desc);
-        if desc_ascii_ratio < 0.7 {
-            return false;
+/// Resolve `--provider` names to ids, warning about typos with a suggestion
+///
+/// Fails when none of the given names is a known provider.
+fn validate_providers(names: &[String]) -> Result<Vec<String>> {
+    let mut ids = Vec::new();
+    for name in names {
+        if is_known_provider(name) {
+            ids.push(provider_alias(&name.to_lowercase()).to_string());
+        } else if let Some(suggestion) = suggest_provider(name) {
+            eprintln!("⚠ Unknown provider: {name} (did you mean '{suggestion}'?)");
+        } else {
+            eprintln!("⚠ Unknown provider: {name}");
         }
     }
+    if ids.is_empty() {
+        anyhow::bail!(
+            "No valid providers in --provider {}; expected github, gitlab, gitea, codeberg, \
+             bitbucket, crates (or gh, gl, ge, cb, bb, cr)",
+            names.join(",")
+        );
+    }
+    Ok(ids)
+}
 
-    true
+/// Map a short provider name (gh, gl, ge, cb, bb, cr) to its id, passing others through
+fn provider_alias(name: &str) -> &str {
+    match name {
+        "gh" => "github",
+        "gl" => "gitlab",
+        "ge" => "gitea",
+        "cb" => "codeberg",
+        "bb" => "bitbucket",
+        "cr" => "crates",
+        _ => name,
+    }
 }
 
-/// Calculate the ratio of ASCII characters in a string
-fn ascii_ratio(s: &str) -> f64 {
-    if s.is_empty() {
-        return 1.0;
+/// Parse a `provider=N` retry override
+fn parse_provider_retries(s: &str) -> Result<(String, usize), String> {
+    let (provider, retries) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected provider=N, got '{s}'"))?;
+    let retries = retries
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid retry count in '{s}'"))?;
+    Ok((provider_alias(provider.trim()).to_string(), retries))
+}
+
+/// Check a `--min-activity` window, keeping it as given
+fn parse_min_activity(s: &str) -> Result<String, String> {
+    parse_duration(s).map_err(|e| e.to_string())?;
+    Ok(s.to_string())
+}
+
+/// Print every cache entry with its timestamp, age, TTL status, and repo count
+async fn print_cache_entries(cache: &Cache) {
+    let entries = cache.list_entries().await;
+    if entries.is_empty() {
+        println!("Cache is empty.");
+        return;
+    }
+
+    for entry in entries {
+        let written = i64::try_from(entry.timestamp)
+            .ok()
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+            .map_or_else(|| "unknown".to_string(), |dt| dt.to_rfc3339());
+        let status = if entry.valid { "valid" } else { "expired" };
+        println!(
+            "{:<10} {} ({} ago) {:<7} {} repos",
+            entry.provider,
+            written,
+            format_age(entry.age_secs),
+            status,
+            entry.repo_count
+        );
+    }
+}
+
+/// Print the cache location and every provider file's age against the TTL
+async fn print_cache_info(cache: &Cache) {
+    println!("Cache directory: {}", cache.dir().display());
+    println!("Default TTL: {} mins", cache.ttl_mins());
+
+    for entry in cache.list_entries().await {
+        let status = if entry.valid { "within TTL" } else { "expired" };
+        println!(
+            "{:<10} {} {} mins old, {status}",
+            entry.provider,
+            entry.path.display(),
+            entry.age_secs / 60
+        );
+    }
+}
+
+/// Format an age in seconds as a short human-readable string
+fn format_age(secs: u64) -> String {
+    let mins = secs / 60;
+    if mins < 60 {
+        format!("{mins}m")
+    } else if mins < 24 * 60 {
+        format!("{}h {}m", mins / 60, mins % 60)
+    } else {
+        format!("{}d", mins / (24 * 60))
+    }
+}
+
+/// Header for stale fallback data, e.g. "⚠ showing cached data from 3h 5m ago"
+///
+/// Uses the oldest stale provider and stays silent below `warn_mins`.
+fn stale_header(stale: &[StaleData], warn_mins: u64) -> Option<String> {
+    let oldest = stale.iter().map(|s| s.age_secs).max()?;
+    if oldest < warn_mins * 60 {
+        return None;
+    }
+
+    let providers: Vec<&str> = stale.iter().map(|s| s.provider.as_str()).collect();
+    Some(format!(
+        "⚠ showing cached data from {} ago ({})",
+        format_age(oldest),
+        providers.join(", ")
+    ))
+}
+
+/// Seed for `--pick`: the local calendar date as a `YYYYMMDD` number
+fn daily_seed() -> u64 {
+    let today = chrono::Local::now().date_naive();
+    u64::try_from(today.year()).unwrap_or(0) * 10_000
+        + u64::from(today.month()) * 100
+        + u64::from(today.day())
+}
+
+/// Keep `n` repositories chosen pseudo-randomly from `seed`
+///
+/// Runs a partial Fisher-Yates shuffle driven by `SplitMix64`, so the same pool
+/// and seed always produce the same picks in the same order.
+fn pick_seeded(repos: &mut Vec<Repo>, n: usize, seed: u64) {
+    let mut state = seed;
+    let count = n.min(repos.len());
+    for i in 0..count {
+        let remaining = (repos.len() - i) as u64;
+        let offset = usize::try_from(splitmix64(&mut state) % remaining).unwrap_or(0);
+        repos.swap(i, i + offset);
+    }
+    repos.truncate(count);
+}
+
+/// `SplitMix64` step: advances `state` and returns the next pseudo-random value
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str) -> Repo {
+        Repo {
+            provider: "github".to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_name_excluded() {
+        let patterns =
+            compile_name_patterns(&["^microsoft/".to_string(), "-awesome$".to_string()]).unwrap();
+
+        // Patterns see the full `owner/name`
+        assert!(name_excluded(&patterns, &repo("microsoft/vscode")));
+        assert!(name_excluded(&patterns, &repo("sindresorhus/rust-awesome")));
+        // Anchors hold: a match elsewhere in the name doesn't count
+        assert!(!name_excluded(&patterns, &repo("fans-of/microsoft")));
+        assert!(!name_excluded(&patterns, &repo("user/awesome-rust")));
+
+        assert!(!name_excluded(&[], &repo("microsoft/vscode")));
+    }
+
+    #[test]
+    fn test_owner_allowed() {
+        let allowed = |cli: &[&str], name: &str| {
+            let args = Args::parse_from([&["trotd"], cli].concat());
+            owner_allowed(&args, &repo(name))
+        };
+
+        assert!(allowed(&[], "rust-lang/rust"));
+        // Owners match case-insensitively, with spaces around commas ignored
+        assert!(allowed(
+            &["--owner", "Rust-Lang, tokio-rs"],
+            "rust-lang/rust"
+        ));
+        assert!(allowed(
+            &["--owner", "Rust-Lang, tokio-rs"],
+            "tokio-rs/axum"
+        ));
+        assert!(!allowed(&["--owner", "rust-lang"], "tokio-rs/axum"));
+        // Names without an owner never match a list
+        assert!(!allowed(&["--owner", "rust-lang"], "rust"));
+        assert!(!allowed(
+            &["--exclude-owner", "microsoft"],
+            "Microsoft/vscode"
+        ));
+        assert!(allowed(&["--exclude-owner", "microsoft"], "rust-lang/rust"));
+        assert!(allowed(&["--exclude-owner", "microsoft"], "rust"));
+        // Exclusion wins over inclusion
+        assert!(!allowed(
+            &["--owner", "tokio-rs", "--exclude-owner", "tokio-rs"],
+            "tokio-rs/axum"
+        ));
+    }
+
+    #[test]
+    fn test_pick_seeded() {
+        let pool: Vec<Repo> = (0..20).map(|i| repo(&format!("owner/repo-{i}"))).collect();
+        let pick = |n: usize, seed: u64| {
+            let mut repos = pool.clone();
+            pick_seeded(&mut repos, n, seed);
+            repos.into_iter().map(|r| r.name).collect::<Vec<_>>()
+        };
+
+        // Same pool and seed, same picks in the same order
+        let picked = pick(5, 20_261_015);
+        assert_eq!(picked, pick(5, 20_261_015));
+        assert_eq!(picked.len(), 5);
+        let mut unique = picked.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 5);
+        assert!(picked
+            .iter()
+            .all(|name| pool.iter().any(|r| &r.name == name)));
+
+        // Another day's seed gives another subset
+        assert_ne!(picked, pick(5, 20_261_016));
+        // Asking for more than the pool keeps everything
+        assert_eq!(pick(50, 1).len(), 20);
+        assert!(pick(0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_validate_providers() {
+        let names = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            validate_providers(&names(&["GitHub", "GL", "guthub", "crates"])).unwrap(),
+            ["github", "gitlab", "crates"]
+        );
+        assert!(validate_providers(&names(&["guthub"])).is_err());
+    }
+
+    #[test]
+    fn test_compile_name_patterns_error() {
+        let err = compile_name_patterns(&["ok".to_string(), "(unclosed".to_string()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid exclude-name pattern '(unclosed' (--exclude-name or general.exclude_name_patterns)"
+        );
     }
-    let total_chars = s.chars().count();
-    let ascii_chars = s.chars().filter(|c| c.is_ascii()).count();
-    ascii_chars as f64 / total_chars as f64
 }
@@ -3,7 +3,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Normalized repository structure across all providers
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Repo {
     pub provider: String,
     pub icon: String,
@@ -16,30 +16,287 @@ pub struct Repo {
     pub last_activity: Option<DateTime<Utc>>,
     #[serde(default)]
     pub topics: Vec<String>,
+    /// Whether this entry was served from the local cache
+    #[serde(default)]
+    pub from_cache: bool,
+    /// GitHub trending windows this repo appeared in (e.g. "daily", "weekly")
+    #[serde(default)]
+    pub trending_windows: Vec<String>,
+    /// Repository creation time (API providers only)
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Owner or namespace avatar (API providers only, JSON output only)
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    /// Fork count (API providers only)
+    #[serde(default)]
+    pub forks_count: Option<u64>,
+    /// Open issue count (API providers only)
+    #[serde(default)]
+    pub open_issues: Option<u64>,
+    /// Pinned via `general.pinned` rather than trending
+    #[serde(default)]
+    pub pinned: bool,
+    /// Which fetch path produced this repo (e.g. `github-trending`, `github-search`)
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Change in `stars_total` since the previous cached fetch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stars_delta: Option<i64>,
+    /// Recent download count, for package registries without stars (crates.io)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downloads: Option<u64>,
+}
+
+impl Repo {
+    /// Provider kind without any instance host, e.g. `gitea` for `gitea@codeberg.org`
+    pub fn provider_kind(&self) -> &str {
+        self.provider.split('@').next().unwrap_or(&self.provider)
+    }
+
+    /// Serialized field names, as accepted by `--json-fields`
+    pub const FIELDS: &'static [&'static str] = &[
+        "provider",
+        "icon",
+        "name",
+        "language",
+        "description",
+        "url",
+        "stars_today",
+        "stars_total",
+        "last_activity",
+        "topics",
+        "from_cache",
+        "trending_windows",
+        "created_at",
+        "avatar_url",
+        "forks_count",
+        "open_issues",
+        "pinned",
+        "source",
+        "stars_delta",
+        "downloads",
+    ];
+
+    /// Star velocity: total stars per day since creation
+    ///
+    /// Needs both `stars_total` and `created_at`, so it's only available for
+    /// API-backed providers. Repos younger than a day count as one day old.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn velocity(&self) -> Option<f64> {
+        let stars = self.stars_total?;
+        let created_at = self.created_at?;
+        let age_days = Utc::now().signed_duration_since(created_at).num_seconds() as f64 / 86_400.0;
+        Some(stars as f64 / age_days.max(1.0))
+    }
+}
+
+/// Owner (user, org, or top-level group) of a repo: the `name` part before `/`
+pub fn owner_of(repo: &Repo) -> Option<&str> {
+    repo.name
+        .split_once('/')
+        .map(|(owner, _)| owner)
+        .filter(|owner| !owner.is_empty())
+}
+
+/// Whether a repo URL is a plain `http(s)://` link, safe to hand to a browser
+///
+/// URLs come from providers and untrusted instances, so anything else
+/// (`javascript:`, `file:`, embedded control characters) is rejected.
+pub fn is_web_url(url: &str) -> bool {
+    let lower = url.get(..8).unwrap_or(url).to_ascii_lowercase();
+    let rest = if lower.starts_with("https://") {
+        &url[8..]
+    } else if lower.starts_with("http://") {
+        &url[7..]
+    } else {
+        return false;
+    };
+    !rest.is_empty() && !url.chars().any(char::is_control)
+}
+
+/// Sort order for the merged repository list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortKey {
+    /// Stars today, then total stars, then most recent activity (default)
+    #[default]
+    Relevance,
+    /// Stars per day since creation, highest first (needs `created_at`)
+    Velocity,
+    /// Total stars, highest first
+    Stars,
+    /// Stars gained today, highest first
+    StarsToday,
+    /// Most recent activity first
+    Recency,
+    /// Repository name, case-insensitive A to Z
+    Name,
+    /// Keep provider order (github, gitlab, gitea, codeberg), no sorting
+    Provider,
+}
+
+impl SortKey {
+    /// Sort repositories in place (stable, so ties keep provider order)
+    pub fn sort(self, repos: &mut [Repo]) {
+        match self {
+            Self::Relevance => repos.sort_by(|a, b| {
+                // `None` orders before `Some`, so comparing b to a puts missing data last
+                b.stars_today
+                    .cmp(&a.stars_today)
+                    .then_with(|| b.stars_total.cmp(&a.stars_total))
+                    .then_with(|| b.last_activity.cmp(&a.last_activity))
+            }),
+            Self::Stars => repos.sort_by_key(|repo| std::cmp::Reverse(repo.stars_total)),
+            Self::StarsToday => repos.sort_by_key(|repo| std::cmp::Reverse(repo.stars_today)),
+            Self::Recency => repos.sort_by_key(|repo| std::cmp::Reverse(repo.last_activity)),
+            Self::Name => repos.sort_by_cached_key(|repo| repo.name.to_lowercase()),
+            Self::Provider => {}
+            Self::Velocity => repos.sort_by(|a, b| match (a.velocity(), b.velocity()) {
+                (Some(va), Some(vb)) => vb.total_cmp(&va),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }),
+        }
+    }
+}
+
+/// GitHub trending window (the `since` parameter of the trending page)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Since {
+    #[default]
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Since {
+    /// Value of the trending page's `since` query parameter
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+        }
+    }
+
+    /// Length of the window in days (the Search API fallback's creation window)
+    pub fn days(self) -> i64 {
+        match self {
+            Self::Daily => 1,
+            Self::Weekly => 7,
+            Self::Monthly => 30,
+        }
+    }
 }
 
 /// Configuration for provider behavior
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ProviderCfg {
     #[allow(dead_code)]
     pub timeout_secs: u64,
     pub token: Option<String>,
-    pub base_url: Option<String>, // For Gitea
-    pub exclude_topics: Vec<String>, // For GitHub
+    pub tokens: Vec<String>, // For GitHub: rotation pool, `token` is its first entry
+    pub base_url: Option<String>, // For Gitea (and extra GitLab instances)
+    pub exclude_topics: Vec<String>, // For GitHub and GitLab
+    pub topics_all: Vec<String>, // For GitHub
+    pub topics_any: Vec<String>, // For GitHub
+    pub retry_on_empty: bool, // For GitHub
+    pub use_graphql: bool,   // For GitHub
+    pub merge_windows: bool, // For GitHub
+    pub since: Since,        // For GitHub
+    pub spoken_language: Option<String>, // For GitHub
+    pub use_languages_api: bool, // For GitLab
+    pub min_recent_commits: Option<u64>, // For GitLab
+    pub icons: IconSet,
+    pub verbose: bool,
+}
+
+/// Icon preset used for the provider column
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    /// Bracketed tags: `[GH]`, `[GL]`, `[GE]`
+    #[default]
+    Ascii,
+    /// Emoji: 🐙, 🦊, 🍵
+    Emoji,
+    /// Nerd Font glyphs (needs a patched font)
+    Nerdfont,
+}
+
+impl IconSet {
+    /// Resolve the icon for a provider, falling back to its built-in ASCII icon
+    pub fn resolve(self, provider_id: &str, ascii: &'static str) -> &'static str {
+        match (self, provider_id) {
+            (Self::Emoji, "github") => "🐙",
+            (Self::Emoji, "gitlab") => "🦊",
+            (Self::Emoji, "gitea") => "🍵",
+            (Self::Emoji, "codeberg") => "🏔",
+            (Self::Emoji, "bitbucket") => "🪣",
+            (Self::Emoji, "crates") => "📦",
+            (Self::Nerdfont, "github") => "\u{f09b}",
+            (Self::Nerdfont, "gitlab") => "\u{f296}",
+            // Codeberg runs Forgejo, so it shares Gitea's git glyph
+            (Self::Nerdfont, "gitea" | "codeberg") => "\u{f1d3}",
+            (Self::Nerdfont, "bitbucket") => "\u{f171}",
+            (Self::Nerdfont, "crates") => "\u{e7a8}",
+            _ => ascii,
+        }
+    }
 }
 
 /// Language filter configuration
 #[derive(Debug, Clone)]
 pub struct LanguageFilter {
     pub languages: Vec<String>,
+    /// Languages always rejected, even when `languages` is empty
+    pub exclude: Vec<String>,
+}
+
+/// Drop case-insensitive duplicates (first one wins)
+fn dedupe_languages(languages: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::with_capacity(languages.len());
+    for lang in languages {
+        if !unique.iter().any(|seen| seen.eq_ignore_ascii_case(&lang)) {
+            unique.push(lang);
+        }
+    }
+    unique
 }
 
 impl LanguageFilter {
+    /// Create a filter, dropping case-insensitive duplicates (first one wins)
     pub fn new(languages: Vec<String>) -> Self {
-        Self { languages }
+        Self {
+            languages: dedupe_languages(languages),
+            exclude: vec![],
+        }
     }
 
+    /// Also reject these languages (case-insensitive)
+    #[must_use]
+    pub fn excluding(mut self, languages: Vec<String>) -> Self {
+        self.exclude = dedupe_languages(languages);
+        self
+    }
+
+    /// True when the filter rejects anything, so languages must be known
+    pub fn is_active(&self) -> bool {
+        !self.languages.is_empty() || !self.exclude.is_empty()
+    }
+
+    /// Included (or no include list) and not excluded; an unknown language
+    /// only passes without an include list
     pub fn matches(&self, language: Option<&String>) -> bool {
+        if language.is_some_and(|lang| self.exclude.iter().any(|ex| lang.eq_ignore_ascii_case(ex)))
+        {
+            return false;
+        }
+
         if self.languages.is_empty() {
             return true;
         }
@@ -68,20 +325,205 @@ pub trait Provider: Send + Sync {
         limit: usize,
         langs: &LanguageFilter,
     ) -> anyhow::Result<Vec<Repo>>;
+
+    /// Enrichment: check whether a repository has a README
+    ///
+    /// Costs one extra request per repository. Returns `None` when the
+    /// provider can't tell, in which case the repository is left untouched.
+    async fn has_readme(&self, _cfg: &ProviderCfg, _repo: &Repo) -> anyhow::Result<Option<bool>> {
+        Ok(None)
+    }
+
+    /// Fetch a single repository by `owner/name` (used for pinned repos)
+    async fn fetch_one(&self, _cfg: &ProviderCfg, name: &str) -> anyhow::Result<Repo> {
+        anyhow::bail!("{} can't fetch single repositories ({name})", self.id())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_web_url() {
+        assert!(is_web_url("https://github.com/a/b"));
+        assert!(is_web_url("HTTP://example.com"));
+        assert!(!is_web_url("https://"));
+        assert!(!is_web_url("javascript:alert(1)"));
+        assert!(!is_web_url("file:///etc/passwd"));
+        assert!(!is_web_url("https://x.com/\x1b]8;;evil\x07"));
+        assert!(!is_web_url(""));
+    }
+
     #[test]
     fn test_language_filter_empty() {
         let filter = LanguageFilter::new(vec![]);
+        assert!(!filter.is_active());
         assert!(filter.matches(Some(&"Rust".to_string())));
         assert!(filter.matches(Some(&"Go".to_string())));
         assert!(filter.matches(None));
     }
 
+    fn repo(name: &str, stars_total: Option<u64>, age_days: Option<i64>) -> Repo {
+        Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: name.to_string(),
+            stars_total,
+            created_at: age_days.map(|d| Utc::now() - chrono::Duration::days(d)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_icon_set_resolve() {
+        assert_eq!(IconSet::Ascii.resolve("github", "[GH]"), "[GH]");
+        assert_eq!(IconSet::Emoji.resolve("gitlab", "[GL]"), "🦊");
+        assert_eq!(IconSet::Nerdfont.resolve("gitea", "[GE]"), "\u{f1d3}");
+        assert_eq!(IconSet::Emoji.resolve("crates", "[CR]"), "📦");
+        assert_eq!(IconSet::Nerdfont.resolve("bitbucket", "[BB]"), "\u{f171}");
+        // Unknown providers keep their own icon
+        assert_eq!(IconSet::Emoji.resolve("other", "[??]"), "[??]");
+    }
+
+    #[test]
+    fn test_velocity() {
+        let v = repo("a/b", Some(1000), Some(10)).velocity().unwrap();
+        assert!((v - 100.0).abs() < 0.1);
+        // Younger than a day counts as one day
+        let v = repo("a/b", Some(50), Some(0)).velocity().unwrap();
+        assert!((v - 50.0).abs() < 0.1);
+        assert!(repo("a/b", None, Some(10)).velocity().is_none());
+        assert!(repo("a/b", Some(10), None).velocity().is_none());
+    }
+
+    #[test]
+    fn test_since() {
+        assert_eq!(Since::default(), Since::Daily);
+        assert_eq!(Since::Weekly.as_str(), "weekly");
+        assert_eq!(Since::Monthly.days(), 30);
+        let since: Since = serde_json::from_str("\"monthly\"").unwrap();
+        assert_eq!(since, Since::Monthly);
+    }
+
+    #[test]
+    fn test_owner_of() {
+        assert_eq!(
+            owner_of(&repo("rust-lang/rust", None, None)),
+            Some("rust-lang")
+        );
+        assert_eq!(
+            owner_of(&repo("group/sub/project", None, None)),
+            Some("group")
+        );
+        assert_eq!(owner_of(&repo("noslash", None, None)), None);
+        assert_eq!(owner_of(&repo("/orphan", None, None)), None);
+    }
+
+    #[test]
+    fn test_sort_velocity() {
+        let mut repos = vec![
+            repo("none", Some(10), None),
+            repo("slow", Some(100), Some(100)),
+            repo("fast", Some(1000), Some(10)),
+        ];
+        SortKey::Velocity.sort(&mut repos);
+        let names: Vec<&str> = repos.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["fast", "slow", "none"]);
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut big = repo("Zeta/big", Some(900), None);
+        big.last_activity = Some(Utc::now() - chrono::Duration::days(3));
+        let mut hot = repo("alpha/hot", Some(10), None);
+        hot.stars_today = Some(40);
+        hot.last_activity = Some(Utc::now());
+        let unknown = repo("beta/unknown", None, None);
+        let repos = vec![unknown, big, hot];
+
+        let sorted = |key: SortKey| {
+            let mut repos = repos.clone();
+            key.sort(&mut repos);
+            repos.into_iter().map(|r| r.name).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            sorted(SortKey::Stars),
+            vec!["Zeta/big", "alpha/hot", "beta/unknown"]
+        );
+        assert_eq!(
+            sorted(SortKey::StarsToday),
+            vec!["alpha/hot", "beta/unknown", "Zeta/big"]
+        );
+        assert_eq!(
+            sorted(SortKey::Recency),
+            vec!["alpha/hot", "Zeta/big", "beta/unknown"]
+        );
+        assert_eq!(
+            sorted(SortKey::Name),
+            vec!["alpha/hot", "beta/unknown", "Zeta/big"]
+        );
+        assert_eq!(
+            sorted(SortKey::Provider),
+            vec!["beta/unknown", "Zeta/big", "alpha/hot"]
+        );
+    }
+
+    #[test]
+    fn test_sort_relevance() {
+        let mut trending = repo("trending", Some(50), None);
+        trending.stars_today = Some(5);
+        let mut hot = repo("hot", Some(10), None);
+        hot.stars_today = Some(40);
+        let mut recent = repo("recent", Some(200), None);
+        recent.last_activity = Some(Utc::now());
+        let stale = repo("stale", Some(200), None);
+
+        let mut repos = vec![stale, repo("small", Some(3), None), recent, trending, hot];
+        SortKey::Relevance.sort(&mut repos);
+        let names = |repos: &[Repo]| repos.iter().map(|r| r.name.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            names(&repos),
+            vec!["hot", "trending", "recent", "stale", "small"]
+        );
+
+        let before = names(&repos);
+        SortKey::Provider.sort(&mut repos);
+        assert_eq!(names(&repos), before);
+    }
+
+    #[test]
+    fn test_repo_deserialize_without_optional_fields() {
+        // Cache entries written before newer fields existed must still load
+        let json = r#"{
+            "provider": "gitea",
+            "icon": "[GE]",
+            "name": "user/repo",
+            "language": null,
+            "description": null,
+            "url": "https://gitea.com/user/repo",
+            "stars_today": null,
+            "stars_total": 5,
+            "last_activity": null,
+            "topics": []
+        }"#;
+        let repo: Repo = serde_json::from_str(json).unwrap();
+        assert!(repo.avatar_url.is_none());
+        assert!(repo.created_at.is_none());
+        assert!(!repo.from_cache);
+    }
+
+    #[test]
+    fn test_language_filter_dedupes() {
+        let filter = LanguageFilter::new(vec![
+            "rust".to_string(),
+            "Rust".to_string(),
+            "go".to_string(),
+            "RUST".to_string(),
+        ]);
+        assert_eq!(filter.languages, vec!["rust".to_string(), "go".to_string()]);
+    }
+
     #[test]
     fn test_language_filter_case_insensitive() {
         let filter = LanguageFilter::new(vec!["rust".to_string(), "go".to_string()]);
@@ -91,4 +533,28 @@ mod tests {
         assert!(!filter.matches(Some(&"Python".to_string())));
         assert!(!filter.matches(None));
     }
+
+    #[test]
+    fn test_language_filter_exclude_only() {
+        let filter = LanguageFilter::new(vec![])
+            .excluding(vec!["JavaScript".to_string(), "php".to_string()]);
+        assert!(filter.is_active());
+        assert!(!filter.matches(Some(&"javascript".to_string())));
+        assert!(!filter.matches(Some(&"PHP".to_string())));
+        assert!(filter.matches(Some(&"Rust".to_string())));
+        // Unknown languages aren't excluded
+        assert!(filter.matches(None));
+    }
+
+    #[test]
+    fn test_language_filter_include_and_exclude() {
+        let filter = LanguageFilter::new(vec!["rust".to_string(), "go".to_string()])
+            .excluding(vec!["Go".to_string()]);
+        assert!(filter.matches(Some(&"Rust".to_string())));
+        assert!(!filter.matches(Some(&"go".to_string())));
+        assert!(!filter.matches(Some(&"Python".to_string())));
+        assert!(!filter.matches(None));
+
+        assert!(!LanguageFilter::new(vec![]).is_active());
+    }
 }
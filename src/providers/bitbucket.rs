@@ -0,0 +1,246 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::http::HttpClient;
+use crate::model::{LanguageFilter, Provider, ProviderCfg, Repo};
+
+/// Bitbucket Cloud REST API root
+const API_URL: &str = "https://api.bitbucket.org/2.0";
+
+/// Upper bound on pages fetched per run (100 repositories each)
+const MAX_PAGES: usize = 3;
+
+/// Bitbucket Cloud provider using the repositories API
+pub struct Bitbucket {
+    http: HttpClient,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketPage {
+    values: Vec<BitbucketRepository>,
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketRepository {
+    full_name: String,
+    description: Option<String>,
+    language: Option<String>,
+    links: BitbucketLinks,
+    updated_on: Option<String>,
+    created_on: Option<String>,
+    owner: Option<BitbucketOwner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketLinks {
+    html: BitbucketLink,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketLink {
+    href: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketOwner {
+    links: Option<BitbucketOwnerLinks>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketOwnerLinks {
+    avatar: Option<BitbucketLink>,
+}
+
+impl Bitbucket {
+    pub fn new(timeout_secs: u64) -> Result<Self> {
+        Ok(Self {
+            http: HttpClient::new(timeout_secs)?,
+        })
+    }
+
+    /// Create a Bitbucket provider with a custom `HttpClient`
+    #[allow(dead_code)]
+    pub fn with_client(http: HttpClient) -> Self {
+        Self { http }
+    }
+
+    /// Fetch recently updated public repositories
+    ///
+    /// Follows the `next` link until `limit` repositories pass the language
+    /// filter, or `MAX_PAGES` pages have been fetched.
+    async fn fetch_repos(
+        &self,
+        cfg: &ProviderCfg,
+        limit: usize,
+        langs: &LanguageFilter,
+    ) -> Result<Vec<BitbucketRepository>> {
+        let base_url = cfg.base_url.as_deref().unwrap_or(API_URL);
+        let mut url = Some(format!(
+            "{base_url}/repositories?sort=-updated_on&pagelen=100"
+        ));
+
+        let mut matches = Vec::new();
+        let mut pages = 0;
+
+        while let Some(page_url) = url {
+            if pages >= MAX_PAGES || matches.len() >= limit {
+                break;
+            }
+
+            let page: BitbucketPage = self.http.get_json(&page_url, cfg.token.as_deref()).await?;
+            pages += 1;
+
+            matches.extend(
+                page.values
+                    .into_iter()
+                    .filter(|r| langs.matches(Self::language(r.language.as_deref()).as_ref())),
+            );
+            url = page.next;
+        }
+
+        if cfg.verbose {
+            eprintln!(
+                "  📄 bitbucket: {} matches from {pages} page(s)",
+                matches.len()
+            );
+        }
+
+        matches.truncate(limit);
+        Ok(matches)
+    }
+
+    /// Normalize Bitbucket's lowercase language (empty when unset), e.g. `python` -> `Python`
+    fn language(language: Option<&str>) -> Option<String> {
+        let language = language.map(str::trim).filter(|l| !l.is_empty())?;
+        let mut chars = language.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+    }
+
+    /// Convert an API repository into a `Repo`
+    fn repo_from_api(&self, r: BitbucketRepository, cfg: &ProviderCfg) -> Repo {
+        let parse = |s: Option<String>| {
+            s.and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+        };
+
+        Repo {
+            provider: self.id().to_string(),
+            icon: cfg.icons.resolve(self.id(), self.icon()).to_string(),
+            name: r.full_name,
+            language: Self::language(r.language.as_deref()),
+            description: r.description.filter(|d| !d.trim().is_empty()),
+            url: r.links.html.href,
+            stars_today: None, // Bitbucket has no stars at all
+            stars_total: None,
+            last_activity: parse(r.updated_on),
+            topics: vec![],
+            from_cache: false,
+            trending_windows: vec![],
+            created_at: parse(r.created_on),
+            avatar_url: r
+                .owner
+                .and_then(|o| o.links)
+                .and_then(|l| l.avatar)
+                .map(|a| a.href),
+            forks_count: None,
+            open_issues: None,
+            pinned: false,
+            source: Some("bitbucket-api".to_string()),
+            stars_delta: None,
+            downloads: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for Bitbucket {
+    fn id(&self) -> &'static str {
+        "bitbucket"
+    }
+
+    fn icon(&self) -> &'static str {
+        "[BB]"
+    }
+
+    async fn top_today(
+        &self,
+        cfg: &ProviderCfg,
+        limit: usize,
+        langs: &LanguageFilter,
+    ) -> Result<Vec<Repo>> {
+        let repositories = self.fetch_repos(cfg, limit, langs).await?;
+
+        let repos = repositories
+            .into_iter()
+            .map(|r| self.repo_from_api(r, cfg))
+            .collect();
+
+        Ok(repos)
+    }
+
+    async fn fetch_one(&self, cfg: &ProviderCfg, name: &str) -> Result<Repo> {
+        let base_url = cfg.base_url.as_deref().unwrap_or(API_URL);
+        let url = format!("{base_url}/repositories/{name}");
+        let repo: BitbucketRepository = self.http.get_json(&url, cfg.token.as_deref()).await?;
+        Ok(self.repo_from_api(repo, cfg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitbucket_provider_metadata() {
+        let bitbucket = Bitbucket::new(6).unwrap();
+        assert_eq!(bitbucket.id(), "bitbucket");
+        assert_eq!(bitbucket.icon(), "[BB]");
+    }
+
+    #[test]
+    fn test_language() {
+        assert_eq!(
+            Bitbucket::language(Some("python")),
+            Some("Python".to_string())
+        );
+        assert_eq!(Bitbucket::language(Some("")), None);
+        assert_eq!(Bitbucket::language(None), None);
+    }
+
+    #[test]
+    fn test_repo_from_api() {
+        let page: BitbucketPage = serde_json::from_str(
+            r#"{
+                "values": [{
+                    "full_name": "atlassian/python-bitbucket",
+                    "description": "",
+                    "language": "python",
+                    "links": {"html": {"href": "https://bitbucket.org/atlassian/python-bitbucket"}},
+                    "updated_on": "2024-05-01T10:00:00.000000+00:00",
+                    "created_on": "2015-01-01T00:00:00+00:00",
+                    "owner": {"links": {"avatar": {"href": "https://bitbucket.org/avatar.png"}}}
+                }],
+                "next": "https://api.bitbucket.org/2.0/repositories?page=2"
+            }"#,
+        )
+        .unwrap();
+        assert!(page.next.is_some());
+
+        let cfg = ProviderCfg::default();
+        let bitbucket = Bitbucket::new(6).unwrap();
+        let repo = bitbucket.repo_from_api(page.values.into_iter().next().unwrap(), &cfg);
+        assert_eq!(repo.name, "atlassian/python-bitbucket");
+        assert_eq!(repo.url, "https://bitbucket.org/atlassian/python-bitbucket");
+        assert_eq!(repo.language.as_deref(), Some("Python"));
+        assert_eq!(repo.description, None);
+        assert!(repo.last_activity.is_some());
+        assert_eq!(
+            repo.avatar_url.as_deref(),
+            Some("https://bitbucket.org/avatar.png")
+        );
+    }
+}
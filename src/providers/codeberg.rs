@@ -0,0 +1,107 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::http::HttpClient;
+use crate::model::{LanguageFilter, Provider, ProviderCfg, Repo};
+use crate::providers::Gitea;
+
+/// Codeberg's public Forgejo instance
+const BASE_URL: &str = "https://codeberg.org";
+
+/// Codeberg provider: the Gitea search API against codeberg.org
+pub struct Codeberg {
+    gitea: Gitea,
+}
+
+impl Codeberg {
+    pub fn new(timeout_secs: u64) -> Result<Self> {
+        Ok(Self {
+            gitea: Gitea::new(timeout_secs)?,
+        })
+    }
+
+    /// Create a Codeberg provider with a custom `HttpClient`
+    #[allow(dead_code)]
+    pub fn with_client(http: HttpClient) -> Self {
+        Self {
+            gitea: Gitea::with_client(http),
+        }
+    }
+
+    /// Provider config for the wrapped Gitea calls, defaulting to codeberg.org
+    fn gitea_cfg(cfg: &ProviderCfg) -> ProviderCfg {
+        let mut cfg = cfg.clone();
+        cfg.base_url.get_or_insert_with(|| BASE_URL.to_string());
+        cfg
+    }
+
+    /// Attribute a repository fetched through the Gitea API to Codeberg
+    fn rebrand(&self, mut repo: Repo, cfg: &ProviderCfg) -> Repo {
+        repo.provider = self.id().to_string();
+        repo.icon = cfg.icons.resolve(self.id(), self.icon()).to_string();
+        repo.source = Some("codeberg-api".to_string());
+        repo
+    }
+}
+
+#[async_trait]
+impl Provider for Codeberg {
+    fn id(&self) -> &'static str {
+        "codeberg"
+    }
+
+    fn icon(&self) -> &'static str {
+        "[CB]"
+    }
+
+    async fn top_today(
+        &self,
+        cfg: &ProviderCfg,
+        limit: usize,
+        langs: &LanguageFilter,
+    ) -> Result<Vec<Repo>> {
+        let repos = self
+            .gitea
+            .top_today(&Self::gitea_cfg(cfg), limit, langs)
+            .await?;
+        Ok(repos.into_iter().map(|r| self.rebrand(r, cfg)).collect())
+    }
+
+    async fn has_readme(&self, cfg: &ProviderCfg, repo: &Repo) -> Result<Option<bool>> {
+        self.gitea.has_readme(&Self::gitea_cfg(cfg), repo).await
+    }
+
+    async fn fetch_one(&self, cfg: &ProviderCfg, name: &str) -> Result<Repo> {
+        let repo = self.gitea.fetch_one(&Self::gitea_cfg(cfg), name).await?;
+        Ok(self.rebrand(repo, cfg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg(base_url: Option<&str>) -> ProviderCfg {
+        ProviderCfg {
+            base_url: base_url.map(ToString::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_codeberg_provider_metadata() {
+        let codeberg = Codeberg::new(6).unwrap();
+        assert_eq!(codeberg.id(), "codeberg");
+        assert_eq!(codeberg.icon(), "[CB]");
+    }
+
+    #[test]
+    fn test_gitea_cfg_defaults_to_codeberg() {
+        assert_eq!(
+            Codeberg::gitea_cfg(&cfg(None)).base_url.as_deref(),
+            Some(BASE_URL)
+        );
+        let custom = Codeberg::gitea_cfg(&cfg(Some("http://127.0.0.1:3000")));
+        assert_eq!(custom.base_url.as_deref(), Some("http://127.0.0.1:3000"));
+    }
+}
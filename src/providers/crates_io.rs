@@ -0,0 +1,233 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::http::HttpClient;
+use crate::model::{LanguageFilter, Provider, ProviderCfg, Repo};
+
+/// crates.io API root
+const API_URL: &str = "https://crates.io/api/v1";
+
+/// Largest page crates.io serves
+const MAX_PER_PAGE: usize = 100;
+
+/// crates.io provider: the most downloaded crates of recent days
+pub struct CratesIo {
+    http: HttpClient,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesPage {
+    crates: Vec<CratesIoCrate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrateResponse {
+    #[serde(rename = "crate")]
+    krate: CratesIoCrate,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesIoCrate {
+    name: String,
+    description: Option<String>,
+    max_version: String,
+    repository: Option<String>,
+    #[serde(default)]
+    recent_downloads: Option<u64>,
+    updated_at: Option<String>,
+    created_at: Option<String>,
+    keywords: Option<Vec<String>>,
+}
+
+impl CratesIo {
+    pub fn new(timeout_secs: u64) -> Result<Self> {
+        Ok(Self {
+            http: HttpClient::new(timeout_secs)?,
+        })
+    }
+
+    /// Create a crates.io provider with a custom `HttpClient`
+    pub fn with_client(http: HttpClient) -> Self {
+        Self { http }
+    }
+
+    /// Language of every crate
+    fn language() -> String {
+        "Rust".to_string()
+    }
+
+    /// Convert an API crate into a `Repo`
+    ///
+    /// The name stays the bare crate name so it identifies the crate across
+    /// releases; the latest version leads the description. `downloads` carries
+    /// the recent (90-day) download count, and crates have no stars. Crates
+    /// without a repository link to their crates.io page.
+    fn repo_from_api(&self, c: CratesIoCrate, cfg: &ProviderCfg) -> Repo {
+        let parse = |s: Option<String>| {
+            s.and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+        };
+        let url = c
+            .repository
+            .filter(|r| !r.trim().is_empty())
+            .unwrap_or_else(|| format!("https://crates.io/crates/{}", c.name));
+
+        let version = format!("v{}", c.max_version);
+        let description = match c.description.as_deref().map(str::trim) {
+            Some(d) if !d.is_empty() => format!("{version} · {d}"),
+            _ => version,
+        };
+
+        Repo {
+            provider: self.id().to_string(),
+            icon: cfg.icons.resolve(self.id(), self.icon()).to_string(),
+            name: c.name,
+            language: Some(Self::language()),
+            description: Some(description),
+            url,
+            stars_today: None,
+            stars_total: None,
+            last_activity: parse(c.updated_at),
+            topics: c.keywords.unwrap_or_default(),
+            from_cache: false,
+            trending_windows: vec![],
+            created_at: parse(c.created_at),
+            avatar_url: None,
+            forks_count: None,
+            open_issues: None,
+            pinned: false,
+            source: Some("crates-api".to_string()),
+            stars_delta: None,
+            downloads: c.recent_downloads,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for CratesIo {
+    fn id(&self) -> &'static str {
+        "crates"
+    }
+
+    fn icon(&self) -> &'static str {
+        "[CR]"
+    }
+
+    async fn top_today(
+        &self,
+        cfg: &ProviderCfg,
+        limit: usize,
+        langs: &LanguageFilter,
+    ) -> Result<Vec<Repo>> {
+        // Every crate is Rust, so a filter without it matches nothing
+        if !langs.matches(Some(&Self::language())) {
+            return Ok(vec![]);
+        }
+
+        let base_url = cfg.base_url.as_deref().unwrap_or(API_URL);
+        let per_page = limit.clamp(1, MAX_PER_PAGE);
+        let url = format!("{base_url}/crates?sort=recent-downloads&per_page={per_page}");
+        let page: CratesPage = self.http.get_json(&url, cfg.token.as_deref()).await?;
+
+        if cfg.verbose {
+            eprintln!("  📄 crates: {} crates", page.crates.len());
+        }
+
+        let repos = page
+            .crates
+            .into_iter()
+            .take(limit)
+            .map(|c| self.repo_from_api(c, cfg))
+            .collect();
+
+        Ok(repos)
+    }
+
+    async fn fetch_one(&self, cfg: &ProviderCfg, name: &str) -> Result<Repo> {
+        let base_url = cfg.base_url.as_deref().unwrap_or(API_URL);
+        let url = format!("{base_url}/crates/{name}");
+        let response: CrateResponse = self.http.get_json(&url, cfg.token.as_deref()).await?;
+        Ok(self.repo_from_api(response.krate, cfg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg(base_url: Option<String>) -> ProviderCfg {
+        ProviderCfg {
+            base_url,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_crates_io_provider_metadata() {
+        let crates = CratesIo::new(6).unwrap();
+        assert_eq!(crates.id(), "crates");
+        assert_eq!(crates.icon(), "[CR]");
+    }
+
+    #[test]
+    fn test_repo_from_api() {
+        let page: CratesPage = serde_json::from_str(
+            r#"{
+                "crates": [{
+                    "name": "syn",
+                    "description": " Parser for Rust source code ",
+                    "max_version": "2.0.87",
+                    "repository": "https://github.com/dtolnay/syn",
+                    "downloads": 912345678,
+                    "recent_downloads": 98765432,
+                    "updated_at": "2024-11-01T10:00:00.123456+00:00",
+                    "created_at": "2016-09-07T00:00:00+00:00"
+                }, {
+                    "name": "tiny",
+                    "description": null,
+                    "max_version": "0.1.0",
+                    "repository": null,
+                    "downloads": 12,
+                    "updated_at": null,
+                    "created_at": null
+                }],
+                "meta": {"total": 2}
+            }"#,
+        )
+        .unwrap();
+
+        let crates = CratesIo::new(6).unwrap();
+        let mut repos = page
+            .crates
+            .into_iter()
+            .map(|c| crates.repo_from_api(c, &cfg(None)));
+
+        let syn = repos.next().unwrap();
+        assert_eq!(syn.name, "syn");
+        assert_eq!(syn.url, "https://github.com/dtolnay/syn");
+        assert_eq!(syn.language.as_deref(), Some("Rust"));
+        assert_eq!(
+            syn.description.as_deref(),
+            Some("v2.0.87 · Parser for Rust source code")
+        );
+        assert_eq!(syn.downloads, Some(98_765_432));
+        assert_eq!(syn.stars_total, None);
+        assert!(syn.last_activity.is_some());
+
+        let tiny = repos.next().unwrap();
+        assert_eq!(tiny.url, "https://crates.io/crates/tiny");
+        assert_eq!(tiny.description.as_deref(), Some("v0.1.0"));
+        assert_eq!(tiny.downloads, None);
+    }
+
+    #[tokio::test]
+    async fn test_language_filter_skips_request() {
+        // An unroutable base URL would fail if a request were made
+        let crates = CratesIo::new(1).unwrap();
+        let cfg = cfg(Some("http://127.0.0.1:9".to_string()));
+        let filter = LanguageFilter::new(vec!["go".to_string()]);
+        let repos = crates.top_today(&cfg, 5, &filter).await.unwrap();
+        assert!(repos.is_empty());
+    }
+}
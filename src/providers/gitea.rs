@@ -23,6 +23,20 @@ struct GiteaRepository {
     stars_count: Option<u64>,
     language: Option<String>,
     updated_at: Option<String>,
+    created_at: Option<String>,
+    owner: Option<GiteaOwner>,
+    forks_count: Option<u64>,
+    open_issues_count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaOwner {
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaContent {
+    name: String,
 }
 
 impl Gitea {
@@ -32,7 +46,7 @@ impl Gitea {
         })
     }
 
-    /// Create a Gitea provider with a custom HttpClient
+    /// Create a Gitea provider with a custom `HttpClient`
     #[allow(dead_code)]
     pub fn with_client(http: HttpClient) -> Self {
         Self { http }
@@ -58,6 +72,48 @@ impl Gitea {
 
         Ok(filtered)
     }
+
+    /// Whether a directory listing contains a README (any extension, any case)
+    fn has_readme_file(contents: &[GiteaContent]) -> bool {
+        contents
+            .iter()
+            .any(|c| c.name.to_ascii_lowercase().starts_with("readme"))
+    }
+
+    /// Convert an API repository into a `Repo`
+    fn repo_from_api(&self, r: GiteaRepository, cfg: &ProviderCfg) -> Repo {
+        let created_at = r
+            .created_at
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+        let last_activity = r
+            .updated_at
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+
+        Repo {
+            provider: self.id().to_string(),
+            icon: cfg.icons.resolve(self.id(), self.icon()).to_string(),
+            name: r.full_name,
+            language: r.language,
+            description: r.description,
+            url: r.html_url,
+            stars_today: None, // Gitea API doesn't provide daily stars
+            stars_total: r.stars_count,
+            last_activity,
+            topics: vec![], // Gitea API doesn't provide topics in search
+            from_cache: false,
+            trending_windows: vec![],
+            created_at,
+            avatar_url: r.owner.and_then(|o| o.avatar_url),
+            forks_count: r.forks_count,
+            open_issues: r.open_issues_count,
+            pinned: false,
+            source: Some("gitea-api".to_string()),
+            stars_delta: None,
+            downloads: None,
+        }
+    }
 }
 
 #[async_trait]
@@ -84,29 +140,26 @@ impl Provider for Gitea {
             .into_iter()
             .filter(|r| langs.matches(r.language.as_ref()))
             .take(limit)
-            .map(|r| {
-                let last_activity = r
-                    .updated_at
-                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&chrono::Utc));
-
-                Repo {
-                    provider: self.id().to_string(),
-                    icon: self.icon().to_string(),
-                    name: r.full_name,
-                    language: r.language,
-                    description: r.description,
-                    url: r.html_url,
-                    stars_today: None, // Gitea API doesn't provide daily stars
-                    stars_total: r.stars_count,
-                    last_activity,
-                    topics: vec![], // Gitea API doesn't provide topics in search
-                }
-            })
+            .map(|r| self.repo_from_api(r, cfg))
             .collect();
 
         Ok(repos)
     }
+
+    async fn has_readme(&self, cfg: &ProviderCfg, repo: &Repo) -> Result<Option<bool>> {
+        let base_url = cfg.base_url.as_deref().unwrap_or("https://gitea.com");
+        // Gitea has no dedicated readme endpoint; list the root directory instead
+        let url = format!("{base_url}/api/v1/repos/{}/contents", repo.name);
+        let contents: Vec<GiteaContent> = self.http.get_json(&url, cfg.token.as_deref()).await?;
+        Ok(Some(Self::has_readme_file(&contents)))
+    }
+
+    async fn fetch_one(&self, cfg: &ProviderCfg, name: &str) -> Result<Repo> {
+        let base_url = cfg.base_url.as_deref().unwrap_or("https://gitea.com");
+        let url = format!("{base_url}/api/v1/repos/{name}");
+        let repo: GiteaRepository = self.http.get_json(&url, cfg.token.as_deref()).await?;
+        Ok(self.repo_from_api(repo, cfg))
+    }
 }
 
 #[cfg(test)]
@@ -120,6 +173,22 @@ mod tests {
         assert_eq!(gitea.icon(), "[GE]");
     }
 
+    #[test]
+    fn test_has_readme_file() {
+        let listing = |names: &[&str]| -> Vec<GiteaContent> {
+            names
+                .iter()
+                .map(|n| GiteaContent {
+                    name: (*n).to_string(),
+                })
+                .collect()
+        };
+        assert!(Gitea::has_readme_file(&listing(&["src", "README.md"])));
+        assert!(Gitea::has_readme_file(&listing(&["readme.rst"])));
+        assert!(!Gitea::has_readme_file(&listing(&["LICENSE", "main.go"])));
+        assert!(!Gitea::has_readme_file(&[]));
+    }
+
     #[tokio::test]
     async fn test_gitea_api() {
         // Use max_retries(0) to avoid retry delays in tests
@@ -130,10 +199,8 @@ mod tests {
             .unwrap();
         let gitea = Gitea::with_client(http);
         let cfg = ProviderCfg {
-            timeout_secs: 10,
-            token: None,
             base_url: Some("https://gitea.com".to_string()),
-            exclude_topics: vec![],
+            ..Default::default()
         };
         let filter = LanguageFilter::new(vec![]);
 
@@ -144,6 +211,7 @@ mod tests {
                 for repo in repos {
                     assert_eq!(repo.provider, "gitea");
                     assert_eq!(repo.icon, "[GE]");
+                    assert_eq!(repo.source.as_deref(), Some("gitea-api"));
                     assert!(repo.last_activity.is_some());
                 }
             }
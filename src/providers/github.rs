@@ -1,14 +1,35 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use scraper::{Html, Selector};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Semaphore;
 
-use crate::http::HttpClient;
-use crate::model::{LanguageFilter, Provider, ProviderCfg, Repo};
+use crate::http::{is_rate_limited, HttpClient, RateLimitedError};
+use crate::model::{LanguageFilter, Provider, ProviderCfg, Repo, Since};
+
+/// Delay before re-fetching a trending page that came back empty
+const EMPTY_RETRY_DELAY_MS: u64 = 1500;
+
+/// Default concurrent trending-page requests (`github.html_concurrency`)
+const DEFAULT_HTML_CONCURRENCY: usize = 2;
+
+/// Default concurrent REST API requests (`github.api_concurrency`)
+const DEFAULT_API_CONCURRENCY: usize = 4;
 
 /// GitHub provider using HTML scraping of trending page
 pub struct GitHub {
     http: HttpClient,
+    /// Limits concurrent trending-page scrapes
+    html_permits: Semaphore,
+    /// Limits concurrent REST API calls
+    api_permits: Semaphore,
+    /// Position in `ProviderCfg::tokens` of the last token that wasn't rate-limited
+    token_index: AtomicUsize,
+    /// GraphQL endpoint, `GRAPHQL_URL` outside tests
+    graphql_url: String,
 }
 
 struct TrendingRepo {
@@ -19,6 +40,7 @@ struct TrendingRepo {
     stars_total: Option<u64>,
     url: String,
     topics: Vec<String>,
+    windows: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,50 +57,419 @@ struct GitHubRepository {
     language: Option<String>,
     topics: Vec<String>,
     updated_at: String,
+    created_at: Option<String>,
+    owner: Option<GitHubOwner>,
+    forks_count: Option<u64>,
+    open_issues_count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubOwner {
+    avatar_url: Option<String>,
+}
+
+/// GitHub GraphQL endpoint (authenticated requests only)
+const GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+/// Repository search with the fields `repo_from_api` needs
+const GRAPHQL_SEARCH: &str = "query($q: String!, $first: Int!) {
+  search(query: $q, type: REPOSITORY, first: $first) {
+    nodes {
+      ... on Repository {
+        nameWithOwner
+        description
+        url
+        primaryLanguage { name }
+        stargazerCount
+        forkCount
+        issues(states: OPEN) { totalCount }
+        repositoryTopics(first: 20) { nodes { topic { name } } }
+        pushedAt
+        createdAt
+        owner { avatarUrl }
+      }
+    }
+  }
+}";
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse {
+    data: Option<GraphQlData>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+    /// Machine-readable kind, e.g. `RATE_LIMITED`
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlData {
+    search: GraphQlSearch,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlSearch {
+    nodes: Vec<GraphQlRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlRepository {
+    name_with_owner: String,
+    description: Option<String>,
+    url: String,
+    primary_language: Option<GraphQlName>,
+    stargazer_count: u64,
+    fork_count: Option<u64>,
+    issues: Option<GraphQlCount>,
+    repository_topics: Option<GraphQlTopics>,
+    pushed_at: Option<String>,
+    created_at: Option<String>,
+    owner: Option<GraphQlOwner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlName {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlCount {
+    total_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlTopics {
+    nodes: Vec<GraphQlTopicNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlTopicNode {
+    topic: GraphQlName,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlOwner {
+    avatar_url: Option<String>,
+}
+
+impl From<GraphQlRepository> for GitHubRepository {
+    fn from(r: GraphQlRepository) -> Self {
+        Self {
+            full_name: r.name_with_owner,
+            description: r.description,
+            html_url: r.url,
+            stargazers_count: r.stargazer_count,
+            language: r.primary_language.map(|l| l.name),
+            topics: r
+                .repository_topics
+                .map(|t| t.nodes.into_iter().map(|n| n.topic.name).collect())
+                .unwrap_or_default(),
+            updated_at: r.pushed_at.unwrap_or_default(),
+            created_at: r.created_at,
+            owner: r.owner.map(|o| GitHubOwner {
+                avatar_url: o.avatar_url,
+            }),
+            forks_count: r.fork_count,
+            open_issues_count: r.issues.map(|i| i.total_count),
+        }
+    }
+}
+
+impl GraphQlResponse {
+    /// Search results, or the GraphQL errors reported alongside a 200 status
+    ///
+    /// A `RATE_LIMITED` error becomes a `RateLimitedError` so token rotation
+    /// treats it like a REST 403/429.
+    fn into_repositories(self, url: &str) -> Result<Vec<GitHubRepository>> {
+        match self.data {
+            Some(data) if self.errors.is_empty() => Ok(data
+                .search
+                .nodes
+                .into_iter()
+                .map(GitHubRepository::from)
+                .collect()),
+            _ if self
+                .errors
+                .iter()
+                .any(|e| e.kind.as_deref() == Some("RATE_LIMITED")) =>
+            {
+                Err(RateLimitedError {
+                    url: url.to_string(),
+                }
+                .into())
+            }
+            _ => {
+                let messages: Vec<String> = self.errors.into_iter().map(|e| e.message).collect();
+                anyhow::bail!("GitHub GraphQL query failed: {}", messages.join("; "))
+            }
+        }
+    }
 }
 
 impl GitHub {
     pub fn new(timeout_secs: u64) -> Result<Self> {
-        Ok(Self {
-            http: HttpClient::new(timeout_secs)?,
-        })
+        Ok(Self::with_client(HttpClient::new(timeout_secs)?))
     }
 
-    /// Create a GitHub provider with a custom HttpClient
+    /// Create a GitHub provider with a custom `HttpClient`
     #[allow(dead_code)]
     pub fn with_client(http: HttpClient) -> Self {
-        Self { http }
+        Self {
+            http,
+            html_permits: Semaphore::new(DEFAULT_HTML_CONCURRENCY),
+            api_permits: Semaphore::new(DEFAULT_API_CONCURRENCY),
+            token_index: AtomicUsize::new(0),
+            graphql_url: GRAPHQL_URL.to_string(),
+        }
     }
 
-    /// Fetch trending repositories from GitHub using Search API (provides topics)
-    async fn fetch_trending_api(&self, token: Option<&str>) -> Result<Vec<GitHubRepository>> {
-        // Search for repos created/updated in the last 7 days, sorted by stars
-        let week_ago = (chrono::Utc::now() - chrono::Duration::days(7))
+    /// Point GraphQL requests at another endpoint, e.g. a mock server
+    #[cfg(test)]
+    fn with_graphql_url(mut self, url: &str) -> Self {
+        self.graphql_url = url.to_string();
+        self
+    }
+
+    /// Set separate concurrency limits for HTML scraping and API requests
+    ///
+    /// GitHub's abuse detection reacts to bursts, and the two paths have very
+    /// different rate limits, so each gets its own budget (minimum 1).
+    pub fn with_concurrency(mut self, html: usize, api: usize) -> Self {
+        self.html_permits = Semaphore::new(html.max(1));
+        self.api_permits = Semaphore::new(api.max(1));
+        self
+    }
+
+    /// Fetch a trending page, waiting for an HTML permit
+    async fn get_html(&self, url: &str) -> Result<String> {
+        let _permit = self.html_permits.acquire().await?;
+        self.http.get_html(url).await
+    }
+
+    /// Fetch REST API JSON, waiting for an API permit
+    async fn get_api<T: DeserializeOwned>(&self, url: &str, cfg: &ProviderCfg) -> Result<T> {
+        let _permit = self.api_permits.acquire().await?;
+        self.with_token(cfg, |token| async move {
+            self.http.get_json(url, token.as_deref()).await
+        })
+        .await
+    }
+
+    /// Run an authenticated request, failing over through `cfg.tokens` on rate limits
+    ///
+    /// Starts from the token that last worked, so once a token is exhausted the
+    /// rest of the run doesn't keep hitting it. A single token (or none) is used as-is.
+    async fn with_token<T, F, Fut>(&self, cfg: &ProviderCfg, request: F) -> Result<T>
+    where
+        F: Fn(Option<String>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let pool = &cfg.tokens;
+        if pool.len() <= 1 {
+            return request(cfg.token.clone()).await;
+        }
+
+        let start = self.token_index.load(Ordering::Relaxed);
+        let mut last_err = None;
+        for offset in 0..pool.len() {
+            let index = (start + offset) % pool.len();
+            match request(Some(pool[index].clone())).await {
+                Err(e) if is_rate_limited(&e) => {
+                    if cfg.verbose {
+                        eprintln!("  🔑 GitHub token #{} rate-limited, rotating", index + 1);
+                    }
+                    last_err = Some(e);
+                }
+                result => {
+                    self.token_index.store(index, Ordering::Relaxed);
+                    return result;
+                }
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("All GitHub tokens are rate-limited")))
+    }
+
+    /// First creation date searched for `since`, as `YYYY-MM-DD`
+    ///
+    /// A single day is too sparse, so daily keeps the 7-day window.
+    fn window_start(since: Since) -> String {
+        let days = since.days().max(7);
+        (chrono::Utc::now() - chrono::Duration::days(days))
             .format("%Y-%m-%d")
-            .to_string();
+            .to_string()
+    }
 
+    /// Fetch trending repositories from GitHub using Search API (provides topics)
+    async fn fetch_trending_api(&self, cfg: &ProviderCfg) -> Result<Vec<GitHubRepository>> {
+        // Search for repos created in the trending window, sorted by stars
+        let window_start = Self::window_start(cfg.since);
         let url = format!(
-            "https://api.github.com/search/repositories?q=created:>={week_ago}&sort=stars&order=desc&per_page=100"
+            "https://api.github.com/search/repositories?q=created:>={window_start}&sort=stars&order=desc&per_page=100"
         );
 
-        let response: GitHubSearchResponse = self.http.get_json(&url, token).await?;
+        let response: GitHubSearchResponse = self.get_api(&url, cfg).await?;
         Ok(response.items)
     }
 
+    /// Search the GraphQL API for well-starred repositories created in the
+    /// trending window (needs a token, like every GraphQL request)
+    async fn fetch_trending_graphql(&self, cfg: &ProviderCfg) -> Result<Vec<GitHubRepository>> {
+        let window_start = Self::window_start(cfg.since);
+        let body = serde_json::json!({
+            "query": GRAPHQL_SEARCH,
+            "variables": {
+                "q": format!("stars:>100 created:>={window_start} sort:stars"),
+                "first": 100,
+            },
+        });
+
+        let _permit = self.api_permits.acquire().await?;
+        self.with_token(cfg, |token| {
+            let body = &body;
+            async move {
+                let response: GraphQlResponse = self
+                    .http
+                    .post_json(&self.graphql_url, body, token.as_deref())
+                    .await?;
+                response.into_repositories(&self.graphql_url)
+            }
+        })
+        .await
+    }
+
     /// Fetch trending repositories from GitHub by scraping the trending page
-    async fn fetch_trending(&self, language: Option<&str>) -> Result<Vec<TrendingRepo>> {
-        let url = if let Some(lang) = language {
-            format!("https://github.com/trending/{lang}?since=daily")
+    ///
+    /// With `retry_on_empty`, a page that parses cleanly but lists zero
+    /// repositories (seen transiently during GitHub deploys) is fetched once
+    /// more after a short delay before giving up.
+    async fn fetch_trending(
+        &self,
+        language: Option<&str>,
+        since: &str,
+        cfg: &ProviderCfg,
+    ) -> Result<Vec<TrendingRepo>> {
+        let url = Self::trending_url(language, since, cfg.spoken_language.as_deref());
+
+        let html = self.get_html(&url).await?;
+        let mut repos = Self::parse_trending_html(&html)?;
+
+        if repos.is_empty() && cfg.retry_on_empty {
+            if cfg.verbose {
+                eprintln!("  ↻ GitHub trending page was empty, retrying once: {url}");
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(EMPTY_RETRY_DELAY_MS)).await;
+            let html = self.get_html(&url).await?;
+            repos = Self::parse_trending_html(&html)?;
+        }
+
+        if repos.is_empty() {
+            anyhow::bail!("GitHub trending page returned no repositories: {url}");
+        }
+
+        Ok(repos)
+    }
+
+    /// Trending page URL for a language, window and optional spoken language code
+    fn trending_url(language: Option<&str>, since: &str, spoken_language: Option<&str>) -> String {
+        let mut url = if let Some(lang) = language {
+            format!("https://github.com/trending/{lang}?since={since}")
         } else {
-            "https://github.com/trending?since=daily".to_string()
+            format!("https://github.com/trending?since={since}")
         };
+        if let Some(code) = spoken_language {
+            url = format!("{url}&spoken_language_code={code}");
+        }
+        url
+    }
+
+    /// Fetch the trending page for `since`, or daily and weekly merged when
+    /// `merge_windows` is set (two concurrent requests per language)
+    async fn fetch_windows(
+        &self,
+        language: Option<&str>,
+        cfg: &ProviderCfg,
+    ) -> Result<Vec<TrendingRepo>> {
+        if !cfg.merge_windows {
+            let mut repos = self
+                .fetch_trending(language, cfg.since.as_str(), cfg)
+                .await?;
+            if cfg.since != Since::Daily {
+                // The page's period count is not a daily figure, so it is dropped
+                for repo in &mut repos {
+                    repo.stars_today = None;
+                    repo.windows = vec![cfg.since.as_str().to_string()];
+                }
+            }
+            return Ok(repos);
+        }
 
-        let html = self.http.get_html(&url).await?;
-        self.parse_trending_html(&html)
+        let (daily, weekly) = futures::join!(
+            self.fetch_trending(language, "daily", cfg),
+            self.fetch_trending(language, "weekly", cfg)
+        );
+
+        match (daily, weekly) {
+            (Ok(daily), Ok(weekly)) => Ok(Self::merge_windows(daily, weekly)),
+            (Ok(daily), Err(e)) => {
+                if cfg.verbose {
+                    eprintln!("  ⚠ GitHub weekly trending failed, using daily only: {e}");
+                }
+                Ok(Self::merge_windows(daily, vec![]))
+            }
+            (Err(e), Ok(weekly)) => {
+                if cfg.verbose {
+                    eprintln!("  ⚠ GitHub daily trending failed, using weekly only: {e}");
+                }
+                Ok(Self::merge_windows(vec![], weekly))
+            }
+            (Err(e), Err(_)) => Err(e),
+        }
+    }
+
+    /// Merge daily and weekly results, deduping by name
+    ///
+    /// Repos trending in both windows come first (in daily order), followed by
+    /// daily-only and then weekly-only repos. Daily star counts are kept; the
+    /// weekly page's period count is not a daily figure so it is dropped.
+    fn merge_windows(daily: Vec<TrendingRepo>, weekly: Vec<TrendingRepo>) -> Vec<TrendingRepo> {
+        let weekly_names: Vec<String> = weekly.iter().map(|r| r.name.clone()).collect();
+        let daily_names: Vec<String> = daily.iter().map(|r| r.name.clone()).collect();
+
+        let (mut both, mut daily_only): (Vec<_>, Vec<_>) = daily
+            .into_iter()
+            .map(|mut r| {
+                r.windows = vec!["daily".to_string()];
+                if weekly_names.contains(&r.name) {
+                    r.windows.push("weekly".to_string());
+                }
+                r
+            })
+            .partition(|r| r.windows.len() > 1);
+
+        let weekly_only = weekly
+            .into_iter()
+            .filter(|r| !daily_names.contains(&r.name))
+            .map(|mut r| {
+                r.windows = vec!["weekly".to_string()];
+                r.stars_today = None;
+                r
+            });
+
+        both.append(&mut daily_only);
+        both.extend(weekly_only);
+        both
     }
 
     /// Parse HTML from GitHub trending page
-    fn parse_trending_html(&self, html: &str) -> Result<Vec<TrendingRepo>> {
+    fn parse_trending_html(html: &str) -> Result<Vec<TrendingRepo>> {
         let document = Html::parse_document(html);
 
         // Selectors for extracting repository data
@@ -89,17 +480,18 @@ impl GitHub {
         let star_selector = Selector::parse("span.d-inline-block.float-sm-right").unwrap();
 
         let mut repos = Vec::new();
+        let mut article_count = 0;
 
         for article in document.select(&article_selector) {
+            article_count += 1;
+
             // Extract repository name and URL
-            let name_elem = match article.select(&name_selector).next() {
-                Some(elem) => elem,
-                None => continue,
+            let Some(name_elem) = article.select(&name_selector).next() else {
+                continue;
             };
 
-            let href = match name_elem.value().attr("href") {
-                Some(h) => h,
-                None => continue,
+            let Some(href) = name_elem.value().attr("href") else {
+                continue;
             };
 
             let name = name_elem.text().collect::<String>().trim()
@@ -126,16 +518,19 @@ impl GitHub {
                 .map(|e| e.text().collect::<String>().trim().to_string())
                 .collect();
 
-            let stars_total = stars_text.iter()
-                .find(|s| !s.contains("stars today"))
-                .and_then(|s| s.replace(',', "").split_whitespace().next().and_then(|n| n.parse().ok()));
+            let is_period = |s: &str| s.contains("stars today") || s.contains("stars this");
+
+            let stars_total = stars_text.iter().find(|s| !is_period(s)).and_then(|s| {
+                s.replace(',', "")
+                    .split_whitespace()
+                    .next()
+                    .and_then(|n| n.parse().ok())
+            });
 
-            let stars_today = stars_text.iter()
-                .find(|s| s.contains("stars today"))
-                .and_then(|s| {
-                    let parts: Vec<&str> = s.split_whitespace().collect();
-                    parts.first().and_then(|n| n.replace(',', "").parse().ok())
-                });
+            let stars_today = stars_text.iter().find(|s| is_period(s)).and_then(|s| {
+                let parts: Vec<&str> = s.split_whitespace().collect();
+                parts.first().and_then(|n| n.replace(',', "").parse().ok())
+            });
 
             repos.push(TrendingRepo {
                 name,
@@ -145,15 +540,60 @@ impl GitHub {
                 stars_total,
                 url,
                 topics: vec![], // HTML scraping doesn't provide topics
+                windows: vec![],
             });
         }
 
-        if repos.is_empty() {
+        // Articles present but none parseable means the page structure changed;
+        // zero articles is a valid (if empty) page and is left to the caller
+        if repos.is_empty() && article_count > 0 {
             anyhow::bail!("Failed to parse any repositories from GitHub trending page");
         }
 
         Ok(repos)
     }
+
+    /// Include filter: every `all` topic (AND) and at least one `any` topic (OR)
+    ///
+    /// Empty lists don't constrain; comparison is case-insensitive.
+    fn topics_match(topics: &[String], all: &[String], any: &[String]) -> bool {
+        let has = |wanted: &String| topics.iter().any(|t| t.eq_ignore_ascii_case(wanted));
+        all.iter().all(has) && (any.is_empty() || any.iter().any(has))
+    }
+
+    /// Convert a REST API repository into a `Repo`
+    fn repo_from_api(&self, r: GitHubRepository, cfg: &ProviderCfg) -> Repo {
+        let last_activity = chrono::DateTime::parse_from_rfc3339(&r.updated_at)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+        let created_at = r
+            .created_at
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+
+        Repo {
+            provider: self.id().to_string(),
+            icon: cfg.icons.resolve(self.id(), self.icon()).to_string(),
+            name: r.full_name,
+            language: r.language,
+            description: r.description,
+            url: r.html_url,
+            stars_today: None, // API doesn't provide daily stars
+            stars_total: Some(r.stargazers_count),
+            last_activity,
+            topics: r.topics,
+            from_cache: false,
+            trending_windows: vec![],
+            created_at,
+            avatar_url: r.owner.and_then(|o| o.avatar_url),
+            forks_count: r.forks_count,
+            open_issues: r.open_issues_count,
+            pinned: false,
+            source: Some("github-search".to_string()),
+            stars_delta: None,
+            downloads: None,
+        }
+    }
 }
 
 #[async_trait]
@@ -172,9 +612,19 @@ impl Provider for GitHub {
         limit: usize,
         langs: &LanguageFilter,
     ) -> Result<Vec<Repo>> {
-        // Use API if topic exclusion is configured (API provides topics)
-        if !cfg.exclude_topics.is_empty() {
-            let api_repos = self.fetch_trending_api(cfg.token.as_deref()).await?;
+        // GraphQL with a token; otherwise the REST API if a topic filter is
+        // configured (both provide topics)
+        let graphql = cfg.use_graphql && cfg.token.is_some();
+        if graphql
+            || !cfg.exclude_topics.is_empty()
+            || !cfg.topics_all.is_empty()
+            || !cfg.topics_any.is_empty()
+        {
+            let (api_repos, source) = if graphql {
+                (self.fetch_trending_graphql(cfg).await?, "github-graphql")
+            } else {
+                (self.fetch_trending_api(cfg).await?, "github-search")
+            };
 
             let repos = api_repos
                 .into_iter()
@@ -184,30 +634,19 @@ impl Provider for GitHub {
                         return false;
                     }
                     // Filter by excluded topics
-                    !r.topics.iter().any(|topic| {
-                        cfg.exclude_topics.iter().any(|excluded| {
-                            topic.eq_ignore_ascii_case(excluded)
-                        })
-                    })
+                    if r.topics.iter().any(|topic| {
+                        cfg.exclude_topics
+                            .iter()
+                            .any(|excluded| topic.eq_ignore_ascii_case(excluded))
+                    }) {
+                        return false;
+                    }
+                    Self::topics_match(&r.topics, &cfg.topics_all, &cfg.topics_any)
                 })
                 .take(limit)
-                .map(|r| {
-                    let last_activity = chrono::DateTime::parse_from_rfc3339(&r.updated_at)
-                        .ok()
-                        .map(|dt| dt.with_timezone(&chrono::Utc));
-
-                    Repo {
-                        provider: self.id().to_string(),
-                        icon: self.icon().to_string(),
-                        name: r.full_name,
-                        language: r.language,
-                        description: r.description,
-                        url: r.html_url,
-                        stars_today: None, // API doesn't provide daily stars
-                        stars_total: Some(r.stargazers_count),
-                        last_activity,
-                        topics: r.topics,
-                    }
+                .map(|r| Repo {
+                    source: Some(source.to_string()),
+                    ..self.repo_from_api(r, cfg)
                 })
                 .collect();
 
@@ -216,16 +655,18 @@ impl Provider for GitHub {
 
         // Fall back to HTML scraping (original behavior)
         let trending = if langs.languages.is_empty() {
-            self.fetch_trending(None).await?
+            self.fetch_windows(None, cfg).await?
         } else {
-            // Try fetching for each language filter and combine results
-            let mut all_repos = Vec::new();
-            for lang in &langs.languages {
-                if let Ok(repos) = self.fetch_trending(Some(lang)).await {
-                    all_repos.extend(repos);
-                }
-            }
-            all_repos
+            // Fetch each language concurrently (bounded by the HTML permits)
+            // and combine results in filter order
+            let pages = futures::future::join_all(
+                langs
+                    .languages
+                    .iter()
+                    .map(|lang| self.fetch_windows(Some(lang), cfg)),
+            )
+            .await;
+            pages.into_iter().filter_map(Result::ok).flatten().collect()
         };
 
         let repos = trending
@@ -234,7 +675,7 @@ impl Provider for GitHub {
             .take(limit)
             .map(|r| Repo {
                 provider: self.id().to_string(),
-                icon: self.icon().to_string(),
+                icon: cfg.icons.resolve(self.id(), self.icon()).to_string(),
                 name: r.name,
                 language: r.language,
                 description: r.description,
@@ -243,17 +684,57 @@ impl Provider for GitHub {
                 stars_total: r.stars_total,
                 last_activity: Some(chrono::Utc::now()), // GitHub trending = active today
                 topics: r.topics,
+                from_cache: false,
+                trending_windows: r.windows,
+                created_at: None,
+                avatar_url: None,
+                forks_count: None,
+                open_issues: None,
+                pinned: false,
+                source: Some("github-trending".to_string()),
+                stars_delta: None,
+                downloads: None,
             })
             .collect();
 
         Ok(repos)
     }
+
+    async fn has_readme(&self, cfg: &ProviderCfg, repo: &Repo) -> Result<Option<bool>> {
+        let url = format!("https://api.github.com/repos/{}/readme", repo.name);
+        let _permit = self.api_permits.acquire().await?;
+        let exists = self
+            .with_token(cfg, |token| {
+                let url = &url;
+                async move { self.http.url_exists(url, token.as_deref()).await }
+            })
+            .await?;
+        Ok(Some(exists))
+    }
+
+    async fn fetch_one(&self, cfg: &ProviderCfg, name: &str) -> Result<Repo> {
+        let url = format!("https://api.github.com/repos/{name}");
+        let repo: GitHubRepository = self.get_api(&url, cfg).await?;
+        Ok(self.repo_from_api(repo, cfg))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_trending_url() {
+        assert_eq!(
+            GitHub::trending_url(None, "daily", None),
+            "https://github.com/trending?since=daily"
+        );
+        assert_eq!(
+            GitHub::trending_url(Some("rust"), "weekly", Some("en")),
+            "https://github.com/trending/rust?since=weekly&spoken_language_code=en"
+        );
+    }
+
     #[test]
     fn test_github_provider_metadata() {
         let github = GitHub::new(6).unwrap();
@@ -261,6 +742,205 @@ mod tests {
         assert_eq!(github.icon(), "[GH]");
     }
 
+    fn trending(name: &str, stars_today: Option<u64>) -> TrendingRepo {
+        TrendingRepo {
+            name: name.to_string(),
+            description: None,
+            language: None,
+            stars_today,
+            stars_total: None,
+            url: format!("https://github.com/{name}"),
+            topics: vec![],
+            windows: vec![],
+        }
+    }
+
+    #[test]
+    fn test_topics_match() {
+        let topics = vec!["rust".to_string(), "WASM".to_string()];
+        let list = |items: &[&str]| items.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert!(GitHub::topics_match(&topics, &[], &[]));
+        assert!(GitHub::topics_match(&topics, &list(&["rust", "wasm"]), &[]));
+        assert!(!GitHub::topics_match(&topics, &list(&["rust", "cli"]), &[]));
+        assert!(GitHub::topics_match(&topics, &[], &list(&["cli", "rust"])));
+        assert!(!GitHub::topics_match(&topics, &[], &list(&["cli", "go"])));
+        assert!(!GitHub::topics_match(
+            &topics,
+            &list(&["rust"]),
+            &list(&["go"])
+        ));
+    }
+
+    #[test]
+    fn test_merge_windows() {
+        let daily = vec![trending("a/daily", Some(5)), trending("b/both", Some(9))];
+        let weekly = vec![trending("b/both", Some(70)), trending("c/weekly", Some(40))];
+
+        let merged = GitHub::merge_windows(daily, weekly);
+        let names: Vec<&str> = merged.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["b/both", "a/daily", "c/weekly"]);
+        assert_eq!(merged[0].windows, vec!["daily", "weekly"]);
+        assert_eq!(merged[0].stars_today, Some(9));
+        assert_eq!(merged[1].windows, vec!["daily"]);
+        assert_eq!(merged[2].windows, vec!["weekly"]);
+        assert_eq!(merged[2].stars_today, None);
+    }
+
+    #[test]
+    fn test_parse_trending_html_empty_page() {
+        let html = r#"<html><body><div class="Box"></div></body></html>"#;
+        let repos = GitHub::parse_trending_html(html).unwrap();
+        assert!(repos.is_empty());
+    }
+
+    #[test]
+    fn test_parse_trending_html_broken_structure() {
+        // Articles exist but lack the expected name link
+        let html = r#"<html><body><article class="Box-row"><p>x</p></article></body></html>"#;
+        assert!(GitHub::parse_trending_html(html).is_err());
+    }
+
+    #[test]
+    fn test_parse_trending_html_article() {
+        let html = r#"<html><body>
+            <article class="Box-row">
+                <h2><a href="/rust-lang/rust">rust-lang / rust</a></h2>
+                <p>Empowering everyone</p>
+                <span itemprop="programmingLanguage">Rust</span>
+                <span class="d-inline-block float-sm-right">120 stars today</span>
+            </article>
+        </body></html>"#;
+        let repos = GitHub::parse_trending_html(html).unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].name, "rust-lang/rust");
+        assert_eq!(repos[0].language.as_deref(), Some("Rust"));
+        assert_eq!(repos[0].stars_today, Some(120));
+    }
+
+    #[test]
+    fn test_graphql_response() {
+        let response: GraphQlResponse = serde_json::from_str(
+            r#"{"data": {"search": {"nodes": [{
+                "nameWithOwner": "octo/fast",
+                "description": "Fast tool",
+                "url": "https://github.com/octo/fast",
+                "primaryLanguage": {"name": "Rust"},
+                "stargazerCount": 1234,
+                "forkCount": 56,
+                "issues": {"totalCount": 7},
+                "repositoryTopics": {"nodes": [{"topic": {"name": "cli"}}]},
+                "pushedAt": "2024-11-01T10:00:00Z",
+                "createdAt": "2024-10-28T09:00:00Z",
+                "owner": {"avatarUrl": "https://avatars.githubusercontent.com/u/1"}
+            }, {
+                "nameWithOwner": "octo/bare",
+                "description": null,
+                "url": "https://github.com/octo/bare",
+                "primaryLanguage": null,
+                "stargazerCount": 101,
+                "repositoryTopics": {"nodes": []},
+                "pushedAt": null
+            }]}}}"#,
+        )
+        .unwrap();
+        let github = GitHub::new(6).unwrap();
+        let cfg = ProviderCfg {
+            use_graphql: true,
+            ..Default::default()
+        };
+
+        let mut repos = response
+            .into_repositories(GRAPHQL_URL)
+            .unwrap()
+            .into_iter()
+            .map(|r| github.repo_from_api(r, &cfg));
+        let fast = repos.next().unwrap();
+        assert_eq!(fast.name, "octo/fast");
+        assert_eq!(fast.language.as_deref(), Some("Rust"));
+        assert_eq!(fast.stars_total, Some(1234));
+        assert_eq!(fast.topics, vec!["cli".to_string()]);
+        assert_eq!(fast.forks_count, Some(56));
+        assert_eq!(fast.open_issues, Some(7));
+        assert!(fast.last_activity.is_some() && fast.created_at.is_some());
+
+        let bare = repos.next().unwrap();
+        assert_eq!(bare.language, None);
+        assert_eq!(bare.last_activity, None);
+    }
+
+    #[test]
+    fn test_graphql_errors() {
+        let response: GraphQlResponse = serde_json::from_str(
+            r#"{"data": null, "errors": [{"type": "NOT_FOUND", "message": "Could not resolve"}]}"#,
+        )
+        .unwrap();
+        let err = response.into_repositories(GRAPHQL_URL).unwrap_err();
+        assert!(!is_rate_limited(&err));
+        assert_eq!(
+            err.to_string(),
+            "GitHub GraphQL query failed: Could not resolve"
+        );
+
+        let response: GraphQlResponse = serde_json::from_str(
+            r#"{"data": null, "errors": [{"type": "RATE_LIMITED", "message": "API rate limit exceeded"}]}"#,
+        )
+        .unwrap();
+        assert!(is_rate_limited(
+            &response.into_repositories(GRAPHQL_URL).unwrap_err()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_top_today_graphql() {
+        let mut server = mockito::Server::new_async().await;
+        // The first token is exhausted, reported GraphQL-style with a 200
+        let limited = server
+            .mock("POST", "/graphql")
+            .match_header("authorization", "Bearer a")
+            .with_body(r#"{"data": null, "errors": [{"type": "RATE_LIMITED", "message": "API rate limit exceeded"}]}"#)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("POST", "/graphql")
+            .match_header("authorization", "Bearer b")
+            .with_body(
+                r#"{"data": {"search": {"nodes": [{
+                    "nameWithOwner": "octo/fast",
+                    "url": "https://github.com/octo/fast",
+                    "primaryLanguage": {"name": "Rust"},
+                    "stargazerCount": 1234
+                }, {
+                    "nameWithOwner": "octo/slow",
+                    "url": "https://github.com/octo/slow",
+                    "primaryLanguage": {"name": "Go"},
+                    "stargazerCount": 500
+                }]}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let http = HttpClient::builder().max_retries(0).build().unwrap();
+        let github =
+            GitHub::with_client(http).with_graphql_url(&format!("{}/graphql", server.url()));
+        let cfg = ProviderCfg {
+            token: Some("a".to_string()),
+            tokens: vec!["a".to_string(), "b".to_string()],
+            use_graphql: true,
+            ..Default::default()
+        };
+        let filter = LanguageFilter::new(vec!["rust".to_string()]);
+
+        let repos = github.top_today(&cfg, 10, &filter).await.unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].name, "octo/fast");
+        assert_eq!(repos[0].stars_total, Some(1234));
+        assert_eq!(repos[0].source.as_deref(), Some("github-graphql"));
+        assert_eq!(github.token_index.load(Ordering::Relaxed), 1);
+        limited.assert_async().await;
+        ok.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_github_trending_api() {
         // This is an integration test that requires network access
@@ -271,12 +951,7 @@ mod tests {
             .build()
             .unwrap();
         let github = GitHub::with_client(http);
-        let cfg = ProviderCfg {
-            timeout_secs: 10,
-            token: None,
-            base_url: None,
-            exclude_topics: vec![],
-        };
+        let cfg = ProviderCfg::default();
         let filter = LanguageFilter::new(vec![]);
 
         // Try to fetch, but don't fail the test if API is down
@@ -286,6 +961,7 @@ mod tests {
                 for repo in repos {
                     assert_eq!(repo.provider, "github");
                     assert_eq!(repo.icon, "[GH]");
+                    assert_eq!(repo.source.as_deref(), Some("github-trending"));
                     assert!(!repo.name.is_empty());
                 }
             }
@@ -295,4 +971,48 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_token_rotation_on_rate_limit() {
+        use crate::http::StatusError;
+
+        let github = GitHub::new(6).unwrap();
+        let cfg = ProviderCfg {
+            token: Some("a".to_string()),
+            tokens: vec!["a".to_string(), "b".to_string()],
+            ..Default::default()
+        };
+        let request = |exhausted: &'static str| {
+            move |token: Option<String>| async move {
+                let token = token.unwrap();
+                if token == exhausted {
+                    Err(StatusError {
+                        status: reqwest::StatusCode::FORBIDDEN,
+                        url: "https://api.github.com/x".to_string(),
+                        rate_limit_reset: None,
+                        authenticated: true,
+                    }
+                    .into())
+                } else {
+                    Ok(token)
+                }
+            }
+        };
+
+        // "a" is rate-limited, so "b" is used and remembered
+        assert_eq!(github.with_token(&cfg, request("a")).await.unwrap(), "b");
+        assert_eq!(github.token_index.load(Ordering::Relaxed), 1);
+
+        // Later calls start from "b"; if it runs out, fail over back to "a"
+        assert_eq!(github.with_token(&cfg, request("b")).await.unwrap(), "a");
+
+        // Non-rate-limit errors are returned without trying other tokens
+        let err = github
+            .with_token(&cfg, |_| async {
+                Err::<String, _>(anyhow::anyhow!("boom"))
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "boom");
+    }
 }
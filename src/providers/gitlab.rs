@@ -1,10 +1,24 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::StreamExt;
 use serde::Deserialize;
+use std::collections::HashMap;
 
-use crate::http::HttpClient;
+use crate::http::{HttpClient, JsonPage};
 use crate::model::{LanguageFilter, Provider, ProviderCfg, Repo};
 
+/// Instance used when `ProviderCfg::base_url` is unset
+const DEFAULT_BASE_URL: &str = "https://gitlab.com";
+
+/// Upper bound on pages fetched per run (100 projects each)
+const MAX_PAGES: usize = 5;
+
+/// Largest page GitLab serves, which caps the usable `min_recent_commits`
+const MAX_PER_PAGE: u64 = 100;
+
+/// Per-project lookups (languages, recent commits) in flight at once
+const LOOKUP_CONCURRENCY: usize = 4;
+
 /// GitLab provider using explore API
 pub struct GitLab {
     http: HttpClient,
@@ -12,6 +26,7 @@ pub struct GitLab {
 
 #[derive(Debug, Deserialize)]
 struct GitLabProject {
+    id: u64,
     #[allow(dead_code)]
     name: String,
     path_with_namespace: String,
@@ -21,6 +36,15 @@ struct GitLabProject {
     #[serde(default)]
     topics: Vec<String>,
     last_activity_at: Option<String>,
+    created_at: Option<String>,
+    namespace: Option<GitLabNamespace>,
+    forks_count: Option<u64>,
+    open_issues_count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabNamespace {
+    avatar_url: Option<String>,
 }
 
 impl GitLab {
@@ -30,31 +54,222 @@ impl GitLab {
         })
     }
 
-    /// Create a GitLab provider with a custom HttpClient
+    /// Create a GitLab provider with a custom `HttpClient`
     #[allow(dead_code)]
     pub fn with_client(http: HttpClient) -> Self {
         Self { http }
     }
 
     /// Fetch recently active projects from GitLab
-    async fn fetch_projects(&self, token: Option<&str>) -> Result<Vec<GitLabProject>> {
+    ///
+    /// Follows pagination until `limit` projects pass the star, language and
+    /// (with `min_recent_commits`) activity filters, or `MAX_PAGES` pages have
+    /// been fetched.
+    async fn fetch_projects(
+        &self,
+        cfg: &ProviderCfg,
+        limit: usize,
+        langs: &LanguageFilter,
+    ) -> Result<Vec<(GitLabProject, Option<String>)>> {
         // Get date from 7 days ago in ISO format
         let week_ago = (chrono::Utc::now() - chrono::Duration::days(7))
             .format("%Y-%m-%dT00:00:00Z")
             .to_string();
 
         // Search for projects with recent activity, sorted by activity date (descending)
-        let url = format!(
-            "https://gitlab.com/api/v4/projects?order_by=last_activity_at&sort=desc&last_activity_after={week_ago}&per_page=100"
-        );
+        let base_url = cfg.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL);
+        let mut url = Some(format!(
+            "{base_url}/api/v4/projects?order_by=last_activity_at&sort=desc&last_activity_after={week_ago}&per_page=100"
+        ));
+
+        let mut matches = Vec::new();
+        let mut pages = 0;
+
+        while let Some(page_url) = url {
+            if pages >= MAX_PAGES || matches.len() >= limit {
+                break;
+            }
+
+            let page: JsonPage<Vec<GitLabProject>> = self
+                .http
+                .get_json_page(&page_url, cfg.token.as_deref())
+                .await?;
+            pages += 1;
+
+            // Filter to only repos with at least 10 stars (actually popular)
+            let popular: Vec<GitLabProject> = page
+                .items
+                .into_iter()
+                .filter(|p| p.star_count.unwrap_or(0) >= 10)
+                .filter(|p| !Self::has_excluded_topic(&p.topics, &cfg.exclude_topics))
+                .collect();
+
+            // A language filter needs every candidate's language up front;
+            // otherwise API lookups wait until `limit` has been applied
+            let languages = if cfg.use_languages_api && langs.is_active() {
+                self.fetch_languages(&popular, cfg).await
+            } else {
+                popular
+                    .iter()
+                    .map(|p| Self::extract_language(&p.topics))
+                    .collect()
+            };
+
+            let mut candidates: Vec<(GitLabProject, Option<String>)> = popular
+                .into_iter()
+                .zip(languages)
+                .filter(|(_, lang)| langs.matches(lang.as_ref()))
+                .collect();
+
+            match cfg.min_recent_commits {
+                // Commit lookups only for as many candidates as are still
+                // needed, topping up from the rest when some fall short
+                Some(min) => {
+                    while !candidates.is_empty() && matches.len() < limit {
+                        let needed = (limit - matches.len()).min(candidates.len());
+                        let rest = candidates.split_off(needed);
+                        let batch = std::mem::replace(&mut candidates, rest);
+                        matches.extend(self.retain_active(batch, cfg, &week_ago, min).await);
+                    }
+                }
+                None => matches.extend(candidates),
+            }
+            url = page.next_url;
+        }
 
-        let projects: Vec<GitLabProject> = self.http.get_json(&url, token).await?;
+        if cfg.verbose {
+            eprintln!(
+                "  📄 gitlab: {} matches from {pages} page(s)",
+                matches.len()
+            );
+        }
+
+        matches.truncate(limit);
+        if cfg.use_languages_api && !langs.is_active() {
+            let projects: Vec<GitLabProject> = matches.into_iter().map(|(p, _)| p).collect();
+            let languages = self.fetch_languages(&projects, cfg).await;
+            matches = projects.into_iter().zip(languages).collect();
+        }
+        Ok(matches)
+    }
+
+    /// Drop projects with fewer than `min` commits since `since`
+    ///
+    /// Costs one commits request per project, `LOOKUP_CONCURRENCY` at a time. Each request
+    /// asks for a page of `min` commits, so a full page means the project
+    /// qualifies without counting further. Projects whose lookup fails are kept.
+    async fn retain_active(
+        &self,
+        projects: Vec<(GitLabProject, Option<String>)>,
+        cfg: &ProviderCfg,
+        since: &str,
+        min: u64,
+    ) -> Vec<(GitLabProject, Option<String>)> {
+        let min = min.min(MAX_PER_PAGE);
+        if min == 0 {
+            return projects;
+        }
+
+        let base_url = cfg.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL);
+        // Collected first: a lazy map here trips the `Send` check of `top_today`
+        let lookups: Vec<_> = projects
+            .iter()
+            .map(|(p, _)| async move {
+                let url = format!(
+                    "{base_url}/api/v4/projects/{}/repository/commits?since={since}&per_page={min}",
+                    p.id
+                );
+                self.http
+                    .get_json::<Vec<serde::de::IgnoredAny>>(&url, cfg.token.as_deref())
+                    .await
+                    .map(|commits| commits.len() as u64)
+            })
+            .collect();
+        let counts: Vec<_> = futures::stream::iter(lookups)
+            .buffered(LOOKUP_CONCURRENCY)
+            .collect()
+            .await;
 
-        // Filter to only repos with at least 10 stars (actually popular)
-        Ok(projects
+        projects
             .into_iter()
-            .filter(|p| p.star_count.unwrap_or(0) >= 10)
-            .collect())
+            .zip(counts)
+            .filter(|((p, _), count)| match count {
+                Ok(count) => Self::is_active(*count, min),
+                Err(e) => {
+                    if cfg.verbose {
+                        eprintln!(
+                            "  ⚠ Commit lookup failed for {}: {e}",
+                            p.path_with_namespace
+                        );
+                    }
+                    true
+                }
+            })
+            .map(|(project, _)| project)
+            .collect()
+    }
+
+    /// Whether any topic is in the exclude list (case-insensitive)
+    fn has_excluded_topic(topics: &[String], excluded: &[String]) -> bool {
+        topics
+            .iter()
+            .any(|topic| excluded.iter().any(|e| topic.eq_ignore_ascii_case(e)))
+    }
+
+    /// Whether a recent-commit count meets the configured minimum
+    fn is_active(count: u64, min: u64) -> bool {
+        count >= min
+    }
+
+    /// Resolve each project's language from its topics, or else its primary
+    /// language from the languages API
+    ///
+    /// Costs one request per project whose topics name no language,
+    /// `LOOKUP_CONCURRENCY` at a time. Failed lookups leave the language unknown.
+    async fn fetch_languages(
+        &self,
+        projects: &[GitLabProject],
+        cfg: &ProviderCfg,
+    ) -> Vec<Option<String>> {
+        let base_url = cfg.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL);
+        let lookups: Vec<_> = projects
+            .iter()
+            .map(|p| async move {
+                if let Some(language) = Self::extract_language(&p.topics) {
+                    return Some(language);
+                }
+                let url = format!("{base_url}/api/v4/projects/{}/languages", p.id);
+                match self
+                    .http
+                    .get_json::<HashMap<String, f64>>(&url, cfg.token.as_deref())
+                    .await
+                {
+                    Ok(breakdown) => Self::primary_language(&breakdown),
+                    Err(e) => {
+                        if cfg.verbose {
+                            eprintln!(
+                                "  ⚠ Language lookup failed for {}: {e}",
+                                p.path_with_namespace
+                            );
+                        }
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        futures::stream::iter(lookups)
+            .buffered(LOOKUP_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Pick the language with the largest share from a languages breakdown
+    fn primary_language(breakdown: &HashMap<String, f64>) -> Option<String> {
+        breakdown
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(lang, _)| lang.clone())
     }
 
     /// Extract language from topics (GitLab uses topics, not a dedicated language field)
@@ -108,6 +323,41 @@ impl GitLab {
                 }
             })
     }
+
+    /// Convert an API project into a `Repo`
+    fn repo_from_api(&self, p: GitLabProject, language: Option<String>, cfg: &ProviderCfg) -> Repo {
+        let created_at = p
+            .created_at
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+        let last_activity = p
+            .last_activity_at
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+
+        Repo {
+            provider: self.id().to_string(),
+            icon: cfg.icons.resolve(self.id(), self.icon()).to_string(),
+            name: p.path_with_namespace,
+            language,
+            description: p.description,
+            url: p.web_url,
+            stars_today: None, // GitLab API doesn't provide daily stars
+            stars_total: p.star_count,
+            last_activity,
+            topics: p.topics,
+            from_cache: false,
+            trending_windows: vec![],
+            created_at,
+            avatar_url: p.namespace.and_then(|n| n.avatar_url),
+            forks_count: p.forks_count,
+            open_issues: p.open_issues_count,
+            pinned: false,
+            source: Some("gitlab-api".to_string()),
+            stars_delta: None,
+            downloads: None,
+        }
+    }
 }
 
 #[async_trait]
@@ -126,39 +376,33 @@ impl Provider for GitLab {
         limit: usize,
         langs: &LanguageFilter,
     ) -> Result<Vec<Repo>> {
-        let projects = self.fetch_projects(cfg.token.as_deref()).await?;
+        let projects = self.fetch_projects(cfg, limit, langs).await?;
 
         let repos = projects
             .into_iter()
-            .map(|p| {
-                let language = Self::extract_language(&p.topics);
-                (p, language)
-            })
-            .filter(|(_, lang)| langs.matches(lang.as_ref()))
-            .take(limit)
-            .map(|(p, language)| {
-                let last_activity = p
-                    .last_activity_at
-                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
-                    .map(|dt| dt.with_timezone(&chrono::Utc));
-
-                Repo {
-                    provider: self.id().to_string(),
-                    icon: self.icon().to_string(),
-                    name: p.path_with_namespace,
-                    language,
-                    description: p.description,
-                    url: p.web_url,
-                    stars_today: None, // GitLab API doesn't provide daily stars
-                    stars_total: p.star_count,
-                    last_activity,
-                    topics: p.topics,
-                }
-            })
+            .map(|(p, language)| self.repo_from_api(p, language, cfg))
             .collect();
 
         Ok(repos)
     }
+
+    async fn fetch_one(&self, cfg: &ProviderCfg, name: &str) -> Result<Repo> {
+        // Project paths must be URL-encoded when used as an id
+        let base_url = cfg.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL);
+        let url = format!("{base_url}/api/v4/projects/{}", name.replace('/', "%2F"));
+        let project: GitLabProject = self.http.get_json(&url, cfg.token.as_deref()).await?;
+
+        let language = if cfg.use_languages_api {
+            self.fetch_languages(std::slice::from_ref(&project), cfg)
+                .await
+                .pop()
+                .flatten()
+        } else {
+            Self::extract_language(&project.topics)
+        };
+
+        Ok(self.repo_from_api(project, language, cfg))
+    }
 }
 
 // Add chrono dependency for date handling
@@ -188,6 +432,106 @@ mod tests {
         assert_eq!(GitLab::extract_language(&["web".to_string()]), None);
     }
 
+    #[test]
+    fn test_primary_language() {
+        let breakdown = HashMap::from([
+            ("Shell".to_string(), 4.5),
+            ("Rust".to_string(), 88.2),
+            ("Python".to_string(), 7.3),
+        ]);
+        assert_eq!(
+            GitLab::primary_language(&breakdown),
+            Some("Rust".to_string())
+        );
+        assert_eq!(GitLab::primary_language(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_is_active() {
+        assert!(GitLab::is_active(5, 5));
+        assert!(GitLab::is_active(9, 5));
+        assert!(!GitLab::is_active(1, 5));
+    }
+
+    #[test]
+    fn test_has_excluded_topic() {
+        let topics = vec!["Mirror".to_string(), "rust".to_string()];
+        assert!(GitLab::has_excluded_topic(&topics, &["mirror".to_string()]));
+        assert!(!GitLab::has_excluded_topic(
+            &topics,
+            &["archived".to_string()]
+        ));
+        assert!(!GitLab::has_excluded_topic(&topics, &[]));
+    }
+
+    #[tokio::test]
+    async fn test_commit_lookups_after_language_filter() {
+        let mut server = mockito::Server::new_async().await;
+        let project = |id: u64, topic: &str| {
+            format!(
+                r#"{{"id": {id}, "name": "p{id}", "path_with_namespace": "group/p{id}",
+                    "star_count": 50, "web_url": "https://gitlab.com/group/p{id}",
+                    "topics": ["{topic}"]}}"#
+            )
+        };
+        let _projects = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/api/v4/projects\?".to_string()),
+            )
+            .with_body(format!(
+                "[{}, {}, {}, {}]",
+                project(1, "go"),
+                project(2, "rust"),
+                project(3, "rust"),
+                project(4, "rust")
+            ))
+            .create_async()
+            .await;
+        let commits = |id: u64| format!("^/api/v4/projects/{id}/repository/commits");
+        // Filtered out by language, so never looked up
+        let go = server
+            .mock("GET", mockito::Matcher::Regex(commits(1)))
+            .expect(0)
+            .create_async()
+            .await;
+        let quiet = server
+            .mock("GET", mockito::Matcher::Regex(commits(2)))
+            .with_body("[]")
+            .expect(1)
+            .create_async()
+            .await;
+        let active = server
+            .mock("GET", mockito::Matcher::Regex(commits(3)))
+            .with_body("[{}]")
+            .expect(1)
+            .create_async()
+            .await;
+        // The limit is met before this one is needed
+        let spare = server
+            .mock("GET", mockito::Matcher::Regex(commits(4)))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let http = HttpClient::builder().max_retries(0).build().unwrap();
+        let gitlab = GitLab::with_client(http);
+        let cfg = ProviderCfg {
+            base_url: Some(server.url()),
+            min_recent_commits: Some(1),
+            ..Default::default()
+        };
+        let filter = LanguageFilter::new(vec!["rust".to_string()]);
+
+        let repos = gitlab.top_today(&cfg, 1, &filter).await.unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].name, "group/p3");
+        go.assert_async().await;
+        quiet.assert_async().await;
+        active.assert_async().await;
+        spare.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_gitlab_api() {
         // Use max_retries(0) to avoid retry delays in tests
@@ -197,12 +541,7 @@ mod tests {
             .build()
             .unwrap();
         let gitlab = GitLab::with_client(http);
-        let cfg = ProviderCfg {
-            timeout_secs: 10,
-            token: None,
-            base_url: None,
-            exclude_topics: vec![],
-        };
+        let cfg = ProviderCfg::default();
         let filter = LanguageFilter::new(vec![]);
 
         // Try to fetch, but don't fail the test if API is down
@@ -212,6 +551,7 @@ mod tests {
                 for repo in repos {
                     assert_eq!(repo.provider, "gitlab");
                     assert_eq!(repo.icon, "[GL]");
+                    assert_eq!(repo.source.as_deref(), Some("gitlab-api"));
                     assert!(repo.last_activity.is_some());
                 }
             }
@@ -1,7 +1,13 @@
+pub mod bitbucket;
+pub mod codeberg;
+pub mod crates_io;
 pub mod gitea;
 pub mod github;
 pub mod gitlab;
 
+pub use bitbucket::Bitbucket;
+pub use codeberg::Codeberg;
+pub use crates_io::CratesIo;
 pub use gitea::Gitea;
 pub use github::GitHub;
 pub use gitlab::GitLab;
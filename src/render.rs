@@ -1,54 +1,471 @@
-use chrono::{Duration, Utc};
-use colored::Colorize;
+use chrono::{DateTime, Duration, Utc};
+use colored::{ColoredString, Colorize};
 use serde_json::json;
-use unicode_width::{UnicodeWidthStr, UnicodeWidthChar};
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::Path;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::model::Repo;
+use crate::model::{is_web_url, Repo};
 
 /// Output format
 #[derive(Debug, Clone, Copy)]
 pub enum OutputFormat {
     Motd,
     Json,
+    /// Bare URLs, one per line
+    Urls,
+    /// The top repository as a single banner sentence
+    One,
+    /// Comma-separated values with a header row
+    Csv,
+    /// GitHub-flavored Markdown table
+    Markdown,
+    /// YAML list with the same fields as JSON
+    Yaml,
+    /// One compact JSON object per line, sorted and filtered like `Json`
+    JsonLines,
+    /// HTML `<table>` fragment for embedding in web pages
+    Html,
+}
+
+/// Machine format written to a file alongside the primary output
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// Value of the `--color` flag
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Decide from the environment and whether stdout is a terminal
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Decide whether to emit ANSI colors
+///
+/// Precedence, first match wins:
+/// 1. `--color always|never`
+/// 2. `NO_COLOR` set to anything non-empty disables color
+/// 3. `CLICOLOR_FORCE` set to anything other than `0` forces color
+/// 4. `CLICOLOR=0` disables color
+/// 5. Otherwise color only when stdout is a terminal
+pub fn should_color(choice: ColorChoice, env: impl Fn(&str) -> Option<String>, tty: bool) -> bool {
+    match choice {
+        ColorChoice::Always => return true,
+        ColorChoice::Never => return false,
+        ColorChoice::Auto => {}
+    }
+    if env("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return false;
+    }
+    if env("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty() && v != "0") {
+        return true;
+    }
+    if env("CLICOLOR").as_deref() == Some("0") {
+        return false;
+    }
+    tty
+}
+
+/// Apply [`should_color`] for the current process environment
+pub fn init_color(choice: ColorChoice) {
+    let tty = std::io::stdout().is_terminal();
+    colored::control::set_override(should_color(choice, |name| std::env::var(name).ok(), tty));
+}
+
+/// Options controlling how repositories are rendered
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct RenderOptions {
+    /// Remove emoji/pictographic characters from names and descriptions
+    pub strip_emoji: bool,
+    /// Prefix rows served from cache with a dim marker
+    pub mark_cached: bool,
+    /// Show at most this many rows, then a "── N more ──" marker
+    pub page_size: Option<usize>,
+    /// Thousands separator for star counts (`None` prints raw digits)
+    pub star_separator: Option<char>,
+    /// Abbreviate MOTD star counts (`90.0k`) instead of grouping digits
+    pub humanize_stars: bool,
+    /// Print a footer mapping each shown icon to its provider name
+    pub legend: bool,
+    /// Print a footer counting repos per provider and summing total stars
+    pub summary: bool,
+    /// Add a stars-per-day velocity column
+    pub show_velocity: bool,
+    /// Add a compact stars/forks/issues badge cluster (API providers)
+    pub badges: bool,
+    /// Dim whole rows (name and description) as repos age
+    pub dim_old: bool,
+    /// Age boundaries used for recency coloring and `dim_old`
+    pub aging: AgingGradient,
+    /// Print the full description word-wrapped to this width below each row
+    pub wrap_desc: Option<usize>,
+    /// Truncate descriptions to this many columns (default 45, 0 hides them)
+    pub desc_width: Option<usize>,
+    /// Terminal width to fit MOTD rows into (`None` keeps the fixed column caps)
+    pub term_width: Option<usize>,
+    /// Limit JSON objects to these `Repo` fields (see [`parse_json_field`])
+    pub json_fields: Option<Vec<String>>,
+    /// Make MOTD repo names clickable (OSC 8) when coloring a terminal
+    pub hyperlinks: bool,
+    /// Icon colors keyed by provider id or kind, replacing the brand colors
+    pub icon_colors: BTreeMap<String, colored::Color>,
+}
+
+/// How fresh a repository's last activity is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgeTier {
+    Fresh,
+    Recent,
+    Aging,
+    Old,
+    /// No `last_activity` (e.g. scraped GitHub rows), never dimmed
+    Unknown,
+}
+
+/// Day boundaries between age tiers: below `days[0]` is fresh, below
+/// `days[1]` recent, below `days[2]` aging, anything older is old
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgingGradient {
+    pub days: [u32; 3],
+}
+
+impl Default for AgingGradient {
+    fn default() -> Self {
+        Self { days: [1, 2, 7] }
+    }
+}
+
+impl AgingGradient {
+    /// Classify a last-activity timestamp relative to `now`
+    pub fn tier(&self, last_activity: Option<DateTime<Utc>>, now: DateTime<Utc>) -> AgeTier {
+        let Some(dt) = last_activity else {
+            return AgeTier::Unknown;
+        };
+        let age = now.signed_duration_since(dt);
+        let [fresh, recent, aging] = self.days.map(|d| Duration::days(i64::from(d)));
+
+        if age < fresh {
+            AgeTier::Fresh
+        } else if age < recent {
+            AgeTier::Recent
+        } else if age < aging {
+            AgeTier::Aging
+        } else {
+            AgeTier::Old
+        }
+    }
+}
+
+/// Color the recency column by age tier
+fn color_recency(text: &str, tier: AgeTier) -> ColoredString {
+    match tier {
+        AgeTier::Fresh => text.bright_green(),
+        AgeTier::Recent => text.yellow(),
+        AgeTier::Aging => text.bright_black(),
+        AgeTier::Old | AgeTier::Unknown => text.bright_black().dimmed(),
+    }
+}
+
+/// Fade row text as it ages (fresh and recent rows are left as-is)
+fn fade(text: ColoredString, tier: AgeTier) -> ColoredString {
+    match tier {
+        AgeTier::Aging => text.dimmed(),
+        AgeTier::Old => text.bright_black().dimmed(),
+        AgeTier::Fresh | AgeTier::Recent | AgeTier::Unknown => text,
+    }
 }
 
 /// Render repositories in MOTD format
-pub fn render(repos: &[Repo], format: OutputFormat) {
+pub fn render(repos: &[Repo], format: OutputFormat, options: &RenderOptions) {
     match format {
-        OutputFormat::Motd => render_motd(repos),
-        OutputFormat::Json => render_json(repos),
+        OutputFormat::Motd => render_motd(repos, options),
+        OutputFormat::One => render_one(repos, options),
+        // Machine formats are built without `colored`, so they never carry
+        // ANSI, even with CLICOLOR_FORCE set
+        OutputFormat::Json => render_json(repos, options.json_fields.as_deref()),
+        OutputFormat::Csv => render_csv(repos),
+        OutputFormat::Markdown => render_markdown(repos, options),
+        OutputFormat::Yaml => render_yaml(repos),
+        OutputFormat::JsonLines => render_jsonl(repos, options.json_fields.as_deref()),
+        OutputFormat::Html => render_html(repos, options),
+        OutputFormat::Urls => render_urls(repos),
+    }
+}
+
+/// Render the first repository (the top one after sorting) as a banner line
+fn render_one(repos: &[Repo], options: &RenderOptions) {
+    match repos.first() {
+        Some(repo) => println!("{}", format_one(repo, options)),
+        None => println!("No trending repositories found today."),
     }
 }
 
+/// Format a repository as "🔥 Today's trending: owner/name (Language) — description"
+fn format_one(repo: &Repo, options: &RenderOptions) -> String {
+    let language = repo
+        .language
+        .as_ref()
+        .map(|language| format!(" ({language})"))
+        .unwrap_or_default();
+    let description = format_description(
+        repo,
+        options,
+        options.desc_width.unwrap_or(DEFAULT_DESC_WIDTH),
+    );
+    let description = if description.is_empty() {
+        description
+    } else {
+        format!(" — {description}")
+    };
+    let name = display_name(repo, options).bold();
+    format!("🔥 Today's trending: {name}{language}{description}")
+}
+
 /// Render plain MOTD format with table alignment
-fn render_motd(repos: &[Repo]) {
+fn render_motd(repos: &[Repo], options: &RenderOptions) {
+    println!("{}", format_motd(repos, options));
+}
+
+/// Format repositories as aligned MOTD rows, one line each
+fn format_motd(repos: &[Repo], options: &RenderOptions) -> String {
     if repos.is_empty() {
-        println!("No trending repositories found today.");
-        return;
+        return "No trending repositories found today.".to_string();
     }
 
+    let shown = options.page_size.unwrap_or(repos.len()).min(repos.len());
+    let visible = &repos[..shown];
+
     // Calculate column widths for alignment
-    let max_name_len = repos
+    let max_name_len = visible
+        .iter()
+        .map(|r| display_name(r, options).width())
+        .max()
+        .unwrap_or(0);
+
+    let max_lang_len = visible
+        .iter()
+        .map(|r| r.language.as_deref().unwrap_or("-").width())
+        .max()
+        .unwrap_or(0);
+
+    // Icon presets differ in display width (e.g. "[GH]" vs "🐙")
+    let icon_width = visible.iter().map(|r| r.icon.width()).max().unwrap_or(0);
+
+    let badge_width = if options.badges {
+        visible
+            .iter()
+            .map(|r| format_badges(r).width())
+            .max()
+            .unwrap_or(0)
+    } else {
+        0
+    };
+
+    let stars_width = visible
         .iter()
-        .map(|r| r.name.width())
+        .map(|r| format_stars(r, options).width())
         .max()
-        .unwrap_or(0)
-        .min(40); // Cap name width at 40 chars
+        .unwrap_or(0);
 
-    let max_lang_len = repos
+    // Only reserve a delta column when some row has one
+    let delta_width = visible
         .iter()
-        .filter_map(|r| r.language.as_ref().map(|l| l.width()))
+        .map(|r| format_stars_delta(r).width())
         .max()
-        .unwrap_or(0)
-        .min(15); // Cap language width at 15 chars
+        .unwrap_or(0);
+
+    // Everything on a row except the name, language and description columns
+    let overhead = if options.mark_cached { 2 } else { 0 }
+        + icon_width
+        + 3 // spaces around name and language
+        + stars_width + 1
+        + if delta_width > 0 { delta_width + 1 } else { 0 }
+        + if options.badges { badge_width + 1 } else { 0 }
+        + if options.show_velocity { 10 } else { 0 }
+        + 11; // recency and its trailing space
+    let (name, lang, desc) = fit_columns(
+        max_name_len,
+        max_lang_len,
+        overhead,
+        options.term_width,
+        options.desc_width,
+    );
+
+    let widths = ColumnWidths {
+        icon: icon_width,
+        name,
+        lang,
+        stars: stars_width,
+        badges: badge_width,
+        delta: delta_width,
+        // Wrapped descriptions go on their own lines, outside the row budget
+        desc: if options.wrap_desc.is_some() {
+            options.desc_width.unwrap_or(DEFAULT_DESC_WIDTH)
+        } else {
+            desc
+        },
+    };
+
+    let mut lines = Vec::new();
+    for (i, repo) in visible.iter().enumerate() {
+        // Separate pinned repos from trending ones
+        if i > 0 && visible[i - 1].pinned && !repo.pinned {
+            lines.push("── trending ──".dimmed().to_string());
+        }
+        lines.push(format_repo_motd(repo, &widths, options));
+    }
+
+    if shown < repos.len() {
+        lines.push(more_marker(repos.len() - shown).dimmed().to_string());
+    }
+
+    if options.legend {
+        lines.push(render_legend(visible, &options.icon_colors));
+    }
+
+    if options.summary {
+        lines.push(
+            format_summary(visible, options.star_separator)
+                .dimmed()
+                .to_string(),
+        );
+    }
+
+    lines.join("\n")
+}
+
+/// Build a summary footer, e.g. "4 repos · 2 GitHub, 1 GitLab, 1 Gitea · 12,340 total stars"
+fn format_summary(repos: &[Repo], separator: Option<char>) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for repo in repos {
+        match counts
+            .iter_mut()
+            .find(|(provider, _)| *provider == repo.provider)
+        {
+            Some((_, count)) => *count += 1,
+            None => counts.push((&repo.provider, 1)),
+        }
+    }
+    let per_provider: Vec<String> = counts
+        .iter()
+        .map(|(provider, count)| format!("{count} {}", provider_name(provider)))
+        .collect();
+    let stars: u64 = repos.iter().filter_map(|r| r.stars_total).sum();
 
+    format!(
+        "{} {} · {} · {} total stars",
+        repos.len(),
+        if repos.len() == 1 { "repo" } else { "repos" },
+        per_provider.join(", "),
+        group_digits(stars, separator)
+    )
+}
+
+/// Build a legend footer for the providers present, e.g. "[GH] GitHub  [GE] Gitea"
+pub fn render_legend(repos: &[Repo], icon_colors: &BTreeMap<String, colored::Color>) -> String {
+    let mut seen: Vec<(&str, &str)> = Vec::new();
     for repo in repos {
-        render_repo_motd(repo, max_name_len, max_lang_len);
+        if !seen.iter().any(|(provider, _)| *provider == repo.provider) {
+            seen.push((&repo.provider, &repo.icon));
+        }
+    }
+
+    seen.iter()
+        .map(|(provider, icon)| {
+            let icon = colorize_icon(provider, icon, icon_colors);
+            format!("{icon} {}", provider_name(provider))
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// Human-readable provider name for a provider id
+fn provider_name(provider: &str) -> &str {
+    match provider {
+        "github" => "GitHub",
+        "gitlab" => "GitLab",
+        "gitea" => "Gitea",
+        "codeberg" => "Codeberg",
+        "bitbucket" => "Bitbucket",
+        "crates" => "crates.io",
+        other => other,
+    }
+}
+
+/// Color a provider icon using the provider's brand color
+///
+/// Instances such as `gitea@codeberg.org` take their kind's color. A color
+/// configured in `[icons]` for the exact id, then for the kind, wins.
+fn colorize_icon(
+    provider: &str,
+    icon: &str,
+    icon_colors: &BTreeMap<String, colored::Color>,
+) -> ColoredString {
+    let kind = provider.split('@').next().unwrap_or(provider);
+    if let Some(&color) = icon_colors.get(provider).or_else(|| icon_colors.get(kind)) {
+        return icon.color(color);
+    }
+    match kind {
+        "github" => icon.bright_purple(),
+        "gitlab" => icon.bright_red(),
+        "gitea" => icon.bright_green(),
+        "codeberg" => icon.bright_blue(),
+        "bitbucket" => icon.blue(),
+        "crates" => icon.yellow(),
+        _ => icon.white(),
+    }
+}
+
+/// Marker printed when `page_size` hides the remaining rows
+fn more_marker(remaining: usize) -> String {
+    format!("── {remaining} more ──")
+}
+
+/// Repository name as displayed, with emoji stripped if enabled
+fn display_name(repo: &Repo, options: &RenderOptions) -> String {
+    if options.strip_emoji {
+        strip_emoji(&repo.name)
+    } else {
+        repo.name.clone()
     }
 }
 
+/// Check if a character is an emoji or other pictographic symbol
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF // Mahjong, cards, emoticons, pictographs, flags, symbols
+            | 0x2600..=0x27BF // Miscellaneous symbols and dingbats
+            | 0x2B00..=0x2BFF // Miscellaneous symbols and arrows (⭐, ⬆)
+            | 0x2300..=0x23FF // Miscellaneous technical (⌚, ⏳)
+            | 0x203C | 0x2049 // Double exclamation and interrobang (‼, ⁉)
+            | 0x25AA..=0x25AB | 0x25B6 | 0x25C0 | 0x25FB..=0x25FE // Geometric shapes (▶, ◾)
+            | 0x2934..=0x2935 // Curved arrows (⤴, ⤵)
+            | 0x303D | 0x3297 | 0x3299 // Part alternation mark, ㊗, ㊙
+            | 0xFE00..=0xFE0F // Variation selectors
+            | 0x200D // Zero width joiner
+            | 0x20E3 // Combining enclosing keycap
+            | 0xE0020..=0xE007F // Tag characters (subdivision flags)
+    )
+}
+
+/// Remove emoji/pictographic characters and tidy up leftover whitespace
+fn strip_emoji(s: &str) -> String {
+    let stripped: String = s.chars().filter(|c| !is_emoji(*c)).collect();
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 /// Clean description by removing/simplifying markdown syntax
-fn clean_description(desc: &str) -> String {
+///
+/// Emoji are stripped as well when `strip_emoji` is enabled.
+fn clean_description(desc: &str, options: &RenderOptions) -> String {
     let mut result = desc.to_string();
 
     // Remove image markdown ![alt](url) - must be done before link conversion
@@ -70,7 +487,11 @@ fn clean_description(desc: &str) -> String {
     let spaces_re = regex::Regex::new(r"\s+").unwrap();
     result = spaces_re.replace_all(&result, " ").to_string();
 
-    result.trim().to_string()
+    if options.strip_emoji {
+        strip_emoji(&result)
+    } else {
+        result.trim().to_string()
+    }
 }
 
 /// Clean up truncated text to remove incomplete words or markdown
@@ -102,12 +523,101 @@ fn clean_truncated_text(text: &str) -> String {
     result
 }
 
-/// Format recency from last_activity timestamp
-fn format_recency(repo: &Repo) -> String {
-    match repo.last_activity {
+/// Format a number with a thousands separator (e.g. `90000` -> `90,000`)
+fn group_digits(n: u64, separator: Option<char>) -> String {
+    let digits = n.to_string();
+    let Some(sep) = separator else {
+        return digits;
+    };
+
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(sep);
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Abbreviate a count to one decimal (e.g. `90000` -> `90.0k`, `1500000` -> `1.5M`)
+///
+/// Rounds in integer tenths, so a count that would show as `1000.0k` moves up to `1.0M`.
+fn humanize_count(n: u64) -> String {
+    let tenths_k = (n + 50) / 100;
+    if n < 1_000 {
+        n.to_string()
+    } else if tenths_k < 10_000 {
+        format!("{}.{}k", tenths_k / 10, tenths_k % 10)
+    } else {
+        let tenths_m = (n + 50_000) / 100_000;
+        format!("{}.{}M", tenths_m / 10, tenths_m % 10)
+    }
+}
+
+/// Build the badge cluster, e.g. `★90.0k ⑂1.2k ⚠42`, skipping missing counts
+fn format_badges(repo: &Repo) -> String {
+    [
+        ('★', repo.stars_total),
+        ('⑂', repo.forks_count),
+        ('⚠', repo.open_issues),
+    ]
+    .iter()
+    .filter_map(|(symbol, count)| count.map(|n| format!("{symbol}{}", humanize_count(n))))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Unpadded MOTD stars column: `★12 today`, `★90.0k` total, or `⇩1.2M` downloads
+fn format_stars(repo: &Repo, options: &RenderOptions) -> String {
+    let count = |n: u64| {
+        if options.humanize_stars {
+            humanize_count(n)
+        } else {
+            group_digits(n, options.star_separator)
+        }
+    };
+    if let Some(stars_today) = repo.stars_today {
+        format!("★{} today", count(stars_today))
+    } else if let Some(stars_total) = repo.stars_total {
+        format!("★{}", count(stars_total))
+    } else if let Some(downloads) = repo.downloads {
+        format!("⇩{}", count(downloads))
+    } else {
+        String::new()
+    }
+}
+
+/// Padded, colored star delta column (green gains, red losses); empty at width 0
+fn delta_column(repo: &Repo, width: usize) -> String {
+    if width == 0 {
+        return String::new();
+    }
+    let text = format_stars_delta(repo);
+    let pad = " ".repeat(width.saturating_sub(text.width()));
+    let colored = if repo.stars_delta.is_some_and(|d| d < 0) {
+        text.red()
+    } else {
+        text.green()
+    };
+    format!("{colored}{pad} ")
+}
+
+/// Star change since the previous fetch, e.g. `(+42)` or `(-3)`; empty when unchanged
+fn format_stars_delta(repo: &Repo) -> String {
+    match repo.stars_delta {
+        Some(delta) if delta != 0 => format!("({delta:+})"),
+        _ => String::new(),
+    }
+}
+
+/// Format recency from `last_activity` timestamp
+fn format_recency(last_activity: Option<DateTime<Utc>>, now: DateTime<Utc>) -> String {
+    match last_activity {
         Some(dt) => {
-            let now = Utc::now();
-            let diff = now.signed_duration_since(dt);
+            // Clock skew or a misconfigured instance can report future
+            // timestamps; treat those as happening right now
+            let diff = now.signed_duration_since(dt).max(Duration::zero());
 
             if diff < Duration::hours(24) {
                 "today".to_string()
@@ -125,193 +635,1674 @@ fn format_recency(repo: &Repo) -> String {
     }
 }
 
-/// Render a single repository in MOTD format with colors and alignment
-fn render_repo_motd(repo: &Repo, name_width: usize, lang_width: usize) {
-    // Icon (colored by provider)
-    let icon = match repo.provider.as_str() {
-        "github" => repo.icon.bright_purple(),
-        "gitlab" => repo.icon.bright_red(),
-        "gitea" => repo.icon.bright_green(),
-        _ => repo.icon.white(),
+/// Display widths of the aligned MOTD columns
+struct ColumnWidths {
+    icon: usize,
+    name: usize,
+    lang: usize,
+    stars: usize,
+    badges: usize,
+    delta: usize,
+    /// Description budget; 0 omits the column
+    desc: usize,
+}
+
+/// Name column cap when the terminal width is unknown
+const NAME_CAP: usize = 40;
+
+/// Language column cap
+const LANG_CAP: usize = 15;
+
+/// Size the name, language and description columns
+///
+/// Takes the natural (widest) name and language widths and the width of the
+/// rest of the row. Without a terminal width the fixed caps apply; with one,
+/// the name may grow on wide terminals and every column shrinks on narrow
+/// ones so the row never exceeds the terminal. Returns `(name, lang, desc)`.
+fn fit_columns(
+    name: usize,
+    lang: usize,
+    overhead: usize,
+    term_width: Option<usize>,
+    desc_width: Option<usize>,
+) -> (usize, usize, usize) {
+    let Some(term_width) = term_width else {
+        return (
+            name.min(NAME_CAP),
+            lang.min(LANG_CAP),
+            desc_width.unwrap_or(DEFAULT_DESC_WIDTH),
+        );
     };
 
+    let budget = term_width.saturating_sub(overhead);
+    let lang = lang.min(LANG_CAP).min(budget / 4);
+    let rest = budget - lang;
+    let name = name.min((rest / 2).max(10)).min(rest);
+    let desc = (rest - name).min(desc_width.unwrap_or(usize::MAX));
+    // Too narrow for any text next to the "..." marker
+    let desc = if desc <= 3 { 0 } else { desc };
+    (name, lang, desc)
+}
+
+/// Shorten text wider than `width` display columns, marking the cut with ".."
+fn truncate_column(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    format!("{}..", take_width(text, width.saturating_sub(2)))
+}
+
+/// Longest prefix of `text` that fits in `width` display columns
+fn take_width(text: &str, width: usize) -> String {
+    let mut total_width = 0;
+    text.chars()
+        .take_while(|c| {
+            total_width += if c.is_ascii() {
+                1
+            } else {
+                c.width().unwrap_or(1)
+            };
+            total_width <= width
+        })
+        .collect()
+}
+
+/// Format a single repository in MOTD format with colors and alignment
+///
+/// Wrapped descriptions (`wrap_desc`) follow on extra lines.
+fn format_repo_motd(repo: &Repo, widths: &ColumnWidths, options: &RenderOptions) -> String {
+    let name_width = widths.name;
+    let lang_width = widths.lang;
+
+    // Icon (colored by provider, padded by display width)
+    let icon_pad = " ".repeat(widths.icon.saturating_sub(repo.icon.width()));
+    let icon = format!(
+        "{}{icon_pad}",
+        colorize_icon(&repo.provider, &repo.icon, &options.icon_colors)
+    );
+
     // Name (truncate if too long, pad for alignment)
-    let name_display = if repo.name.width() > name_width {
-        let mut total_width = 0;
-        let truncated: String = repo.name.chars().take_while(|c| {
-            let char_width = if c.is_ascii() { 1 } else { c.width().unwrap_or(1) };
-            total_width += char_width;
-            total_width <= name_width - 2
-        }).collect();
-        format!("{truncated}..")
+    let name_display = truncate_column(&display_name(repo, options), name_width);
+    // Pad by display width: CJK and emoji take two cells, and a hyperlink's
+    // escape sequence takes none
+    let pad = " ".repeat(name_width.saturating_sub(name_display.width()));
+    let name_padded = if link_names(options) {
+        format!("{}{pad}", hyperlink(&name_display, &repo.url))
     } else {
-        repo.name.clone()
+        format!("{name_display}{pad}")
     };
-    let name_padded = format!("{:<width$}", name_display, width = name_width);
-    let name = name_padded.bright_cyan().bold();
+    let mut name = name_padded.bright_cyan().bold();
 
     // Language (pad for alignment)
-    let lang_display = repo.language.as_deref().unwrap_or("-");
-    let lang_truncated = if lang_display.width() > lang_width {
-        let mut total_width = 0;
-        let truncated: String = lang_display.chars().take_while(|c| {
-            let char_width = if c.is_ascii() { 1 } else { c.width().unwrap_or(1) };
-            total_width += char_width;
-            total_width <= lang_width - 2
-        }).collect();
-        format!("{truncated}..")
-    } else {
-        lang_display.to_string()
-    };
-    let lang_padded = format!("{:<width$}", lang_truncated, width = lang_width);
+    let lang_truncated = truncate_column(repo.language.as_deref().unwrap_or("-"), lang_width);
+    let lang_pad = " ".repeat(lang_width.saturating_sub(lang_truncated.width()));
+    let lang_padded = format!("{lang_truncated}{lang_pad}");
     let lang = lang_padded.bright_yellow();
 
     // Stars
-    let stars = if let Some(stars_today) = repo.stars_today {
-        format!("★{:<4} today", stars_today).bright_green().to_string()
-    } else if let Some(stars_total) = repo.stars_total {
-        format!("★{:<10}", stars_total)
-            .bright_black()
-            .to_string()
+    let stars_text = format_stars(repo, options);
+    let stars_pad = " ".repeat(widths.stars.saturating_sub(stars_text.width()));
+    let stars = if repo.stars_today.is_some() {
+        format!("{}{stars_pad}", stars_text.bright_green())
     } else {
-        format!("{:<11}", "").to_string()
+        format!("{}{stars_pad}", stars_text.bright_black())
     };
 
-    // Recency
-    let recency = format_recency(repo);
-    let recency_colored = match recency.as_str() {
-        "today" => recency.bright_green(),
-        "yesterday" => recency.yellow(),
-        _ => recency.bright_black(),
+    // Star change since the last fetch (only when some row has one)
+    let delta = delta_column(repo, widths.delta);
+
+    // Badges (optional column, padded by display width)
+    let badges = if options.badges {
+        let text = format_badges(repo);
+        let pad = " ".repeat(widths.badges.saturating_sub(text.width()));
+        format!("{}{pad} ", text.bright_black())
+    } else {
+        String::new()
     };
 
-    // Description (truncate for remaining space)
-    let desc = if let Some(ref d) = repo.description {
-        let cleaned = clean_description(d);
-        if cleaned.width() > 45 {
-            let mut total_width = 0;
-            let truncated: String = cleaned.chars().take_while(|c| {
-                let char_width = if c.is_ascii() { 1 } else { c.width().unwrap_or(1) };
-                total_width += char_width;
-                total_width <= 42
-            }).collect();
-            let final_text = clean_truncated_text(&truncated);
-            format!("{final_text}...")
-        } else {
-            cleaned
-        }
+    // Velocity (optional column)
+    let velocity = if options.show_velocity {
+        let text = repo
+            .velocity()
+            .map_or_else(|| "-".to_string(), |v| format!("{v:.1}/d"));
+        format!("{text:<9} ").bright_blue().to_string()
     } else {
         String::new()
     };
 
-    // Print aligned columns
-    println!(
-        "{} {} {} {} {} {}",
-        icon,
-        name,
-        lang,
-        stars,
-        format!("{:<10}", recency_colored),
-        desc.white()
-    );
-}
+    // Recency
+    let now = Utc::now();
+    let recency = format_recency(repo.last_activity, now);
+    let tier = options.aging.tier(repo.last_activity, now);
+    let recency_colored = color_recency(&recency, tier);
 
-/// Render JSON format
-fn render_json(repos: &[Repo]) {
-    let output = json!(repos);
-    println!("{}", serde_json::to_string_pretty(&output).unwrap());
-}
+    // Description (truncate for remaining space)
+    let desc = format_description(repo, options, widths.desc);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    // Cache marker (only when enabled, blank for live rows to keep alignment)
+    let marker = if !options.mark_cached {
+        String::new()
+    } else if repo.from_cache {
+        format!("{} ", "⟳".dimmed())
+    } else {
+        "  ".to_string()
+    };
 
-    #[test]
-    fn test_render_empty() {
-        let repos = vec![];
-        // This will print to stdout, but won't panic
-        render(&repos, OutputFormat::Motd);
+    // Fade the whole row for older repos
+    let style = |text: &str| {
+        let text = text.white();
+        if options.dim_old {
+            fade(text, tier)
+        } else {
+            text
+        }
+    };
+    if options.dim_old {
+        name = fade(name, tier);
     }
 
-    #[test]
-    fn test_render_json() {
-        let repos = vec![Repo {
-            provider: "github".to_string(),
-            icon: "[GH]".to_string(),
-            name: "test/repo".to_string(),
-            language: Some("Rust".to_string()),
-            description: Some("Test repository".to_string()),
-            url: "https://github.com/test/repo".to_string(),
-            stars_today: Some(10),
-            stars_total: Some(100),
-            last_activity: Some(Utc::now()),
-            topics: vec!["rust".to_string(), "cli".to_string()],
-        }];
+    let row = format!("{marker}{icon} {name} {lang} {stars} {delta}{badges}{velocity}");
 
-        render(&repos, OutputFormat::Json);
+    let Some(width) = options.wrap_desc else {
+        if widths.desc == 0 {
+            return format!("{row}{recency_colored}");
+        }
+        let desc = style(&desc);
+        return format!("{row}{recency_colored:<10} {desc}");
+    };
+
+    // Full description on indented continuation lines
+    let mut lines = vec![format!("{row}{recency_colored}")];
+    let indent = " ".repeat(WRAP_INDENT);
+    for line in wrap_text(&desc, width.saturating_sub(WRAP_INDENT).max(20)) {
+        lines.push(format!("{indent}{}", style(&line)));
     }
+    lines.join("\n")
+}
 
-    #[test]
-    fn test_render_motd() {
-        let repos = vec![
-            Repo {
-                provider: "github".to_string(),
-                icon: "[GH]".to_string(),
-                name: "rust-lang/rust".to_string(),
-                language: Some("Rust".to_string()),
-                description: Some(
-                    "Empowering everyone to build reliable and efficient software.".to_string(),
-                ),
-                url: "https://github.com/rust-lang/rust".to_string(),
-                stars_today: Some(50),
-                stars_total: Some(90000),
-                last_activity: Some(Utc::now()),
-                topics: vec!["rust".to_string(), "compiler".to_string()],
-            },
-            Repo {
-                provider: "gitlab".to_string(),
-                icon: "[GL]".to_string(),
-                name: "gitlab-org/gitlab".to_string(),
-                language: Some("Ruby".to_string()),
-                description: None,
-                url: "https://gitlab.com/gitlab-org/gitlab".to_string(),
-                stars_today: None,
-                stars_total: Some(5000),
-                last_activity: Some(Utc::now() - Duration::days(3)),
-                topics: vec!["gitlab".to_string(), "ruby".to_string()],
-            },
-        ];
+/// Whether MOTD names get OSC 8 links
+///
+/// Terminals without OSC 8 support print the raw escapes, so links are only
+/// emitted when coloring a real terminal.
+fn link_names(options: &RenderOptions) -> bool {
+    options.hyperlinks
+        && colored::control::SHOULD_COLORIZE.should_colorize()
+        && std::io::stdout().is_terminal()
+}
 
-        render(&repos, OutputFormat::Motd);
+/// Wrap text in an OSC 8 escape sequence linking it to `url`
+///
+/// Only `http(s)://` URLs without control characters are linked, so an
+/// untrusted instance can't end the sequence early and inject escapes.
+fn hyperlink(text: &str, url: &str) -> String {
+    if !is_web_url(url) {
+        return text.to_string();
     }
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
 
-    #[test]
-    fn test_clean_description_markdown_links() {
-        // Full markdown links should be converted to just text
-        let desc = "Check out [README](https://example.com) for more info";
-        let cleaned = clean_description(desc);
-        assert_eq!(cleaned, "Check out README for more info");
-    }
+/// Default description column width
+const DEFAULT_DESC_WIDTH: usize = 45;
 
-    #[test]
-    fn test_clean_description_multiple_links() {
-        let desc = "See [docs](url1) and [API](url2) for details";
-        let cleaned = clean_description(desc);
-        assert_eq!(cleaned, "See docs and API for details");
+/// Cleaned description, truncated to `desc_width` unless it will be wrapped
+fn format_description(repo: &Repo, options: &RenderOptions, desc_width: usize) -> String {
+    if desc_width == 0 {
+        return String::new();
     }
-
-    #[test]
-    fn test_clean_description_bold_italic() {
-        let desc = "This is **bold** and __also bold__ text";
-        let cleaned = clean_description(desc);
-        assert_eq!(cleaned, "This is bold and also bold text");
+    if let Some(ref d) = repo.description {
+        let cleaned = clean_description(d, options);
+        if options.wrap_desc.is_some() {
+            cleaned
+        } else if cleaned.width() > desc_width {
+            // Like `truncate_column`, but tidying the cut and marking it with "..."
+            let truncated = take_width(&cleaned, desc_width.saturating_sub(3));
+            format!("{}...", clean_truncated_text(&truncated))
+        } else {
+            cleaned
+        }
+    } else {
+        String::new()
     }
+}
 
-    #[test]
-    fn test_clean_description_images() {
-        let desc = "Project logo ![logo](image.png) here";
-        let cleaned = clean_description(desc);
-        assert_eq!(cleaned, "Project logo here");
+/// Indentation of wrapped description lines
+const WRAP_INDENT: usize = 6;
+
+/// Word-wrap text so every line fits `width` display columns
+///
+/// Words wider than a full line are broken across lines.
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let needed = if line.is_empty() {
+            word.width()
+        } else {
+            line.width() + 1 + word.width()
+        };
+        if needed <= width {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            if line.width() + c.width().unwrap_or(0) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Render JSON format, optionally projected onto a subset of fields
+fn render_json(repos: &[Repo], fields: Option<&[String]>) {
+    match fields {
+        Some(fields) => println!("{}", format_json_fields(repos, fields)),
+        None => println!("{}", format_json(repos)),
+    }
+}
+
+/// Render bare URLs, one per line
+fn render_urls(repos: &[Repo]) {
+    print!("{}", format_urls(repos));
+}
+
+/// Join repository URLs with trailing newlines
+fn format_urls(repos: &[Repo]) -> String {
+    let mut out = String::new();
+    for repo in repos {
+        out.push_str(&repo.url);
+        out.push('\n');
+    }
+    out
+}
+
+/// Serialize repositories as pretty-printed JSON
+fn format_json(repos: &[Repo]) -> String {
+    let output = json!(repos);
+    serde_json::to_string_pretty(&output).unwrap()
+}
+
+/// Serialize repositories keeping only `fields`, in the requested order
+fn format_json_fields(repos: &[Repo], fields: &[String]) -> String {
+    let projected: Vec<serde_json::Value> = repos
+        .iter()
+        .map(|repo| project_fields(repo, fields))
+        .collect();
+    serde_json::to_string_pretty(&projected).unwrap()
+}
+
+/// Build a JSON object holding only `fields` of `repo`
+fn project_fields(repo: &Repo, fields: &[String]) -> serde_json::Value {
+    let full = json!(repo);
+    let mut object = serde_json::Map::new();
+    for field in fields {
+        object.insert(field.clone(), full.get(field).cloned().unwrap_or_default());
+    }
+    serde_json::Value::Object(object)
+}
+
+/// Serialize one repository as a single NDJSON line (no trailing newline)
+pub fn format_ndjson_line(repo: &Repo, fields: Option<&[String]>) -> String {
+    match fields {
+        Some(fields) => project_fields(repo, fields).to_string(),
+        None => json!(repo).to_string(),
+    }
+}
+
+/// Render JSON Lines: one object per line, no enclosing array
+fn render_jsonl(repos: &[Repo], fields: Option<&[String]>) {
+    print!("{}", format_jsonl(repos, fields));
+}
+
+/// Join NDJSON lines for all repositories, each with a trailing newline
+fn format_jsonl(repos: &[Repo], fields: Option<&[String]>) -> String {
+    let mut out = String::new();
+    for repo in repos {
+        out.push_str(&format_ndjson_line(repo, fields));
+        out.push('\n');
+    }
+    out
+}
+
+/// Validate one `--json-fields` entry against the `Repo` fields
+///
+/// `stars` is accepted as shorthand for `stars_total`.
+pub fn parse_json_field(field: &str) -> Result<String, String> {
+    let field = match field.trim() {
+        "stars" => "stars_total",
+        other => other,
+    };
+    if Repo::FIELDS.contains(&field) {
+        Ok(field.to_string())
+    } else {
+        Err(format!(
+            "unknown field '{field}' (expected one of: {})",
+            Repo::FIELDS.join(", ")
+        ))
+    }
+}
+
+/// Write repositories to `path` in a machine format, independent of stdout rendering
+pub fn write_export(repos: &[Repo], format: ExportFormat, path: &Path) -> std::io::Result<()> {
+    let content = match format {
+        ExportFormat::Json => format_json(repos) + "\n",
+        ExportFormat::Csv => format_csv(repos),
+    };
+    std::fs::write(path, content)
+}
+
+/// Render repositories as CSV with a header row
+fn render_csv(repos: &[Repo]) {
+    print!("{}", format_csv(repos));
+}
+
+/// Serialize repositories as CSV with a header row
+///
+/// Descriptions are cleaned first so embedded newlines never split a row.
+fn format_csv(repos: &[Repo]) -> String {
+    let mut out = String::from(
+        "provider,name,language,stars_total,stars_today,last_activity,url,description\n",
+    );
+    for repo in repos {
+        let count = |n: Option<u64>| n.map(|n| n.to_string()).unwrap_or_default();
+        let fields = [
+            repo.provider.clone(),
+            repo.name.clone(),
+            repo.language.clone().unwrap_or_default(),
+            count(repo.stars_total),
+            count(repo.stars_today),
+            repo.last_activity
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default(),
+            repo.url.clone(),
+            repo.description
+                .as_deref()
+                .map(|d| clean_description(d, &RenderOptions::default()))
+                .unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Render YAML format
+fn render_yaml(repos: &[Repo]) {
+    print!("{}", format_yaml(repos));
+}
+
+/// Serialize repositories as a block-style YAML list
+///
+/// Goes through the same serde representation as JSON, so field order and
+/// RFC3339 timestamps match `--json`. Strings are emitted double-quoted with
+/// JSON escapes, which YAML reads identically.
+fn format_yaml(repos: &[Repo]) -> String {
+    let value = serde_json::to_value(repos).unwrap_or_default();
+    if !is_yaml_block(&value) {
+        return format!("{}\n", yaml_scalar(&value));
+    }
+    yaml_lines(&value)
+        .into_iter()
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Whether a value is written as nested lines rather than inline
+fn is_yaml_block(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Array(items) => !items.is_empty(),
+        serde_json::Value::Object(map) => !map.is_empty(),
+        _ => false,
+    }
+}
+
+/// Inline YAML for scalars and empty collections
+fn yaml_scalar(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Array(_) => "[]".to_string(),
+        serde_json::Value::Object(_) => "{}".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Block YAML lines for a non-empty array or object, unindented
+fn yaml_lines(value: &serde_json::Value) -> Vec<String> {
+    let mut lines = Vec::new();
+    match value {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                if is_yaml_block(item) {
+                    for (i, line) in yaml_lines(item).into_iter().enumerate() {
+                        lines.push(if i == 0 {
+                            format!("- {line}")
+                        } else {
+                            format!("  {line}")
+                        });
+                    }
+                } else {
+                    lines.push(format!("- {}", yaml_scalar(item)));
+                }
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                if is_yaml_block(value) {
+                    lines.push(format!("{key}:"));
+                    lines.extend(
+                        yaml_lines(value)
+                            .into_iter()
+                            .map(|line| format!("  {line}")),
+                    );
+                } else {
+                    lines.push(format!("{key}: {}", yaml_scalar(value)));
+                }
+            }
+        }
+        _ => {}
+    }
+    lines
+}
+
+/// Render repositories as a Markdown table
+fn render_markdown(repos: &[Repo], options: &RenderOptions) {
+    print!("{}", format_markdown(repos, options, Utc::now()));
+}
+
+/// Format repositories as a GitHub-flavored Markdown table
+///
+/// Cells carry the same content as the MOTD columns, with full (untruncated)
+/// descriptions and the repository name linked to its URL (http(s) only).
+fn format_markdown(repos: &[Repo], options: &RenderOptions, now: DateTime<Utc>) -> String {
+    let header = "| Repo | Lang | Stars | Updated | Description |\n|---|---|---|---|---|\n";
+    let rows: Vec<String> = repos
+        .iter()
+        .map(|repo| {
+            let stars = stars_cell(repo, options);
+            let description = repo
+                .description
+                .as_deref()
+                .map(|d| clean_description(d, options))
+                .unwrap_or_default();
+            // Untrusted instances could hand out `javascript:` links
+            let name = markdown_cell(&display_name(repo, options));
+            let name = if is_web_url(&repo.url) {
+                format!("[{name}]({})", markdown_url(&repo.url))
+            } else {
+                name
+            };
+            format!(
+                "| {name} | {} | {stars} | {} | {} |\n",
+                markdown_cell(repo.language.as_deref().unwrap_or("-")),
+                format_recency(repo.last_activity, now),
+                markdown_cell(&description),
+            )
+        })
+        .collect();
+    format!("{header}{}", rows.concat())
+}
+
+/// Stars cell for the Markdown and HTML tables, falling back to downloads
+fn stars_cell(repo: &Repo, options: &RenderOptions) -> String {
+    if let Some(stars_today) = repo.stars_today {
+        format!(
+            "★{} today",
+            group_digits(stars_today, options.star_separator)
+        )
+    } else if let Some(stars_total) = repo.stars_total {
+        format!("★{}", group_digits(stars_total, options.star_separator))
+    } else if let Some(downloads) = repo.downloads {
+        format!("⇩{}", group_digits(downloads, options.star_separator))
+    } else {
+        "-".to_string()
+    }
+}
+
+/// Render HTML format
+fn render_html(repos: &[Repo], options: &RenderOptions) {
+    print!("{}", format_html(repos, options, Utc::now()));
+}
+
+/// Provider colors for HTML output, matching the MOTD icon colors
+const HTML_STYLE: &str = "<style>
+.trotd .provider-github { color: #c678dd; }
+.trotd .provider-gitlab { color: #e06c75; }
+.trotd .provider-gitea { color: #98c379; }
+.trotd .provider-codeberg { color: #61afef; }
+.trotd .provider-bitbucket { color: #2f6fd6; }
+.trotd .provider-crates { color: #d19a66; }
+</style>
+";
+
+/// Format repositories as an HTML table fragment
+///
+/// Columns match the Markdown table, plus the provider icon. Each row carries
+/// a `provider-<kind>` class (instances share their kind's) for styling.
+fn format_html(repos: &[Repo], options: &RenderOptions, now: DateTime<Utc>) -> String {
+    let header = "<table class=\"trotd\">\n<thead><tr><th></th><th>Repo</th><th>Lang</th>\
+                  <th>Stars</th><th>Updated</th><th>Description</th></tr></thead>\n<tbody>\n";
+    let rows: Vec<String> = repos
+        .iter()
+        .map(|repo| {
+            let stars = stars_cell(repo, options);
+            let description = repo
+                .description
+                .as_deref()
+                .map(|d| clean_description(d, options))
+                .unwrap_or_default();
+            // Untrusted instances could hand out `javascript:` links
+            let name = html_escape(&display_name(repo, options));
+            let name = if is_web_url(&repo.url) {
+                format!("<a href=\"{}\">{name}</a>", html_escape(&repo.url))
+            } else {
+                name
+            };
+            format!(
+                "<tr class=\"provider-{}\"><td>{}</td><td>{name}</td>\
+                 <td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(repo.provider_kind()),
+                html_escape(&repo.icon),
+                html_escape(repo.language.as_deref().unwrap_or("-")),
+                html_escape(&stars),
+                format_recency(repo.last_activity, now),
+                html_escape(&description),
+            )
+        })
+        .collect();
+    format!("{HTML_STYLE}{header}{}</tbody>\n</table>\n", rows.concat())
+}
+
+/// Escape text for HTML element content and quoted attribute values
+fn html_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape pipes so cell content can't split a Markdown table row
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+/// Percent-encode characters that would end a Markdown link target or split
+/// the table row
+fn markdown_url(url: &str) -> String {
+    let mut out = String::with_capacity(url.len());
+    for c in url.chars() {
+        match c {
+            '(' => out.push_str("%28"),
+            ')' => out.push_str("%29"),
+            '|' => out.push_str("%7C"),
+            ' ' => out.push_str("%20"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Quote a CSV field when it contains a delimiter, quote, or line break
+///
+/// Fields starting with `=`, `+`, `-`, `@`, tab, or carriage return get a `'`
+/// prefix so spreadsheets don't evaluate descriptions as formulas.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use colored::Styles;
+
+    /// Output with ANSI color escapes removed (other tests toggle colors globally)
+    fn plain(text: &str) -> String {
+        regex::Regex::new(r"\x1b\[[0-9;]*m")
+            .unwrap()
+            .replace_all(text, "")
+            .into_owned()
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn test_csv_field_formula_injection() {
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1 tool"), "'+1 tool");
+        assert_eq!(csv_field("-fast"), "'-fast");
+        assert_eq!(csv_field("@user"), "'@user");
+        assert_eq!(csv_field("a=b"), "a=b");
+    }
+
+    #[test]
+    fn test_hyperlink() {
+        assert_eq!(
+            hyperlink("user/repo", "https://github.com/user/repo"),
+            "\x1b]8;;https://github.com/user/repo\x1b\\user/repo\x1b]8;;\x1b\\"
+        );
+        assert_eq!(hyperlink("user/repo", "javascript:alert(1)"), "user/repo");
+        assert_eq!(
+            hyperlink("user/repo", "https://x.dev/\x1b]8;;\x1b\\\x1b[2J"),
+            "user/repo"
+        );
+    }
+
+    #[test]
+    fn test_fit_columns() {
+        // Undetected width: the fixed caps
+        assert_eq!(fit_columns(60, 20, 30, None, None), (40, 15, 45));
+        assert_eq!(fit_columns(12, 4, 30, None, Some(0)), (12, 4, 0));
+
+        // Wide terminal: long names fit, the description takes the rest
+        assert_eq!(fit_columns(60, 20, 30, Some(200), None), (60, 15, 95));
+        assert_eq!(fit_columns(60, 20, 30, Some(200), Some(45)), (60, 15, 45));
+
+        // Narrow terminal: every column shrinks to fit
+        let (name, lang, desc) = fit_columns(60, 20, 30, Some(80), None);
+        assert!(30 + name + lang + desc <= 80);
+        assert_eq!((name, lang, desc), (19, 12, 19));
+        assert_eq!(fit_columns(60, 20, 30, Some(40), None), (8, 2, 0));
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap_text("a fast tool for the terminal", 10),
+            vec!["a fast", "tool for", "the", "terminal"]
+        );
+        assert_eq!(wrap_text("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(
+            wrap_text("日本語 テキスト", 6),
+            vec!["日本語", "テキス", "ト"]
+        );
+        assert!(wrap_text("   ", 10).is_empty());
+    }
+
+    #[test]
+    fn test_format_repo_motd_pads_by_display_width() {
+        let repo = Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "漢字/仓库".to_string(),
+            language: Some("Rust".to_string()),
+            url: "https://github.com/x/y".to_string(),
+            stars_total: Some(5),
+            ..Default::default()
+        };
+        let widths = ColumnWidths {
+            icon: 4,
+            name: 12,
+            lang: 6,
+            stars: 2,
+            badges: 0,
+            delta: 0,
+            desc: 0,
+        };
+
+        // Other tests toggle colors globally, so compare without escapes
+        let ansi = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+        let line = format_repo_motd(&repo, &widths, &RenderOptions::default());
+        let line = ansi.replace_all(&line, "");
+
+        // 9 cells of name + 3 of padding fill the 12-cell column
+        assert!(line.starts_with("[GH] 漢字/仓库    Rust   ★5"), "{line:?}");
+    }
+
+    #[test]
+    fn test_format_one() {
+        let mut repo = Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "rust-lang/rust".to_string(),
+            language: Some("Rust".to_string()),
+            description: Some("Empowering everyone to build reliable software".to_string()),
+            url: "https://github.com/rust-lang/rust".to_string(),
+            stars_today: Some(100),
+            ..Default::default()
+        };
+        let options = RenderOptions::default();
+        // Other tests force colors globally, so compare without escapes
+        let ansi = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+        let plain = |repo: &Repo| {
+            ansi.replace_all(&format_one(repo, &options), "")
+                .into_owned()
+        };
+        assert_eq!(
+            plain(&repo),
+            "🔥 Today's trending: rust-lang/rust (Rust) — Empowering everyone to build reliable soft..."
+        );
+
+        repo.language = None;
+        repo.description = None;
+        assert_eq!(plain(&repo), "🔥 Today's trending: rust-lang/rust");
+    }
+
+    #[test]
+    fn test_format_description_width() {
+        let repo = Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "rust-lang/rust".to_string(),
+            description: Some("Empowering everyone to build reliable software".to_string()),
+            url: "https://github.com/rust-lang/rust".to_string(),
+            ..Default::default()
+        };
+        let options = RenderOptions::default();
+
+        assert_eq!(
+            format_description(&repo, &options, 20),
+            "Empowering everyo..."
+        );
+        assert_eq!(
+            format_description(&repo, &options, 80),
+            "Empowering everyone to build reliable software"
+        );
+        assert_eq!(format_description(&repo, &options, 0), "");
+    }
+
+    #[test]
+    fn test_truncate_column() {
+        assert_eq!(truncate_column("rust-lang/rust", 20), "rust-lang/rust");
+        assert_eq!(truncate_column("rust-lang/rust", 8), "rust-l..");
+        // Wide characters are never split: a 2-cell char that doesn't fit is dropped
+        assert_eq!(truncate_column("漢字漢字漢字", 7), "漢字..");
+        assert_eq!(take_width("漢字漢字", 5), "漢字");
+
+        let repo = Repo {
+            description: Some("漢字漢字漢字漢字".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_description(&repo, &RenderOptions::default(), 10),
+            "漢字漢..."
+        );
+    }
+
+    #[test]
+    fn test_parse_json_field() {
+        assert_eq!(parse_json_field("url"), Ok("url".to_string()));
+        assert_eq!(parse_json_field("stars"), Ok("stars_total".to_string()));
+        assert!(parse_json_field("owner")
+            .unwrap_err()
+            .contains("unknown field 'owner'"));
+    }
+
+    #[test]
+    fn test_json_fields_projection() {
+        let repo = Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "user/repo".to_string(),
+            description: Some("secret".to_string()),
+            url: "https://github.com/user/repo".to_string(),
+            stars_total: Some(7),
+            stars_delta: Some(2),
+            downloads: Some(3),
+            ..Default::default()
+        };
+
+        // Every declared field really is a serialized key
+        let full = json!(repo);
+        for field in Repo::FIELDS {
+            assert!(full.get(field).is_some(), "{field} not serialized");
+        }
+        assert_eq!(full.as_object().unwrap().len(), Repo::FIELDS.len());
+
+        let fields = vec![
+            "name".to_string(),
+            "url".to_string(),
+            "stars_total".to_string(),
+        ];
+        let projected = format_json_fields(std::slice::from_ref(&repo), &fields);
+        let value: serde_json::Value = serde_json::from_str(&projected).unwrap();
+        assert_eq!(
+            value,
+            json!([{"name": "user/repo", "url": "https://github.com/user/repo", "stars_total": 7}])
+        );
+
+        // NDJSON lines are single-line objects with the same projection
+        let line = format_ndjson_line(&repo, Some(&fields));
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            line,
+            r#"{"name":"user/repo","url":"https://github.com/user/repo","stars_total":7}"#
+        );
+        let full_line: serde_json::Value =
+            serde_json::from_str(&format_ndjson_line(&repo, None)).unwrap();
+        assert_eq!(full_line, full);
+
+        // JSON Lines output is one such line per repo, without array brackets
+        let jsonl = format_jsonl(&[repo.clone(), repo], Some(&fields));
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| *l == line));
+        assert!(jsonl.ends_with("}\n") && !jsonl.starts_with('['));
+    }
+
+    #[test]
+    fn test_humanize_count() {
+        assert_eq!(humanize_count(0), "0");
+        assert_eq!(humanize_count(999), "999");
+        assert_eq!(humanize_count(1_000), "1.0k");
+        assert_eq!(humanize_count(1_049), "1.0k");
+        assert_eq!(humanize_count(1_050), "1.1k");
+        assert_eq!(humanize_count(90_000), "90.0k");
+        assert_eq!(humanize_count(999_949), "999.9k");
+        assert_eq!(humanize_count(999_999), "1.0M");
+        assert_eq!(humanize_count(1_000_000), "1.0M");
+        assert_eq!(humanize_count(1_500_000), "1.5M");
+    }
+
+    #[test]
+    fn test_format_stars() {
+        let mut repo = Repo {
+            stars_today: Some(12_345),
+            stars_total: Some(90_000),
+            ..Default::default()
+        };
+        let exact = RenderOptions {
+            star_separator: Some(','),
+            ..Default::default()
+        };
+        // The column is sized from this text, so long "today" counts fit
+        assert_eq!(format_stars(&repo, &exact), "★12,345 today");
+
+        repo.stars_today = None;
+        let humanized = RenderOptions {
+            humanize_stars: true,
+            ..Default::default()
+        };
+        assert_eq!(format_stars(&repo, &humanized), "★90.0k");
+
+        repo.stars_total = None;
+        assert_eq!(format_stars(&repo, &humanized), "");
+        repo.downloads = Some(1_500_000);
+        assert_eq!(format_stars(&repo, &humanized), "⇩1.5M");
+    }
+
+    #[test]
+    fn test_format_badges() {
+        let mut repo = Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "user/repo".to_string(),
+            stars_total: Some(90_000),
+            forks_count: Some(1_200),
+            open_issues: Some(42),
+            ..Default::default()
+        };
+        assert_eq!(format_badges(&repo), "★90.0k ⑂1.2k ⚠42");
+
+        repo.forks_count = None;
+        assert_eq!(format_badges(&repo), "★90.0k ⚠42");
+
+        repo.stars_total = None;
+        repo.open_issues = None;
+        assert_eq!(format_badges(&repo), "");
+
+        assert_eq!(format_stars_delta(&repo), "");
+        repo.stars_delta = Some(42);
+        assert_eq!(format_stars_delta(&repo), "(+42)");
+        repo.stars_delta = Some(-3);
+        assert_eq!(format_stars_delta(&repo), "(-3)");
+        repo.stars_delta = Some(0);
+        assert_eq!(format_stars_delta(&repo), "");
+    }
+
+    #[test]
+    fn test_aging_tier_boundaries() {
+        let gradient = AgingGradient::default();
+        let now = Utc::now();
+        let ago = |hours: i64| Some(now - Duration::hours(hours));
+
+        assert_eq!(gradient.tier(ago(0), now), AgeTier::Fresh);
+        assert_eq!(gradient.tier(ago(23), now), AgeTier::Fresh);
+        assert_eq!(gradient.tier(ago(24), now), AgeTier::Recent);
+        assert_eq!(gradient.tier(ago(47), now), AgeTier::Recent);
+        assert_eq!(gradient.tier(ago(48), now), AgeTier::Aging);
+        assert_eq!(gradient.tier(ago(7 * 24 - 1), now), AgeTier::Aging);
+        assert_eq!(gradient.tier(ago(7 * 24), now), AgeTier::Old);
+        assert_eq!(gradient.tier(None, now), AgeTier::Unknown);
+    }
+
+    #[test]
+    fn test_aging_custom_gradient() {
+        let gradient = AgingGradient { days: [3, 14, 30] };
+        let now = Utc::now();
+        let ago = |days: i64| Some(now - Duration::days(days));
+
+        assert_eq!(gradient.tier(ago(2), now), AgeTier::Fresh);
+        assert_eq!(gradient.tier(ago(3), now), AgeTier::Recent);
+        assert_eq!(gradient.tier(ago(14), now), AgeTier::Aging);
+        assert_eq!(gradient.tier(ago(30), now), AgeTier::Old);
+    }
+
+    #[test]
+    fn test_format_recency() {
+        let now = Utc::now();
+        let ago = |days: i64| Some(now - Duration::days(days));
+
+        assert_eq!(format_recency(ago(0), now), "today");
+        assert_eq!(format_recency(ago(1), now), "yesterday");
+        assert_eq!(format_recency(ago(3), now), "3d ago");
+        assert_eq!(format_recency(ago(14), now), "2w ago");
+        assert_eq!(format_recency(ago(90), now), "3mo ago");
+        assert_eq!(format_recency(None, now), "unknown");
+    }
+
+    #[test]
+    fn test_format_recency_future_timestamp() {
+        let now = Utc::now();
+        assert_eq!(format_recency(Some(now + Duration::hours(3)), now), "today");
+        assert_eq!(
+            format_recency(Some(now + Duration::days(400)), now),
+            "today"
+        );
+    }
+
+    #[test]
+    fn test_render_motd_dim_old() {
+        let repo = |hours: i64| Repo {
+            provider: "gitlab".to_string(),
+            icon: "[GL]".to_string(),
+            name: format!("user/repo-{hours}"),
+            language: Some("Rust".to_string()),
+            description: Some("A project".to_string()),
+            stars_total: Some(10),
+            last_activity: Some(Utc::now() - Duration::hours(hours)),
+            ..Default::default()
+        };
+        let repos = [repo(1), repo(30), repo(72), repo(500)];
+        let options = RenderOptions {
+            dim_old: true,
+            ..Default::default()
+        };
+        // Fading only restyles rows, the text stays the same
+        assert_eq!(
+            plain(&format_motd(&repos, &options)),
+            plain(&format_motd(&repos, &RenderOptions::default()))
+        );
+
+        let dimmed = |tier| fade("row".white(), tier).style.contains(Styles::Dimmed);
+        assert!(!dimmed(AgeTier::Fresh));
+        assert!(!dimmed(AgeTier::Recent));
+        assert!(dimmed(AgeTier::Aging));
+        assert!(dimmed(AgeTier::Old));
+        assert_eq!(
+            fade("row".white(), AgeTier::Old).fgcolor,
+            Some(colored::Color::BrightBlack)
+        );
+        assert!(!dimmed(AgeTier::Unknown));
+    }
+
+    #[test]
+    fn test_render_empty() {
+        let repos = vec![];
+        // This will print to stdout, but won't panic
+        render(&repos, OutputFormat::Motd, &RenderOptions::default());
+    }
+
+    #[test]
+    fn test_render_json() {
+        let repos = vec![Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "test/repo".to_string(),
+            language: Some("Rust".to_string()),
+            description: Some("Test repository".to_string()),
+            url: "https://github.com/test/repo".to_string(),
+            stars_today: Some(10),
+            stars_total: Some(100),
+            last_activity: Some(Utc::now()),
+            topics: vec!["rust".to_string(), "cli".to_string()],
+            ..Default::default()
+        }];
+
+        render(&repos, OutputFormat::Json, &RenderOptions::default());
+    }
+
+    #[test]
+    fn test_format_yaml() {
+        let repo = Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "test/repo".to_string(),
+            description: Some("Say \"hi\": fast".to_string()),
+            url: "https://github.com/test/repo".to_string(),
+            stars_today: Some(10),
+            last_activity: DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z")
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc)),
+            topics: vec!["rust".to_string(), "cli".to_string()],
+            ..Default::default()
+        };
+
+        let yaml = format_yaml(&[repo]);
+        let lines: Vec<&str> = yaml.lines().collect();
+        assert_eq!(lines[0], "- provider: \"github\"");
+        assert!(lines.contains(&"  name: \"test/repo\""));
+        assert!(lines.contains(&"  language: null"));
+        assert!(lines.contains(&"  description: \"Say \\\"hi\\\": fast\""));
+        assert!(lines.contains(&"  stars_today: 10"));
+        assert!(lines.contains(&"  last_activity: \"2024-05-01T10:00:00Z\""));
+        assert!(lines.contains(&"  topics:"));
+        assert!(lines.contains(&"    - \"rust\""));
+        assert!(lines.contains(&"  trending_windows: []"));
+
+        // Field order follows the JSON output
+        let position = |prefix: &str| lines.iter().position(|l| l.contains(prefix)).unwrap();
+        assert!(position("provider:") < position("name:"));
+        assert!(position("name:") < position("url:"));
+        assert_eq!(format_yaml(&[]), "[]\n");
+    }
+
+    #[test]
+    fn test_yaml_nested_and_empty_collections() {
+        let value = serde_json::json!({
+            "topics": [],
+            "trending_windows": ["daily", "weekly"],
+            "matrix": [["a", "b"], [], [{"k": 1, "empty": {}}]],
+            "meta": {"tags": [], "owner": {"name": "octo"}},
+        });
+        assert_eq!(
+            yaml_lines(&value),
+            vec![
+                "topics: []",
+                "trending_windows:",
+                "  - \"daily\"",
+                "  - \"weekly\"",
+                "matrix:",
+                "  - - \"a\"",
+                "    - \"b\"",
+                "  - []",
+                "  - - k: 1",
+                "      empty: {}",
+                "meta:",
+                "  tags: []",
+                "  owner:",
+                "    name: \"octo\"",
+            ]
+        );
+
+        let repo = Repo {
+            name: "test/repo".to_string(),
+            trending_windows: vec!["daily".to_string()],
+            ..Default::default()
+        };
+        let yaml = format_yaml(&[repo]);
+        assert!(yaml.contains("\n  topics: []\n"), "{yaml}");
+        assert!(
+            yaml.contains("\n  trending_windows:\n    - \"daily\"\n"),
+            "{yaml}"
+        );
+    }
+
+    #[test]
+    fn test_format_urls() {
+        let repo = |url: &str| Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "user/repo".to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        };
+        let repos = vec![
+            repo("https://github.com/a/b"),
+            repo("https://gitlab.com/c/d"),
+        ];
+
+        assert_eq!(
+            format_urls(&repos),
+            "https://github.com/a/b\nhttps://gitlab.com/c/d\n"
+        );
+        assert_eq!(format_urls(&[]), "");
+    }
+
+    #[test]
+    fn test_format_csv() {
+        let repos = vec![Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "user/repo".to_string(),
+            language: Some("Rust".to_string()),
+            description: Some("Fast, small".to_string()),
+            url: "https://github.com/user/repo".to_string(),
+            stars_today: Some(12),
+            ..Default::default()
+        }];
+
+        let csv = format_csv(&repos);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("provider,name,language,stars_total,stars_today,last_activity,url,description")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("github,user/repo,Rust,,12,,https://github.com/user/repo,\"Fast, small\"")
+        );
+        assert_eq!(lines.next(), None);
+
+        // Multi-line descriptions stay on one row; missing values are empty
+        let mut repo = repos[0].clone();
+        repo.description = Some("line one\nline \"two\"".to_string());
+        repo.language = None;
+        repo.stars_today = None;
+        repo.stars_total = Some(5);
+        assert_eq!(
+            format_csv(&[repo]).lines().nth(1),
+            Some(
+                "github,user/repo,,5,,,https://github.com/user/repo,\"line one line \"\"two\"\"\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_markdown() {
+        let now = Utc::now();
+        let mut repo = Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "user/repo".to_string(),
+            language: Some("Rust".to_string()),
+            description: Some("Fast | small, see [docs](https://x.dev)".to_string()),
+            url: "https://github.com/user/repo".to_string(),
+            stars_today: Some(1234),
+            last_activity: Some(now - Duration::days(3)),
+            ..Default::default()
+        };
+        let options = RenderOptions::default();
+
+        let table = format_markdown(std::slice::from_ref(&repo), &options, now);
+        let mut lines = table.lines();
+        assert_eq!(
+            lines.next(),
+            Some("| Repo | Lang | Stars | Updated | Description |")
+        );
+        assert_eq!(lines.next(), Some("|---|---|---|---|---|"));
+        assert_eq!(
+            lines.next(),
+            Some(
+                "| [user/repo](https://github.com/user/repo) | Rust | ★1234 today | 3d ago \
+                 | Fast \\| small, see docs |"
+            )
+        );
+        assert_eq!(lines.next(), None);
+
+        repo.language = None;
+        repo.description = None;
+        repo.stars_today = None;
+        repo.stars_total = Some(7);
+        repo.last_activity = None;
+        assert_eq!(
+            format_markdown(std::slice::from_ref(&repo), &options, now)
+                .lines()
+                .nth(2),
+            Some("| [user/repo](https://github.com/user/repo) | - | ★7 | unknown |  |")
+        );
+
+        repo.stars_total = None;
+        repo.downloads = Some(98_765);
+        assert_eq!(
+            format_markdown(std::slice::from_ref(&repo), &options, now)
+                .lines()
+                .nth(2),
+            Some("| [user/repo](https://github.com/user/repo) | - | ⇩98765 | unknown |  |")
+        );
+
+        // Hostile URLs: no link for other schemes, and no way out of the target
+        repo.url = "javascript:alert(1)".to_string();
+        assert_eq!(
+            format_markdown(std::slice::from_ref(&repo), &options, now)
+                .lines()
+                .nth(2),
+            Some("| user/repo | - | ⇩98765 | unknown |  |")
+        );
+        repo.url = "https://evil.example/a b)|c(d".to_string();
+        assert_eq!(
+            format_markdown(&[repo], &options, now).lines().nth(2),
+            Some(
+                "| [user/repo](https://evil.example/a%20b%29%7Cc%28d) | - | ⇩98765 | unknown |  |"
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_html() {
+        let now = Utc::now();
+        let repo = Repo {
+            provider: "gitea@git.example.com".to_string(),
+            icon: "[GE]@git.example.com".to_string(),
+            name: "user/<repo>".to_string(),
+            language: Some("C++".to_string()),
+            description: Some("Fast & \"safe\" <b>tools</b> isn't it".to_string()),
+            url: "https://git.example.com/user/repo?a=1&b=\"2\"".to_string(),
+            stars_total: Some(1234),
+            last_activity: Some(now - Duration::days(3)),
+            ..Default::default()
+        };
+
+        let html = format_html(&[repo], &RenderOptions::default(), now);
+        assert!(html.starts_with("<style>"));
+        assert!(html.contains(".trotd .provider-github"));
+        assert!(html.contains(
+            "<tr class=\"provider-gitea\"><td>[GE]@git.example.com</td>\
+             <td><a href=\"https://git.example.com/user/repo?a=1&amp;b=&quot;2&quot;\">\
+             user/&lt;repo&gt;</a></td><td>C++</td><td>★1234</td><td>3d ago</td>\
+             <td>Fast &amp; &quot;safe&quot; &lt;b&gt;tools&lt;/b&gt; isn&#39;t it</td></tr>"
+        ));
+        assert!(html.ends_with("</tbody>\n</table>\n"));
+    }
+
+    #[test]
+    fn test_format_html_skips_unsafe_links() {
+        let repo = Repo {
+            provider: "gitea@evil.example".to_string(),
+            icon: "[GE]".to_string(),
+            name: "user/repo".to_string(),
+            url: "javascript:alert(document.cookie)".to_string(),
+            ..Default::default()
+        };
+
+        let html = format_html(&[repo], &RenderOptions::default(), Utc::now());
+        assert!(!html.contains("<a "));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("<td>user/repo</td>"));
+    }
+
+    #[test]
+    fn test_machine_formats_no_ansi_when_forced() {
+        let repos = vec![Repo {
+            provider: "gitlab".to_string(),
+            icon: "[GL]".to_string(),
+            name: "test/repo".to_string(),
+            language: Some("Rust".to_string()),
+            description: Some("Test repository".to_string()),
+            url: "https://gitlab.com/test/repo".to_string(),
+            stars_total: Some(100),
+            last_activity: Some(Utc::now()),
+            from_cache: true,
+            ..Default::default()
+        }];
+
+        // Same effect as CLICOLOR_FORCE=1
+        colored::control::set_override(true);
+        let options = RenderOptions::default();
+        let now = Utc::now();
+        let outputs = [
+            format_json(&repos),
+            format_jsonl(&repos, None),
+            format_csv(&repos),
+            format_yaml(&repos),
+            format_urls(&repos),
+            format_markdown(&repos, &options, now),
+            format_html(&repos, &options, now),
+        ];
+        colored::control::unset_override();
+
+        for output in outputs {
+            assert!(!output.contains("\x1b["), "{output}");
+        }
+    }
+
+    #[test]
+    fn test_should_color_precedence() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| (*v).to_string())
+            }
+        };
+
+        // The flag beats every variable
+        assert!(should_color(
+            ColorChoice::Always,
+            env(&[("NO_COLOR", "1")]),
+            false
+        ));
+        assert!(!should_color(
+            ColorChoice::Never,
+            env(&[("CLICOLOR_FORCE", "1")]),
+            true
+        ));
+
+        // Auto: NO_COLOR > CLICOLOR_FORCE > CLICOLOR > TTY
+        let both = env(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]);
+        assert!(!should_color(ColorChoice::Auto, both, true));
+        assert!(should_color(
+            ColorChoice::Auto,
+            env(&[("CLICOLOR_FORCE", "1")]),
+            false
+        ));
+        assert!(!should_color(
+            ColorChoice::Auto,
+            env(&[("CLICOLOR_FORCE", "0")]),
+            false
+        ));
+        assert!(!should_color(
+            ColorChoice::Auto,
+            env(&[("CLICOLOR", "0")]),
+            true
+        ));
+        assert!(should_color(
+            ColorChoice::Auto,
+            env(&[("CLICOLOR", "1")]),
+            true
+        ));
+        assert!(should_color(
+            ColorChoice::Auto,
+            env(&[("NO_COLOR", "")]),
+            true
+        ));
+        assert!(!should_color(ColorChoice::Auto, env(&[]), false));
+    }
+
+    #[test]
+    fn test_render_motd() {
+        let repos = vec![
+            Repo {
+                provider: "github".to_string(),
+                icon: "[GH]".to_string(),
+                name: "rust-lang/rust".to_string(),
+                language: Some("Rust".to_string()),
+                description: Some(
+                    "Empowering everyone to build reliable and efficient software.".to_string(),
+                ),
+                url: "https://github.com/rust-lang/rust".to_string(),
+                stars_today: Some(50),
+                stars_total: Some(90000),
+                last_activity: Some(Utc::now()),
+                topics: vec!["rust".to_string(), "compiler".to_string()],
+                ..Default::default()
+            },
+            Repo {
+                provider: "gitlab".to_string(),
+                icon: "[GL]".to_string(),
+                name: "gitlab-org/gitlab".to_string(),
+                language: Some("Ruby".to_string()),
+                url: "https://gitlab.com/gitlab-org/gitlab".to_string(),
+                stars_total: Some(5000),
+                last_activity: Some(Utc::now() - Duration::days(3)),
+                topics: vec!["gitlab".to_string(), "ruby".to_string()],
+                ..Default::default()
+            },
+        ];
+
+        render(&repos, OutputFormat::Motd, &RenderOptions::default());
+    }
+
+    #[test]
+    fn test_render_motd_mark_cached() {
+        let cached = Repo {
+            provider: "gitea".to_string(),
+            icon: "[GE]".to_string(),
+            name: "gitea/tea".to_string(),
+            language: Some("Go".to_string()),
+            description: Some("Command line tool for Gitea".to_string()),
+            url: "https://gitea.com/gitea/tea".to_string(),
+            stars_total: Some(300),
+            last_activity: Some(Utc::now()),
+            from_cache: true,
+            ..Default::default()
+        };
+        let live = Repo {
+            from_cache: false,
+            ..cached.clone()
+        };
+        let repos = [cached, live];
+
+        let options = RenderOptions {
+            mark_cached: true,
+            ..RenderOptions::default()
+        };
+        let output = plain(&format_motd(&repos, &options));
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].starts_with("⟳ [GE] gitea/tea"), "{output}");
+        // Live rows keep the marker column blank so the rows stay aligned
+        assert!(lines[1].starts_with("  [GE] gitea/tea"), "{output}");
+
+        let output = plain(&format_motd(&repos, &RenderOptions::default()));
+        assert!(
+            output.lines().all(|line| line.starts_with("[GE]")),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn test_render_motd_page_size() {
+        let repo = Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "test/repo".to_string(),
+            language: Some("Rust".to_string()),
+            url: "https://github.com/test/repo".to_string(),
+            stars_today: Some(1),
+            stars_total: Some(10),
+            last_activity: Some(Utc::now()),
+            ..Default::default()
+        };
+        let repos = vec![repo.clone(), repo.clone(), repo];
+
+        let options = RenderOptions {
+            page_size: Some(1),
+            ..RenderOptions::default()
+        };
+        let output = plain(&format_motd(&repos, &options));
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2, "{output}");
+        assert!(lines[0].starts_with("[GH] test/repo"), "{output}");
+        assert_eq!(lines[1], "── 2 more ──");
+
+        // A page as large as the list shows everything without a marker
+        let options = RenderOptions {
+            page_size: Some(3),
+            ..RenderOptions::default()
+        };
+        let output = plain(&format_motd(&repos, &options));
+        assert_eq!(output.lines().count(), 3, "{output}");
+        assert!(!output.contains("more"), "{output}");
+    }
+
+    #[test]
+    fn test_colorize_icon_overrides() {
+        use colored::Color;
+
+        let colors = BTreeMap::from([
+            ("gitlab".to_string(), Color::Cyan),
+            ("gitlab@company".to_string(), Color::Yellow),
+        ]);
+        assert_eq!(
+            colorize_icon("github", "[GH]", &colors).fgcolor,
+            Some(Color::BrightMagenta)
+        );
+        assert_eq!(
+            colorize_icon("gitlab", "[GL]", &colors).fgcolor,
+            Some(Color::Cyan)
+        );
+        assert_eq!(
+            colorize_icon("gitlab@gitlab.gnome.org", "x", &colors).fgcolor,
+            Some(Color::Cyan)
+        );
+        assert_eq!(
+            colorize_icon("gitlab@company", "[CO]", &colors).fgcolor,
+            Some(Color::Yellow)
+        );
+    }
+
+    #[test]
+    fn test_render_legend() {
+        let repo = |provider: &str, icon: &str| Repo {
+            provider: provider.to_string(),
+            icon: icon.to_string(),
+            name: "test/repo".to_string(),
+            ..Default::default()
+        };
+        let repos = vec![
+            repo("github", "[GH]"),
+            repo("gitea", "[GE]"),
+            repo("github", "[GH]"),
+        ];
+
+        let legend = render_legend(&repos, &BTreeMap::new());
+        assert!(legend.contains("[GH]") && legend.contains("GitHub"));
+        assert!(legend.contains("[GE]") && legend.contains("Gitea"));
+        assert!(!legend.contains("GitLab"));
+        assert_eq!(legend.matches("GitHub").count(), 1);
+    }
+
+    #[test]
+    fn test_format_summary() {
+        let repo = |provider: &str, stars: Option<u64>| Repo {
+            provider: provider.to_string(),
+            name: "test/repo".to_string(),
+            stars_total: stars,
+            ..Default::default()
+        };
+        let repos = vec![
+            repo("github", Some(10_000)),
+            repo("gitlab", Some(2_000)),
+            repo("github", None),
+            repo("gitea", Some(340)),
+        ];
+
+        assert_eq!(
+            format_summary(&repos, Some(',')),
+            "4 repos · 2 GitHub, 1 GitLab, 1 Gitea · 12,340 total stars"
+        );
+        assert_eq!(
+            format_summary(&repos[3..], None),
+            "1 repo · 1 Gitea · 340 total stars"
+        );
+    }
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits(90000, None), "90000");
+        assert_eq!(group_digits(90000, Some(',')), "90,000");
+        assert_eq!(group_digits(999, Some(',')), "999");
+        assert_eq!(group_digits(1000, Some(' ')), "1 000");
+        assert_eq!(group_digits(1_234_567, Some('.')), "1.234.567");
+        assert_eq!(group_digits(0, Some(',')), "0");
+    }
+
+    #[test]
+    fn test_clean_description_markdown_links() {
+        // Full markdown links should be converted to just text
+        let desc = "Check out [README](https://example.com) for more info";
+        let cleaned = clean_description(desc, &RenderOptions::default());
+        assert_eq!(cleaned, "Check out README for more info");
+    }
+
+    #[test]
+    fn test_clean_description_multiple_links() {
+        let desc = "See [docs](url1) and [API](url2) for details";
+        let cleaned = clean_description(desc, &RenderOptions::default());
+        assert_eq!(cleaned, "See docs and API for details");
+    }
+
+    #[test]
+    fn test_clean_description_bold_italic() {
+        let desc = "This is **bold** and __also bold__ text";
+        let cleaned = clean_description(desc, &RenderOptions::default());
+        assert_eq!(cleaned, "This is bold and also bold text");
+    }
+
+    #[test]
+    fn test_clean_description_images() {
+        let desc = "Project logo ![logo](image.png) here";
+        let cleaned = clean_description(desc, &RenderOptions::default());
+        assert_eq!(cleaned, "Project logo here");
+    }
+
+    #[test]
+    fn test_strip_emoji_prefix() {
+        assert_eq!(strip_emoji("🚀 Fast HTTP server"), "Fast HTTP server");
+        assert_eq!(strip_emoji("🔥🔥 Blazing ⚡ fast"), "Blazing fast");
+    }
+
+    #[test]
+    fn test_strip_emoji_keeps_text() {
+        assert_eq!(strip_emoji("Plain description"), "Plain description");
+        assert_eq!(strip_emoji("Überschall café 中文"), "Überschall café 中文");
+        assert_eq!(strip_emoji("波〰線 テキスト"), "波〰線 テキスト");
+        assert_eq!(strip_emoji("👨‍💻 Dev tools ❤️"), "Dev tools");
+    }
+
+    #[test]
+    fn test_strip_emoji_descriptions() {
+        let cases = [
+            (
+                "⚡️ Lightning fast web framework 🦀",
+                "Lightning fast web framework",
+            ),
+            ("▶️ Video player ◾ minimal", "Video player minimal"),
+            ("‼️ Breaking: v2 released ⤴️", "Breaking: v2 released"),
+            ("🇯🇵 日本語 docs 〽️", "日本語 docs"),
+            ("1️⃣ One-click deploy", "1 One-click deploy"),
+        ];
+        for (description, expected) in cases {
+            assert_eq!(strip_emoji(description), expected);
+        }
+    }
+
+    #[test]
+    fn test_render_motd_strip_emoji() {
+        let repos = vec![Repo {
+            provider: "github".to_string(),
+            icon: "[GH]".to_string(),
+            name: "emoji/repo 🦀".to_string(),
+            description: Some("🚀 **Fast** and ✨ shiny".to_string()),
+            url: "https://github.com/emoji/repo".to_string(),
+            stars_total: Some(1),
+            ..Default::default()
+        }];
+
+        let options = RenderOptions {
+            strip_emoji: true,
+            ..RenderOptions::default()
+        };
+        let output = plain(&format_motd(&repos, &options));
+        assert!(output.starts_with("[GH] emoji/repo "), "{output}");
+        assert!(output.ends_with(" Fast and shiny"), "{output}");
+        assert!(!output.contains(['🦀', '🚀', '✨']), "{output}");
+
+        let output = plain(&format_motd(&repos, &RenderOptions::default()));
+        assert!(output.contains("emoji/repo 🦀"), "{output}");
+        assert!(output.ends_with(" 🚀 Fast and ✨ shiny"), "{output}");
     }
 
     #[test]
@@ -0,0 +1,207 @@
+//! Unicode script detection for the script whitelist filter
+//!
+//! A compact table of the major scripts' code point ranges. Non-letters
+//! (digits, punctuation, symbols, emoji, combining marks) and the micro sign
+//! count as `Common`, and letters outside every listed range as `Other`.
+
+/// Writing system of a character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    Latin,
+    Common,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Thai,
+    Hangul,
+    Hiragana,
+    Katakana,
+    Han,
+    Other,
+}
+
+/// Letter ranges per script, checked in order
+const RANGES: &[(Script, char, char)] = &[
+    (Script::Latin, 'A', 'Z'),
+    (Script::Latin, 'a', 'z'),
+    (Script::Latin, '\u{00AA}', '\u{00AA}'),
+    (Script::Latin, '\u{00BA}', '\u{00BA}'),
+    (Script::Common, '\u{00B5}', '\u{00B5}'),
+    (Script::Latin, '\u{00C0}', '\u{024F}'),
+    (Script::Latin, '\u{0250}', '\u{02AF}'),
+    (Script::Latin, '\u{1E00}', '\u{1EFF}'),
+    (Script::Latin, '\u{2C60}', '\u{2C7F}'),
+    (Script::Latin, '\u{A720}', '\u{A7FF}'),
+    (Script::Latin, '\u{FF21}', '\u{FF3A}'),
+    (Script::Latin, '\u{FF41}', '\u{FF5A}'),
+    (Script::Greek, '\u{0370}', '\u{03FF}'),
+    (Script::Greek, '\u{1F00}', '\u{1FFF}'),
+    (Script::Cyrillic, '\u{0400}', '\u{052F}'),
+    (Script::Cyrillic, '\u{1C80}', '\u{1C8F}'),
+    (Script::Cyrillic, '\u{2DE0}', '\u{2DFF}'),
+    (Script::Cyrillic, '\u{A640}', '\u{A69F}'),
+    (Script::Armenian, '\u{0530}', '\u{058F}'),
+    (Script::Hebrew, '\u{0590}', '\u{05FF}'),
+    (Script::Arabic, '\u{0600}', '\u{06FF}'),
+    (Script::Arabic, '\u{0750}', '\u{077F}'),
+    (Script::Arabic, '\u{08A0}', '\u{08FF}'),
+    (Script::Arabic, '\u{FB50}', '\u{FDFF}'),
+    (Script::Arabic, '\u{FE70}', '\u{FEFF}'),
+    (Script::Devanagari, '\u{0900}', '\u{097F}'),
+    (Script::Thai, '\u{0E00}', '\u{0E7F}'),
+    (Script::Hangul, '\u{1100}', '\u{11FF}'),
+    (Script::Hangul, '\u{3130}', '\u{318F}'),
+    (Script::Hangul, '\u{AC00}', '\u{D7AF}'),
+    (Script::Hiragana, '\u{3040}', '\u{309F}'),
+    (Script::Katakana, '\u{30A0}', '\u{30FF}'),
+    (Script::Katakana, '\u{31F0}', '\u{31FF}'),
+    (Script::Katakana, '\u{FF66}', '\u{FF9F}'),
+    (Script::Han, '\u{2E80}', '\u{2FDF}'),
+    (Script::Han, '\u{3005}', '\u{3007}'),
+    (Script::Han, '\u{3400}', '\u{4DBF}'),
+    (Script::Han, '\u{4E00}', '\u{9FFF}'),
+    (Script::Han, '\u{F900}', '\u{FAFF}'),
+    (Script::Han, '\u{20000}', '\u{2FA1F}'),
+];
+
+/// Names accepted by `Script::from_name`
+const NAMES: &[(&str, Script)] = &[
+    ("latin", Script::Latin),
+    ("common", Script::Common),
+    ("greek", Script::Greek),
+    ("cyrillic", Script::Cyrillic),
+    ("armenian", Script::Armenian),
+    ("hebrew", Script::Hebrew),
+    ("arabic", Script::Arabic),
+    ("devanagari", Script::Devanagari),
+    ("thai", Script::Thai),
+    ("hangul", Script::Hangul),
+    ("hiragana", Script::Hiragana),
+    ("katakana", Script::Katakana),
+    ("han", Script::Han),
+    ("other", Script::Other),
+];
+
+impl Script {
+    /// Script of a single character
+    pub fn of(c: char) -> Self {
+        RANGES
+            .iter()
+            .find(|(_, start, end)| (*start..=*end).contains(&c))
+            .map_or_else(
+                || {
+                    if c.is_alphabetic() {
+                        Self::Other
+                    } else {
+                        Self::Common
+                    }
+                },
+                |(script, _, _)| *script,
+            )
+    }
+
+    /// Parse a script name such as `Han` or `cyrillic` (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        NAMES
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name))
+            .map(|(_, script)| *script)
+    }
+
+    /// All names `from_name` accepts, lowercase
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        NAMES.iter().map(|(name, _)| *name)
+    }
+}
+
+/// Whitelist of scripts: always Latin and Common, plus any extras
+#[derive(Debug, Clone)]
+pub struct ScriptFilter {
+    allowed: Vec<Script>,
+}
+
+impl ScriptFilter {
+    /// Create a filter allowing Latin, Common, and the named extra scripts
+    ///
+    /// Unknown names are ignored; `Config::validate` rejects them up front.
+    pub fn new(extra: &[String]) -> Self {
+        let mut allowed = vec![Script::Latin, Script::Common];
+        allowed.extend(extra.iter().filter_map(|name| Script::from_name(name)));
+        Self { allowed }
+    }
+
+    /// Whether every character of `text` is in an allowed script
+    ///
+    /// Stray Greek letters (`λ-calculus`, `Δt`) are common symbols in Latin
+    /// text, so disallowed Greek is tolerated while it's outnumbered by letters
+    /// of allowed scripts.
+    pub fn allows(&self, text: &str) -> bool {
+        let mut allowed_letters = 0;
+        let mut greek_letters = 0;
+        for script in text.chars().map(Script::of) {
+            if self.allowed.contains(&script) {
+                if script != Script::Common {
+                    allowed_letters += 1;
+                }
+            } else if script == Script::Greek {
+                greek_letters += 1;
+            } else {
+                return false;
+            }
+        }
+        greek_letters == 0 || greek_letters < allowed_letters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_of() {
+        assert_eq!(Script::of('a'), Script::Latin);
+        assert_eq!(Script::of('é'), Script::Latin);
+        assert_eq!(Script::of('ü'), Script::Latin);
+        assert_eq!(Script::of('7'), Script::Common);
+        assert_eq!(Script::of('-'), Script::Common);
+        assert_eq!(Script::of('🚀'), Script::Common);
+        assert_eq!(Script::of('\u{0301}'), Script::Common);
+        assert_eq!(Script::of('д'), Script::Cyrillic);
+        assert_eq!(Script::of('中'), Script::Han);
+        assert_eq!(Script::of('か'), Script::Hiragana);
+        assert_eq!(Script::of('한'), Script::Hangul);
+        assert_eq!(Script::of('µ'), Script::Common);
+        assert_eq!(Script::of('º'), Script::Latin);
+        assert_eq!(Script::of('λ'), Script::Greek);
+    }
+
+    #[test]
+    fn test_script_from_name() {
+        assert_eq!(Script::from_name("Han"), Some(Script::Han));
+        assert_eq!(Script::from_name(" cyrillic "), Some(Script::Cyrillic));
+        assert_eq!(Script::from_name("klingon"), None);
+        assert!(Script::names().all(|name| Script::from_name(name).is_some()));
+    }
+
+    #[test]
+    fn test_script_filter() {
+        let latin = ScriptFilter::new(&[]);
+        assert!(latin.allows("café-über 🚀 v2.0"));
+        assert!(!latin.allows("awesome-中文"));
+        assert!(!latin.allows("привет"));
+        assert!(latin.allows("5µs latency"));
+        assert!(latin.allows("λ-calculus interpreter"));
+        assert!(!latin.allows("Καλημέρα κόσμε"));
+
+        let greek = ScriptFilter::new(&["greek".to_string()]);
+        assert!(greek.allows("Καλημέρα κόσμε"));
+
+        let cjk = ScriptFilter::new(&["Han".to_string(), "hiragana".to_string()]);
+        assert!(cjk.allows("awesome-中文"));
+        assert!(!cjk.allows("привет"));
+    }
+}